@@ -0,0 +1,40 @@
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+use world_sim_core::Position;
+
+/// Why a simulation command could not be carried out
+#[derive(Debug, Clone)]
+pub enum CommandError {
+    /// The feature is not available (e.g. no database for snapshots)
+    Unavailable(String),
+    /// The referenced snapshot does not exist
+    NotFound,
+    Failed(String),
+}
+
+/// Commands the Admin API forwards to the simulation loop
+#[derive(Debug)]
+pub enum SimCommand {
+    Pause,
+    Resume,
+    /// Replace the observer positions used for agent level of detail
+    SetFocus {
+        observers: Vec<Position>,
+    },
+    CreateSnapshot {
+        name: String,
+        reply: oneshot::Sender<Result<Uuid, CommandError>>,
+    },
+    RestoreSnapshot {
+        id: Uuid,
+        reply: oneshot::Sender<Result<(), CommandError>>,
+    },
+}
+
+pub type SimCommandSender = mpsc::UnboundedSender<SimCommand>;
+pub type SimCommandReceiver = mpsc::UnboundedReceiver<SimCommand>;
+
+/// Create the channel connecting the Admin API to the simulation loop
+pub fn command_channel() -> (SimCommandSender, SimCommandReceiver) {
+    mpsc::unbounded_channel()
+}
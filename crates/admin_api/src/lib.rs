@@ -2,6 +2,11 @@
 mod routes;
 mod handlers;
 mod server;
+mod openapi;
+mod control;
+pub mod types;
 
+pub use openapi::{openapi, ApiDoc};
+pub use control::{command_channel, CommandError, SimCommand, SimCommandReceiver, SimCommandSender};
 pub use server::{AdminApiServer, SimulationMetrics, WorldState, AgentState, ResourceState, MarketState, CurrencyInfo, BuildingState};
 
@@ -1,155 +1,764 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
     Json,
 };
-use serde::Deserialize;
+use futures_util::stream::{self, Stream};
+use std::convert::Infallible;
 use std::sync::Arc;
+use tokio::sync::{broadcast, oneshot};
+use uuid::Uuid;
+use world_sim_core::ChunkCoord;
+use world_sim_event_bus::Webhook;
+use world_sim_meta::EventOverrides;
+use world_sim_world::BuildingOwner;
 
-use crate::server::ApiState;
+use crate::control::{CommandError, SimCommand};
+use crate::server::{AgentState, ApiState, WorldState};
+use crate::types::*;
 
 /// Health check endpoint
+#[utoipa::path(get, path = "/health", responses((status = 200, description = "Server is running", body = String)))]
 pub async fn health_check() -> &'static str {
     "OK"
 }
 
 /// Get event history
-#[derive(Deserialize)]
-pub struct HistoryQuery {
-    pub event_type: Option<String>,
-    pub limit: Option<i64>,
-}
-
+#[utoipa::path(
+    get,
+    path = "/api/history",
+    params(HistoryQuery),
+    responses((status = 200, body = HistoryResponse))
+)]
 pub async fn get_event_history(
     State(state): State<Arc<ApiState>>,
     Query(query): Query<HistoryQuery>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<HistoryResponse>, StatusCode> {
     if let Some(db) = &state.database {
         let limit = query.limit.unwrap_or(100);
         let events = db
             .query_events(query.event_type.as_deref(), limit)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-        
-        Ok(Json(serde_json::json!({ "events": events })))
+
+        Ok(Json(HistoryResponse { events, message: None }))
     } else {
         // No database - return empty with message
-        Ok(Json(serde_json::json!({ 
-            "events": [],
-            "message": "Database not configured. Set DATABASE_URL to enable event history."
-        })))
+        Ok(Json(HistoryResponse {
+            events: Vec::new(),
+            message: Some("Database not configured. Set DATABASE_URL to enable event history.".to_string()),
+        }))
     }
 }
 
-/// Inject a custom event (Dungeon Master control)
-#[derive(Deserialize)]
-pub struct InjectEventRequest {
-    pub event_type: String,
-    pub payload: serde_json::Value,
+/// Stream live events as Server-Sent Events (one JSON `EventEnvelope` per message)
+#[utoipa::path(
+    get,
+    path = "/api/events/stream",
+    params(EventStreamQuery),
+    responses((status = 200, description = "text/event-stream of event envelopes"))
+)]
+pub async fn stream_events(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<EventStreamQuery>,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let receiver = state.event_stream.subscribe();
+
+    let events = stream::unfold((receiver, query.event_type), |(mut receiver, filter)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(envelope) => {
+                    if filter.as_ref().is_some_and(|f| *f != envelope.event_type) {
+                        continue;
+                    }
+                    let event = SseEvent::default()
+                        .event(envelope.event_type.clone())
+                        .json_data(&envelope)
+                        .unwrap_or_default();
+                    return Some((Ok(event), (receiver, filter)));
+                }
+                // Slow client - skip what was missed
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
 }
 
+/// Inject a custom event (Dungeon Master control)
+#[utoipa::path(
+    post,
+    path = "/api/dm/inject_event",
+    request_body = InjectEventRequest,
+    responses((status = 200, body = InjectEventResponse))
+)]
 pub async fn inject_event(
     State(state): State<Arc<ApiState>>,
     Json(request): Json<InjectEventRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<InjectEventResponse>, StatusCode> {
     // Create event envelope
     let envelope = world_sim_event_bus::EventEnvelope::new(
         request.event_type,
         "admin_api".to_string(),
         request.payload,
     );
-    
+
     // Publish to event bus
     state.event_bus.publish_envelope(envelope.clone()).await;
-    
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "event_id": envelope.id
-    })))
+
+    Ok(Json(InjectEventResponse {
+        success: true,
+        event_id: envelope.id,
+    }))
 }
 
-/// Add a false memory to an agent
-#[derive(Deserialize)]
-pub struct AddMemoryRequest {
-    pub fact: String,
-    #[allow(dead_code)]
-    pub source: Option<String>,
+/// List the Dungeon Master's story event library
+#[utoipa::path(
+    get,
+    path = "/api/dm/events",
+    responses((status = 200, body = DmEventsResponse), (status = 503, description = "Dungeon Master not attached"))
+)]
+pub async fn list_dm_events(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<DmEventsResponse>, StatusCode> {
+    let dm = state.dungeon_master.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(DmEventsResponse {
+        events: dm.list_story_events(),
+    }))
+}
+
+/// Trigger a story event by id
+#[utoipa::path(
+    post,
+    path = "/api/dm/events/{id}/trigger",
+    params(("id" = String, Path, description = "Story event id")),
+    request_body(content = Option<TriggerDmEventRequest>),
+    responses(
+        (status = 200, body = TriggerDmEventResponse),
+        (status = 400, description = "Non-finite center, or non-finite or negative radius or severity"),
+        (status = 404, description = "Unknown story event id"),
+        (status = 503, description = "Dungeon Master not attached")
+    )
+)]
+pub async fn trigger_dm_event(
+    State(state): State<Arc<ApiState>>,
+    Path(event_id): Path<String>,
+    request: Option<Json<TriggerDmEventRequest>>,
+) -> Result<Json<TriggerDmEventResponse>, StatusCode> {
+    let dm = state.dungeon_master.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let Json(request) = request.unwrap_or_default();
+    let overrides = EventOverrides {
+        center: request.center,
+        radius: request.radius,
+        severity: request.severity,
+    };
+    if !overrides.is_valid() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let event = dm
+        .trigger_event(&event_id, &overrides)
+        .await
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(TriggerDmEventResponse {
+        success: true,
+        event,
+        overrides,
+    }))
+}
+
+/// Get the Dungeon Master's boredom score, settings and world metrics
+#[utoipa::path(
+    get,
+    path = "/api/dm/status",
+    responses((status = 200, body = DmStatusResponse), (status = 503, description = "Dungeon Master not attached"))
+)]
+pub async fn get_dm_status(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<DmStatusResponse>, StatusCode> {
+    let dm = state.dungeon_master.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let settings = dm.get_settings();
+    Ok(Json(DmStatusResponse {
+        enabled: settings.enabled,
+        boredom_threshold: settings.boredom_threshold,
+        boredom: dm.calculate_boredom(),
+        metrics: dm.get_metrics(),
+    }))
+}
+
+/// Update Dungeon Master settings
+#[utoipa::path(
+    post,
+    path = "/api/dm/settings",
+    request_body = UpdateDmSettingsRequest,
+    responses(
+        (status = 200, body = DmSettingsResponse),
+        (status = 400, description = "boredom_threshold outside 0..=1"),
+        (status = 503, description = "Dungeon Master not attached")
+    )
+)]
+pub async fn update_dm_settings(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<UpdateDmSettingsRequest>,
+) -> Result<Json<DmSettingsResponse>, StatusCode> {
+    let dm = state.dungeon_master.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    if let Some(threshold) = request.boredom_threshold {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        dm.set_boredom_threshold(threshold);
+    }
+    if let Some(enabled) = request.enabled {
+        dm.set_enabled(enabled);
+    }
+
+    Ok(Json(DmSettingsResponse {
+        success: true,
+        settings: dm.get_settings(),
+    }))
+}
+
+/// Place a new building
+#[utoipa::path(
+    post,
+    path = "/api/buildings",
+    request_body = PlaceBuildingRequest,
+    responses((status = 200, body = PlaceBuildingResponse), (status = 503, description = "Buildings not attached"))
+)]
+pub async fn place_building(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<PlaceBuildingRequest>,
+) -> Result<Json<PlaceBuildingResponse>, StatusCode> {
+    let buildings = state.buildings.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    let mut buildings = buildings.write();
+    let building = buildings.new_building(
+        request.building_type,
+        request.position,
+        request
+            .name
+            .unwrap_or_else(|| format!("{:?} (Admin)", request.building_type)),
+        request.owner.unwrap_or(BuildingOwner::Public),
+    );
+    let detail = BuildingDetail::from_building(&building);
+    let building_id = buildings.add_building(building);
+    drop(buildings);
+
+    tracing::info!("🏗️ Admin placed building {}", building_id);
+
+    Ok(Json(PlaceBuildingResponse {
+        success: true,
+        building_id,
+        detail,
+    }))
+}
+
+/// Get full building detail
+#[utoipa::path(
+    get,
+    path = "/api/buildings/{id}",
+    params(("id" = Uuid, Path, description = "Building id")),
+    responses((status = 200, body = BuildingDetail), (status = 404, description = "Unknown building id"))
+)]
+pub async fn get_building(
+    State(state): State<Arc<ApiState>>,
+    Path(building_id): Path<Uuid>,
+) -> Result<Json<BuildingDetail>, StatusCode> {
+    let buildings = state.buildings.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let buildings = buildings.read();
+    let building = buildings.get_building(building_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(BuildingDetail::from_building(building)))
+}
+
+/// Add gold to a building's construction fund
+#[utoipa::path(
+    post,
+    path = "/api/buildings/{id}/fund",
+    params(("id" = Uuid, Path, description = "Building id")),
+    request_body = FundBuildingRequest,
+    responses(
+        (status = 200, body = FundBuildingResponse),
+        (status = 400, description = "Amount is not positive"),
+        (status = 404, description = "Unknown building id")
+    )
+)]
+pub async fn fund_building(
+    State(state): State<Arc<ApiState>>,
+    Path(building_id): Path<Uuid>,
+    Json(request): Json<FundBuildingRequest>,
+) -> Result<Json<FundBuildingResponse>, StatusCode> {
+    let buildings = state.buildings.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    if !request.amount.is_finite() || request.amount <= 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut buildings = buildings.write();
+    let building = buildings.get_building_mut(building_id).ok_or(StatusCode::NOT_FOUND)?;
+    building.add_construction_fund(request.amount);
+
+    Ok(Json(FundBuildingResponse {
+        success: true,
+        construction_fund: building.construction_fund,
+    }))
+}
+
+/// Mark a building's construction as complete
+#[utoipa::path(
+    post,
+    path = "/api/buildings/{id}/complete",
+    params(("id" = Uuid, Path, description = "Building id")),
+    responses((status = 200, body = CompleteBuildingResponse), (status = 404, description = "Unknown building id"))
+)]
+pub async fn complete_building(
+    State(state): State<Arc<ApiState>>,
+    Path(building_id): Path<Uuid>,
+) -> Result<Json<CompleteBuildingResponse>, StatusCode> {
+    let buildings = state.buildings.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    let mut buildings = buildings.write();
+    let building = buildings.get_building_mut(building_id).ok_or(StatusCode::NOT_FOUND)?;
+    building.complete_construction();
+
+    Ok(Json(CompleteBuildingResponse {
+        success: true,
+        detail: BuildingDetail::from_building(building),
+    }))
+}
+
+/// Damage a building (destroyed buildings are removed)
+#[utoipa::path(
+    post,
+    path = "/api/buildings/{id}/damage",
+    params(("id" = Uuid, Path, description = "Building id")),
+    request_body = DamageBuildingRequest,
+    responses(
+        (status = 200, body = DamageBuildingResponse),
+        (status = 400, description = "Amount is negative"),
+        (status = 404, description = "Unknown building id")
+    )
+)]
+pub async fn damage_building(
+    State(state): State<Arc<ApiState>>,
+    Path(building_id): Path<Uuid>,
+    Json(request): Json<DamageBuildingRequest>,
+) -> Result<Json<DamageBuildingResponse>, StatusCode> {
+    let buildings = state.buildings.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    if !request.amount.is_finite() || request.amount < 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut buildings = buildings.write();
+    let building = buildings.get_building_mut(building_id).ok_or(StatusCode::NOT_FOUND)?;
+    building.damage(request.amount);
+    let health = building.health;
+    let destroyed = building.is_destroyed();
+
+    if destroyed {
+        buildings.remove_building(building_id);
+    }
+
+    Ok(Json(DamageBuildingResponse {
+        success: true,
+        health,
+        destroyed,
+    }))
+}
+
+/// Demolish a building
+#[utoipa::path(
+    delete,
+    path = "/api/buildings/{id}",
+    params(("id" = Uuid, Path, description = "Building id")),
+    responses((status = 200, body = DemolishBuildingResponse), (status = 404, description = "Unknown building id"))
+)]
+pub async fn demolish_building(
+    State(state): State<Arc<ApiState>>,
+    Path(building_id): Path<Uuid>,
+) -> Result<Json<DemolishBuildingResponse>, StatusCode> {
+    let buildings = state.buildings.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let building = buildings
+        .write()
+        .remove_building(building_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    tracing::info!("💥 Admin demolished building {} ({})", building.name, building.id);
+
+    Ok(Json(DemolishBuildingResponse {
+        success: true,
+        building_id: building.id,
+    }))
 }
 
+/// Add a false memory to an agent
+#[utoipa::path(
+    post,
+    path = "/api/agent/{id}/add_memory",
+    params(("id" = String, Path, description = "Agent id")),
+    request_body = AddMemoryRequest,
+    responses((status = 200, body = AddMemoryResponse))
+)]
 pub async fn add_agent_memory(
     Path(agent_id): Path<String>,
     Json(request): Json<AddMemoryRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<AddMemoryResponse>, StatusCode> {
     // TODO: Integrate with actual agent memory system
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "agent_id": agent_id,
-        "memory": request.fact
-    })))
+    Ok(Json(AddMemoryResponse {
+        success: true,
+        agent_id,
+        memory: request.fact,
+    }))
 }
 
 /// Get agent information
+#[utoipa::path(
+    get,
+    path = "/api/agent/{id}",
+    params(("id" = String, Path, description = "Agent id (bare UUID or `AgentId(uuid)`)")),
+    responses((status = 200, body = AgentState), (status = 404, description = "Unknown agent id"))
+)]
 pub async fn get_agent_info(
+    State(state): State<Arc<ApiState>>,
     Path(agent_id): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    // TODO: Integrate with actual agent system
-    Ok(Json(serde_json::json!({
-        "agent_id": agent_id,
-        "status": "placeholder"
-    })))
+) -> Result<Json<AgentState>, StatusCode> {
+    let debug_id = format!("AgentId({})", agent_id);
+    let world_state = state.world_state.read();
+    world_state
+        .agents
+        .iter()
+        .find(|a| a.id == agent_id || a.id == debug_id)
+        .cloned()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// List all living agents
+#[utoipa::path(get, path = "/api/agents", responses((status = 200, body = AgentsResponse)))]
+pub async fn list_agents(
+    State(state): State<Arc<ApiState>>,
+) -> Json<AgentsResponse> {
+    Json(AgentsResponse {
+        agents: state.world_state.read().agents.clone(),
+    })
+}
+
+/// List markets with their order books
+#[utoipa::path(
+    get,
+    path = "/api/markets",
+    responses((status = 200, body = MarketsResponse), (status = 503, description = "Markets not attached"))
+)]
+pub async fn list_markets(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<MarketsResponse>, StatusCode> {
+    let markets = state.markets.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let mut markets: Vec<_> = markets.read().get_all_markets().into_iter().cloned().collect();
+    markets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(MarketsResponse { markets }))
+}
+
+/// Map a simulation command failure to an HTTP status
+fn command_status(error: CommandError) -> StatusCode {
+    match error {
+        CommandError::Unavailable(reason) => {
+            tracing::warn!("Admin command unavailable: {}", reason);
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        CommandError::NotFound => StatusCode::NOT_FOUND,
+        CommandError::Failed(reason) => {
+            tracing::warn!("Admin command failed: {}", reason);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Send a command to the simulation loop
+fn send_command(state: &ApiState, command: SimCommand) -> Result<(), StatusCode> {
+    let commands = state.commands.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    commands.send(command).map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// Pause the simulation loop
+#[utoipa::path(
+    post,
+    path = "/api/sim/pause",
+    responses((status = 200, body = SimControlResponse), (status = 503, description = "Simulation control not attached"))
+)]
+pub async fn pause_simulation(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<SimControlResponse>, StatusCode> {
+    send_command(&state, SimCommand::Pause)?;
+    Ok(Json(SimControlResponse { success: true, paused: true }))
+}
+
+/// Resume the simulation loop
+#[utoipa::path(
+    post,
+    path = "/api/sim/resume",
+    responses((status = 200, body = SimControlResponse), (status = 503, description = "Simulation control not attached"))
+)]
+pub async fn resume_simulation(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<SimControlResponse>, StatusCode> {
+    send_command(&state, SimCommand::Resume)?;
+    Ok(Json(SimControlResponse { success: true, paused: false }))
+}
+
+/// Set where observers are, for agent level of detail
+#[utoipa::path(
+    post,
+    path = "/api/sim/focus",
+    request_body = SetFocusRequest,
+    responses((status = 200, body = SetFocusResponse), (status = 503, description = "Simulation control not attached"))
+)]
+pub async fn set_focus(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetFocusRequest>,
+) -> Result<Json<SetFocusResponse>, StatusCode> {
+    let observers = request.observers.len();
+    send_command(&state, SimCommand::SetFocus { observers: request.observers })?;
+    Ok(Json(SetFocusResponse { success: true, observers }))
+}
+
+/// List registered webhooks
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    responses((status = 200, body = WebhooksResponse), (status = 503, description = "Webhooks not configured"))
+)]
+pub async fn list_webhooks(State(state): State<Arc<ApiState>>) -> Result<Json<WebhooksResponse>, StatusCode> {
+    let webhooks = state.webhooks.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(WebhooksResponse { webhooks: webhooks.list() }))
+}
+
+/// Register a webhook
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    request_body = RegisterWebhookRequest,
+    responses((status = 200, body = RegisterWebhookResponse), (status = 400, description = "URL is not http(s)"))
+)]
+pub async fn register_webhook(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<RegisterWebhookRequest>,
+) -> Result<Json<RegisterWebhookResponse>, StatusCode> {
+    let webhooks = state.webhooks.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    if !request.url.starts_with("http://") && !request.url.starts_with("https://") {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let webhook = Webhook::new(request.url, request.event_types, request.secret);
+    webhooks.register(webhook.clone());
+
+    Ok(Json(RegisterWebhookResponse { success: true, webhook }))
+}
+
+/// Remove a webhook
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    params(("id" = Uuid, Path, description = "Webhook id")),
+    responses((status = 200, body = DeleteWebhookResponse), (status = 404, description = "Unknown webhook id"))
+)]
+pub async fn delete_webhook(
+    State(state): State<Arc<ApiState>>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<DeleteWebhookResponse>, StatusCode> {
+    let webhooks = state.webhooks.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    webhooks.unregister(webhook_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(DeleteWebhookResponse { success: true, webhook_id }))
+}
+
+/// List deliveries that exhausted their retries
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/dead_letters",
+    responses((status = 200, body = DeadLettersResponse), (status = 503, description = "Webhooks not configured"))
+)]
+pub async fn list_dead_letters(State(state): State<Arc<ApiState>>) -> Result<Json<DeadLettersResponse>, StatusCode> {
+    let webhooks = state.webhooks.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(DeadLettersResponse {
+        dead_letters: webhooks.dead_letters(),
+    }))
+}
+
+/// Drop every dead letter
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/dead_letters",
+    responses((status = 200, body = ClearDeadLettersResponse))
+)]
+pub async fn clear_dead_letters(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<ClearDeadLettersResponse>, StatusCode> {
+    let webhooks = state.webhooks.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(ClearDeadLettersResponse {
+        success: true,
+        cleared: webhooks.clear_dead_letters(),
+    }))
+}
+
+/// Re-deliver a dead letter
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/dead_letters/{id}/retry",
+    params(("id" = Uuid, Path, description = "Dead letter id")),
+    responses(
+        (status = 200, body = RetryDeadLetterResponse),
+        (status = 404, description = "Unknown dead letter, or its webhook was removed")
+    )
+)]
+pub async fn retry_dead_letter(
+    State(state): State<Arc<ApiState>>,
+    Path(dead_letter_id): Path<Uuid>,
+) -> Result<Json<RetryDeadLetterResponse>, StatusCode> {
+    let webhooks = state.webhooks.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    if !webhooks.retry_dead_letter(dead_letter_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(RetryDeadLetterResponse {
+        success: true,
+        dead_letter_id,
+    }))
 }
 
 /// Create a world snapshot
+#[utoipa::path(
+    post,
+    path = "/api/world/snapshot",
+    params(CreateSnapshotQuery),
+    responses(
+        (status = 200, body = CreateSnapshotResponse),
+        (status = 503, description = "Simulation control or database not available")
+    )
+)]
 pub async fn create_snapshot(
-    State(_state): State<Arc<ApiState>>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    // TODO: Integrate with actual world state
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Snapshot created (placeholder)"
-    })))
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<CreateSnapshotQuery>,
+) -> Result<Json<CreateSnapshotResponse>, StatusCode> {
+    let name = query.name.unwrap_or_else(|| "Manual".to_string());
+    let (reply, response) = oneshot::channel();
+    send_command(&state, SimCommand::CreateSnapshot { name: name.clone(), reply })?;
+
+    let snapshot_id = response
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?
+        .map_err(command_status)?;
+
+    Ok(Json(CreateSnapshotResponse {
+        success: true,
+        message: format!("Snapshot '{}' created", name),
+        snapshot_id: Some(snapshot_id),
+    }))
+}
+
+/// Restore the simulation from a snapshot
+#[utoipa::path(
+    post,
+    path = "/api/world/snapshots/{id}/restore",
+    params(("id" = Uuid, Path, description = "Snapshot id")),
+    responses(
+        (status = 200, body = RestoreSnapshotResponse),
+        (status = 404, description = "Unknown snapshot id"),
+        (status = 500, description = "Snapshot could not be decoded; the world is unchanged"),
+        (status = 503, description = "Simulation control or database not available")
+    )
+)]
+pub async fn restore_snapshot(
+    State(state): State<Arc<ApiState>>,
+    Path(snapshot_id): Path<Uuid>,
+) -> Result<Json<RestoreSnapshotResponse>, StatusCode> {
+    let (reply, response) = oneshot::channel();
+    send_command(&state, SimCommand::RestoreSnapshot { id: snapshot_id, reply })?;
+
+    response
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?
+        .map_err(command_status)?;
+
+    Ok(Json(RestoreSnapshotResponse {
+        success: true,
+        snapshot_id,
+    }))
 }
 
 /// List all snapshots
+#[utoipa::path(
+    get,
+    path = "/api/world/snapshots",
+    responses((status = 200, body = SnapshotListResponse), (status = 503, description = "Database not configured"))
+)]
 pub async fn list_snapshots(
     State(state): State<Arc<ApiState>>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<SnapshotListResponse>, StatusCode> {
     if let Some(db) = &state.database {
         let snapshots = db
             .list_snapshots()
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-        
-        Ok(Json(serde_json::json!({ "snapshots": snapshots })))
+
+        Ok(Json(SnapshotListResponse { snapshots }))
     } else {
         Err(StatusCode::SERVICE_UNAVAILABLE)
     }
 }
 
+/// Get one loaded chunk of the voxel grid, its blocks run-length encoded
+#[utoipa::path(
+    get,
+    path = "/api/world/chunks/{x}/{y}/{z}",
+    params(
+        ("x" = i32, Path, description = "Chunk x (block x divided by 32, rounded down)"),
+        ("y" = i32, Path, description = "Chunk y"),
+        ("z" = i32, Path, description = "Chunk z")
+    ),
+    responses(
+        (status = 200, body = ChunkResponse),
+        (status = 404, description = "Chunk not loaded (never generated, or paged out)"),
+        (status = 503, description = "Grid not attached")
+    )
+)]
+pub async fn get_chunk(
+    State(state): State<Arc<ApiState>>,
+    Path((x, y, z)): Path<(i32, i32, i32)>,
+) -> Result<Json<ChunkResponse>, StatusCode> {
+    let grid = state.grid.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let chunk = grid.get_chunk(ChunkCoord::new(x, y, z)).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(ChunkResponse {
+        coord: chunk.coord,
+        revision: chunk.revision,
+        blocks: chunk.blocks,
+    }))
+}
+
 /// Get simulation metrics
+#[utoipa::path(get, path = "/api/metrics", responses((status = 200, body = MetricsResponse)))]
 pub async fn get_metrics(
     State(state): State<Arc<ApiState>>,
-) -> Json<serde_json::Value> {
+) -> Json<MetricsResponse> {
     let metrics = state.metrics.read();
-    Json(serde_json::json!({
-        "uptime_seconds": metrics.uptime_seconds,
-        "agent_count": metrics.agent_count,
-        "events_processed": metrics.events_processed
-    }))
+    Json(MetricsResponse {
+        paused: metrics.paused,
+        uptime_seconds: metrics.uptime_seconds,
+        agent_count: metrics.agent_count,
+        events_processed: metrics.events_processed,
+    })
 }
 
 /// Get world state
+#[utoipa::path(get, path = "/api/world/state", responses((status = 200, body = WorldState)))]
 pub async fn get_world_state(
     State(state): State<Arc<ApiState>>,
-) -> Json<serde_json::Value> {
-    let world_state = state.world_state.read();
-    Json(serde_json::json!({
-        "agents": world_state.agents,
-        "resources": world_state.resources,
-        "markets": world_state.markets,
-        "buildings": world_state.buildings,
-        "currency_info": world_state.currency_info,
-        "terrain_size": world_state.terrain_size
-    }))
+) -> Json<WorldState> {
+    Json(state.world_state.read().clone())
 }
 
+/// Get the OpenAPI document for this API
+#[utoipa::path(get, path = "/api/openapi.json", responses((status = 200, description = "This OpenAPI document")))]
+pub async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(crate::openapi::openapi())
+}
@@ -1,25 +1,34 @@
 use crate::routes;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, MethodRouter},
     Router,
 };
 use parking_lot::RwLock;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
+use utoipa::ToSchema;
 use world_sim_event_bus::EventBus;
+use tokio::sync::broadcast;
+use world_sim_event_bus::{EventEnvelope, EventSubscriber, WebhookDispatcher};
+use world_sim_meta::DungeonMaster;
+use world_sim_societal::MarketSystem;
+use world_sim_world::{BuildingManager, GridLayer};
+
+use crate::control::SimCommandSender;
 use world_sim_persistence::Database;
 
 /// Simulation metrics for API
 #[derive(Clone, Default)]
 pub struct SimulationMetrics {
+    pub paused: bool,
     pub agent_count: usize,
     pub events_processed: u64,
     pub uptime_seconds: u64,
 }
 
 /// World state for visualization
-#[derive(Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct WorldState {
     pub agents: Vec<AgentState>,
     pub resources: Vec<ResourceState>,
@@ -30,7 +39,7 @@ pub struct WorldState {
 }
 
 /// Resource state for visualization
-#[derive(Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ResourceState {
     pub id: String,
     pub resource_type: String,
@@ -41,7 +50,7 @@ pub struct ResourceState {
 }
 
 /// Market state for visualization
-#[derive(Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MarketState {
     pub id: String,
     pub name: String,
@@ -58,7 +67,7 @@ pub struct MarketState {
 }
 
 /// Building state for visualization
-#[derive(Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BuildingState {
     pub id: String,
     pub building_type: String,
@@ -83,7 +92,7 @@ pub struct BuildingState {
 }
 
 /// Currency information for visualization
-#[derive(Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct CurrencyInfo {
     pub total_supply: f64,
     pub inflation_rate: f64,
@@ -92,7 +101,7 @@ pub struct CurrencyInfo {
 }
 
 /// Agent state for visualization
-#[derive(Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AgentState {
     pub id: String,
     pub x: f32,
@@ -122,6 +131,12 @@ pub struct AdminApiServer {
     database: Option<Arc<Database>>,
     metrics: Arc<RwLock<SimulationMetrics>>,
     world_state: Arc<RwLock<WorldState>>,
+    dungeon_master: Option<Arc<DungeonMaster>>,
+    buildings: Option<Arc<RwLock<BuildingManager>>>,
+    markets: Option<Arc<RwLock<MarketSystem>>>,
+    grid: Option<Arc<GridLayer>>,
+    commands: Option<SimCommandSender>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
 }
 
 impl AdminApiServer {
@@ -131,6 +146,12 @@ impl AdminApiServer {
             database: None,
             metrics: Arc::new(RwLock::new(SimulationMetrics::default())),
             world_state: Arc::new(RwLock::new(WorldState::default())),
+            dungeon_master: None,
+            buildings: None,
+            markets: None,
+            grid: None,
+            commands: None,
+            webhooks: None,
         }
     }
 
@@ -149,39 +170,64 @@ impl AdminApiServer {
         self
     }
 
+    pub fn with_dungeon_master(mut self, dungeon_master: Arc<DungeonMaster>) -> Self {
+        self.dungeon_master = Some(dungeon_master);
+        self
+    }
+
+    pub fn with_buildings(mut self, buildings: Arc<RwLock<BuildingManager>>) -> Self {
+        self.buildings = Some(buildings);
+        self
+    }
+
+    pub fn with_markets(mut self, markets: Arc<RwLock<MarketSystem>>) -> Self {
+        self.markets = Some(markets);
+        self
+    }
+
+    /// Serve the voxel grid's loaded chunks
+    pub fn with_grid(mut self, grid: Arc<GridLayer>) -> Self {
+        self.grid = Some(grid);
+        self
+    }
+
+    /// Forward pause/resume and snapshot commands to the simulation loop
+    pub fn with_commands(mut self, commands: SimCommandSender) -> Self {
+        self.commands = Some(commands);
+        self
+    }
+
+    /// Manage outgoing webhooks (the dispatcher should already be subscribed to the event bus)
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
     /// Build the router
     pub fn build_router(self) -> Router {
+        // Fan every published event out to streaming clients
+        let (event_stream, _) = broadcast::channel(1024);
+        self.event_bus.subscribe_all(Arc::new(EventStreamSubscriber {
+            sender: event_stream.clone(),
+        }));
+
         let state = Arc::new(ApiState {
             event_bus: self.event_bus,
             database: self.database,
             metrics: self.metrics,
             world_state: self.world_state,
+            dungeon_master: self.dungeon_master,
+            buildings: self.buildings,
+            markets: self.markets,
+            grid: self.grid,
+            commands: self.commands,
+            webhooks: self.webhooks,
+            event_stream,
         });
 
-        Router::new()
-            // Health check
-            .route("/health", get(routes::health_check))
-            
-            // Event history
-            .route("/api/history", get(routes::get_event_history))
-            
-            // Dungeon Master controls
-            .route("/api/dm/inject_event", post(routes::inject_event))
-            
-            // Agent manipulation
-            .route("/api/agent/:id/add_memory", post(routes::add_agent_memory))
-            .route("/api/agent/:id", get(routes::get_agent_info))
-            
-            // World state
-            .route("/api/world/snapshot", get(routes::create_snapshot))
-            .route("/api/world/snapshots", get(routes::list_snapshots))
-            
-            // Metrics
-            .route("/api/metrics", get(routes::get_metrics))
-            
-            // World state
-            .route("/api/world/state", get(routes::get_world_state))
-            
+        routes()
+            .into_iter()
+            .fold(Router::new(), |router, (path, handlers)| router.route(path, handlers))
             .layer(CorsLayer::permissive())
             .with_state(state)
     }
@@ -198,11 +244,91 @@ impl AdminApiServer {
     }
 }
 
+/// Every route the Admin API serves, as `(path, handlers)`; the OpenAPI test checks each is documented
+pub(crate) fn routes() -> Vec<(&'static str, MethodRouter<Arc<ApiState>>)> {
+    vec![
+        // Health check
+        ("/health", get(routes::health_check)),
+
+        // Event history
+        ("/api/history", get(routes::get_event_history)),
+        ("/api/events/stream", get(routes::stream_events)),
+
+        // Dungeon Master controls
+        ("/api/dm/inject_event", post(routes::inject_event)),
+        ("/api/dm/events", get(routes::list_dm_events)),
+        ("/api/dm/events/:id/trigger", post(routes::trigger_dm_event)),
+        ("/api/dm/status", get(routes::get_dm_status)),
+        ("/api/dm/settings", post(routes::update_dm_settings)),
+
+        // Building administration
+        ("/api/buildings", post(routes::place_building)),
+        ("/api/buildings/:id", get(routes::get_building).delete(routes::demolish_building)),
+        ("/api/buildings/:id/fund", post(routes::fund_building)),
+        ("/api/buildings/:id/complete", post(routes::complete_building)),
+        ("/api/buildings/:id/damage", post(routes::damage_building)),
+
+        // Agent manipulation
+        ("/api/agent/:id/add_memory", post(routes::add_agent_memory)),
+        ("/api/agent/:id", get(routes::get_agent_info)),
+        ("/api/agents", get(routes::list_agents)),
+
+        // Markets
+        ("/api/markets", get(routes::list_markets)),
+
+        // Simulation control
+        ("/api/sim/pause", post(routes::pause_simulation)),
+        ("/api/sim/resume", post(routes::resume_simulation)),
+        ("/api/sim/focus", post(routes::set_focus)),
+
+        // Webhooks
+        ("/api/webhooks", get(routes::list_webhooks).post(routes::register_webhook)),
+        ("/api/webhooks/:id", delete(routes::delete_webhook)),
+        ("/api/webhooks/dead_letters", get(routes::list_dead_letters).delete(routes::clear_dead_letters)),
+        ("/api/webhooks/dead_letters/:id/retry", post(routes::retry_dead_letter)),
+
+        // World state
+        ("/api/world/snapshot", get(routes::create_snapshot).post(routes::create_snapshot)),
+        ("/api/world/snapshots", get(routes::list_snapshots)),
+        ("/api/world/snapshots/:id/restore", post(routes::restore_snapshot)),
+        ("/api/world/chunks/:x/:y/:z", get(routes::get_chunk)),
+
+        // Metrics
+        ("/api/metrics", get(routes::get_metrics)),
+
+        // API description
+        ("/api/openapi.json", get(routes::get_openapi)),
+
+        // World state
+        ("/api/world/state", get(routes::get_world_state)),
+    ]
+}
+
 /// Shared state for API handlers
 pub struct ApiState {
     pub event_bus: Arc<EventBus>,
     pub database: Option<Arc<Database>>,
     pub metrics: Arc<RwLock<SimulationMetrics>>,
     pub world_state: Arc<RwLock<WorldState>>,
+    pub dungeon_master: Option<Arc<DungeonMaster>>,
+    pub buildings: Option<Arc<RwLock<BuildingManager>>>,
+    pub markets: Option<Arc<RwLock<MarketSystem>>>,
+    pub grid: Option<Arc<GridLayer>>,
+    pub commands: Option<SimCommandSender>,
+    pub webhooks: Option<Arc<WebhookDispatcher>>,
+    pub event_stream: broadcast::Sender<EventEnvelope>,
+}
+
+/// Forwards every event bus event to the streaming endpoint
+struct EventStreamSubscriber {
+    sender: broadcast::Sender<EventEnvelope>,
+}
+
+#[async_trait::async_trait]
+impl EventSubscriber for EventStreamSubscriber {
+    async fn on_event(&self, event: &EventEnvelope) {
+        // No receivers is fine - nobody is tailing
+        let _ = self.sender.send(event.clone());
+    }
 }
 
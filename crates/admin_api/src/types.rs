@@ -0,0 +1,361 @@
+//! Typed request/response bodies for every Admin API route
+//!
+//! Shared by the server handlers and the `world_sim_admin_client` crate. Domain
+//! types owned by other crates are documented as generic objects in the OpenAPI
+//! schema (except `Position`, `ChunkCoord` and `ChunkBlocks`, which have local schemas below).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use world_sim_core::{ChunkCoord, Position, ResourceType};
+use world_sim_event_bus::{DeadLetter, EventEnvelope, Webhook};
+use world_sim_meta::{DungeonMasterSettings, EventOverrides, StoryEvent, StoryEventStatus, WorldMetrics};
+use world_sim_societal::Market;
+use world_sim_world::{Building, BuildingOwner, BuildingType, ChunkBlocks};
+
+use crate::server::AgentState;
+
+/// OpenAPI schema for `world_sim_core::Position`
+#[derive(ToSchema)]
+#[schema(as = Position)]
+#[allow(dead_code)]
+pub(crate) struct PositionSchema {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+/// OpenAPI schema for `world_sim_core::ChunkCoord`
+#[derive(ToSchema)]
+#[schema(as = ChunkCoord)]
+#[allow(dead_code)]
+pub(crate) struct ChunkCoordSchema {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+/// OpenAPI schema for the run-length wire form of `world_sim_world::ChunkBlocks`
+#[derive(ToSchema)]
+#[schema(as = ChunkBlocks)]
+#[allow(dead_code)]
+pub(crate) struct ChunkBlocksSchema {
+    /// Block types in the chunk, e.g. `"Grass"`
+    palette: Vec<String>,
+    /// `[palette index, length]` runs in flattened order (x fastest, then y, then z)
+    runs: Vec<Vec<u32>>,
+}
+
+// ===== Event history =====
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct HistoryQuery {
+    pub event_type: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct EventStreamQuery {
+    /// Only stream events of this type
+    pub event_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HistoryResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub events: Vec<EventEnvelope>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+// ===== Dungeon Master =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InjectEventRequest {
+    pub event_type: String,
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InjectEventResponse {
+    pub success: bool,
+    pub event_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DmEventsResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub events: Vec<StoryEventStatus>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct TriggerDmEventRequest {
+    #[schema(value_type = Option<PositionSchema>)]
+    pub center: Option<Position>,
+    pub radius: Option<f32>,
+    pub severity: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TriggerDmEventResponse {
+    pub success: bool,
+    #[schema(value_type = Object)]
+    pub event: StoryEvent,
+    #[schema(value_type = Object)]
+    pub overrides: EventOverrides,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DmStatusResponse {
+    pub enabled: bool,
+    pub boredom_threshold: f32,
+    pub boredom: f32,
+    #[schema(value_type = Object)]
+    pub metrics: WorldMetrics,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdateDmSettingsRequest {
+    pub enabled: Option<bool>,
+    pub boredom_threshold: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DmSettingsResponse {
+    pub success: bool,
+    #[schema(value_type = Object)]
+    pub settings: DungeonMasterSettings,
+}
+
+// ===== Buildings =====
+
+/// Full detail for a single building
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BuildingDetail {
+    #[schema(value_type = Object)]
+    pub building: Building,
+    pub is_complete: bool,
+    pub storage_used: u32,
+    pub storage_available: u32,
+    #[schema(value_type = HashMap<String, u32>)]
+    pub remaining_resources: HashMap<ResourceType, u32>,
+}
+
+impl BuildingDetail {
+    pub fn from_building(building: &Building) -> Self {
+        Self {
+            building: building.clone(),
+            is_complete: building.is_complete(),
+            storage_used: building.storage.current_usage(),
+            storage_available: building.storage.available_space(),
+            remaining_resources: building.remaining_resources(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlaceBuildingRequest {
+    #[schema(value_type = String, example = "Warehouse")]
+    pub building_type: BuildingType,
+    #[schema(value_type = PositionSchema)]
+    pub position: Position,
+    /// `"Public"`, `{"Faction": uuid}` or `{"Agent": uuid}` (default: Public)
+    #[schema(value_type = Option<Object>)]
+    pub owner: Option<BuildingOwner>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlaceBuildingResponse {
+    pub success: bool,
+    pub building_id: Uuid,
+    pub detail: BuildingDetail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FundBuildingRequest {
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FundBuildingResponse {
+    pub success: bool,
+    pub construction_fund: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CompleteBuildingResponse {
+    pub success: bool,
+    pub detail: BuildingDetail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DamageBuildingRequest {
+    pub amount: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DamageBuildingResponse {
+    pub success: bool,
+    pub health: f32,
+    pub destroyed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DemolishBuildingResponse {
+    pub success: bool,
+    pub building_id: Uuid,
+}
+
+// ===== Agents =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddMemoryRequest {
+    pub fact: String,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddMemoryResponse {
+    pub success: bool,
+    pub agent_id: String,
+    pub memory: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AgentsResponse {
+    pub agents: Vec<AgentState>,
+}
+
+// ===== Markets =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MarketsResponse {
+    /// Markets with inventory and open buy/sell orders
+    #[schema(value_type = Vec<Object>)]
+    pub markets: Vec<Market>,
+}
+
+// ===== World chunks =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChunkResponse {
+    #[schema(value_type = ChunkCoordSchema)]
+    pub coord: ChunkCoord,
+    /// Grid revision of the chunk's last change
+    pub revision: u64,
+    /// The chunk's 32x32x32 blocks
+    #[schema(value_type = ChunkBlocksSchema)]
+    pub blocks: ChunkBlocks,
+}
+
+// ===== Simulation control =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimControlResponse {
+    pub success: bool,
+    pub paused: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SetFocusRequest {
+    /// Observer positions agents near which get full detail (replaces earlier ones; `y` is ignored)
+    #[schema(value_type = Vec<PositionSchema>)]
+    pub observers: Vec<Position>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetFocusResponse {
+    pub success: bool,
+    pub observers: usize,
+}
+
+// ===== Webhooks =====
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhooksResponse {
+    /// Registered hooks (secrets are never returned)
+    #[schema(value_type = Vec<Object>)]
+    pub webhooks: Vec<Webhook>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegisterWebhookRequest {
+    /// `http://` or `https://` endpoint receiving `POST`ed event envelopes
+    pub url: String,
+    /// Event types to deliver (empty or omitted = every event)
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Secret for the `X-WorldSim-Signature: sha256=<hmac>` header
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegisterWebhookResponse {
+    pub success: bool,
+    #[schema(value_type = Object)]
+    pub webhook: Webhook,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeleteWebhookResponse {
+    pub success: bool,
+    pub webhook_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeadLettersResponse {
+    /// Deliveries that exhausted their retries, oldest first
+    #[schema(value_type = Vec<Object>)]
+    pub dead_letters: Vec<DeadLetter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RetryDeadLetterResponse {
+    pub success: bool,
+    pub dead_letter_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClearDeadLettersResponse {
+    pub success: bool,
+    pub cleared: usize,
+}
+
+// ===== Snapshots and metrics =====
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct CreateSnapshotQuery {
+    /// Snapshot name (default: "Manual")
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateSnapshotResponse {
+    pub success: bool,
+    pub message: String,
+    pub snapshot_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RestoreSnapshotResponse {
+    pub success: bool,
+    pub snapshot_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SnapshotListResponse {
+    /// `(id, name, created_at)` tuples
+    #[schema(value_type = Vec<Vec<String>>)]
+    pub snapshots: Vec<(Uuid, String, chrono::DateTime<chrono::Utc>)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MetricsResponse {
+    pub paused: bool,
+    pub uptime_seconds: u64,
+    pub agent_count: usize,
+    pub events_processed: u64,
+}
@@ -0,0 +1,289 @@
+/// Admin Client - typed Rust client for the Admin API
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+pub use world_sim_admin_api::types::*;
+pub use world_sim_admin_api::{AgentState, BuildingState, CurrencyInfo, MarketState, ResourceState, WorldState};
+pub use world_sim_event_bus::{DeadLetter, EventEnvelope, Webhook};
+
+/// Errors returned by the client
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("API returned status {0}")]
+    Status(u16),
+
+    #[error("Invalid event stream data: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Typed client wrapping every Admin API endpoint
+#[derive(Clone)]
+pub struct AdminClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl AdminClient {
+    /// Create a client for a server such as `http://127.0.0.1:8080`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T> {
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(ClientError::Status(response.status().as_u16()));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        Self::send(self.http.get(self.url(path))).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        Self::send(self.http.post(self.url(path)).json(body)).await
+    }
+
+    /// GET /health
+    pub async fn health(&self) -> Result<bool> {
+        let response = self.http.get(self.url("/health")).send().await?;
+        Ok(response.status().is_success() && response.text().await? == "OK")
+    }
+
+    /// GET /api/history
+    pub async fn history(&self, query: &HistoryQuery) -> Result<HistoryResponse> {
+        Self::send(self.http.get(self.url("/api/history")).query(query)).await
+    }
+
+    /// POST /api/dm/inject_event
+    pub async fn inject_event(&self, request: &InjectEventRequest) -> Result<InjectEventResponse> {
+        self.post("/api/dm/inject_event", request).await
+    }
+
+    /// GET /api/dm/events
+    pub async fn dm_events(&self) -> Result<DmEventsResponse> {
+        self.get("/api/dm/events").await
+    }
+
+    /// POST /api/dm/events/:id/trigger
+    pub async fn trigger_dm_event(
+        &self,
+        event_id: &str,
+        request: &TriggerDmEventRequest,
+    ) -> Result<TriggerDmEventResponse> {
+        self.post(&format!("/api/dm/events/{}/trigger", event_id), request).await
+    }
+
+    /// GET /api/dm/status
+    pub async fn dm_status(&self) -> Result<DmStatusResponse> {
+        self.get("/api/dm/status").await
+    }
+
+    /// POST /api/dm/settings
+    pub async fn update_dm_settings(&self, request: &UpdateDmSettingsRequest) -> Result<DmSettingsResponse> {
+        self.post("/api/dm/settings", request).await
+    }
+
+    /// POST /api/buildings
+    pub async fn place_building(&self, request: &PlaceBuildingRequest) -> Result<PlaceBuildingResponse> {
+        self.post("/api/buildings", request).await
+    }
+
+    /// GET /api/buildings/:id
+    pub async fn building(&self, building_id: Uuid) -> Result<BuildingDetail> {
+        self.get(&format!("/api/buildings/{}", building_id)).await
+    }
+
+    /// POST /api/buildings/:id/fund
+    pub async fn fund_building(&self, building_id: Uuid, amount: f64) -> Result<FundBuildingResponse> {
+        self.post(&format!("/api/buildings/{}/fund", building_id), &FundBuildingRequest { amount })
+            .await
+    }
+
+    /// POST /api/buildings/:id/complete
+    pub async fn complete_building(&self, building_id: Uuid) -> Result<CompleteBuildingResponse> {
+        Self::send(self.http.post(self.url(&format!("/api/buildings/{}/complete", building_id)))).await
+    }
+
+    /// POST /api/buildings/:id/damage
+    pub async fn damage_building(&self, building_id: Uuid, amount: f32) -> Result<DamageBuildingResponse> {
+        self.post(&format!("/api/buildings/{}/damage", building_id), &DamageBuildingRequest { amount })
+            .await
+    }
+
+    /// DELETE /api/buildings/:id
+    pub async fn demolish_building(&self, building_id: Uuid) -> Result<DemolishBuildingResponse> {
+        Self::send(self.http.delete(self.url(&format!("/api/buildings/{}", building_id)))).await
+    }
+
+    /// POST /api/agent/:id/add_memory
+    pub async fn add_agent_memory(&self, agent_id: &str, request: &AddMemoryRequest) -> Result<AddMemoryResponse> {
+        self.post(&format!("/api/agent/{}/add_memory", agent_id), request).await
+    }
+
+    /// GET /api/agent/:id
+    pub async fn agent(&self, agent_id: &str) -> Result<AgentState> {
+        self.get(&format!("/api/agent/{}", agent_id)).await
+    }
+
+    /// GET /api/agents
+    pub async fn agents(&self) -> Result<AgentsResponse> {
+        self.get("/api/agents").await
+    }
+
+    /// GET /api/markets
+    pub async fn markets(&self) -> Result<MarketsResponse> {
+        self.get("/api/markets").await
+    }
+
+    /// POST /api/sim/pause
+    pub async fn pause(&self) -> Result<SimControlResponse> {
+        Self::send(self.http.post(self.url("/api/sim/pause"))).await
+    }
+
+    /// POST /api/sim/resume
+    pub async fn resume(&self) -> Result<SimControlResponse> {
+        Self::send(self.http.post(self.url("/api/sim/resume"))).await
+    }
+
+    /// POST /api/sim/focus
+    pub async fn set_focus(&self, request: &SetFocusRequest) -> Result<SetFocusResponse> {
+        self.post("/api/sim/focus", request).await
+    }
+
+    /// POST /api/world/snapshot
+    pub async fn create_snapshot(&self, name: Option<&str>) -> Result<CreateSnapshotResponse> {
+        let query = CreateSnapshotQuery {
+            name: name.map(str::to_string),
+        };
+        Self::send(self.http.post(self.url("/api/world/snapshot")).query(&query)).await
+    }
+
+    /// POST /api/world/snapshots/:id/restore
+    pub async fn restore_snapshot(&self, snapshot_id: Uuid) -> Result<RestoreSnapshotResponse> {
+        Self::send(self.http.post(self.url(&format!("/api/world/snapshots/{}/restore", snapshot_id)))).await
+    }
+
+    /// GET /api/webhooks
+    pub async fn webhooks(&self) -> Result<WebhooksResponse> {
+        self.get("/api/webhooks").await
+    }
+
+    /// POST /api/webhooks
+    pub async fn register_webhook(&self, request: &RegisterWebhookRequest) -> Result<RegisterWebhookResponse> {
+        self.post("/api/webhooks", request).await
+    }
+
+    /// DELETE /api/webhooks/:id
+    pub async fn delete_webhook(&self, webhook_id: Uuid) -> Result<DeleteWebhookResponse> {
+        Self::send(self.http.delete(self.url(&format!("/api/webhooks/{}", webhook_id)))).await
+    }
+
+    /// GET /api/webhooks/dead_letters
+    pub async fn dead_letters(&self) -> Result<DeadLettersResponse> {
+        self.get("/api/webhooks/dead_letters").await
+    }
+
+    /// DELETE /api/webhooks/dead_letters
+    pub async fn clear_dead_letters(&self) -> Result<ClearDeadLettersResponse> {
+        Self::send(self.http.delete(self.url("/api/webhooks/dead_letters"))).await
+    }
+
+    /// POST /api/webhooks/dead_letters/:id/retry
+    pub async fn retry_dead_letter(&self, dead_letter_id: Uuid) -> Result<RetryDeadLetterResponse> {
+        Self::send(self.http.post(self.url(&format!("/api/webhooks/dead_letters/{}/retry", dead_letter_id)))).await
+    }
+
+    /// GET /api/world/snapshots
+    pub async fn list_snapshots(&self) -> Result<SnapshotListResponse> {
+        self.get("/api/world/snapshots").await
+    }
+
+    /// GET /api/metrics
+    pub async fn metrics(&self) -> Result<MetricsResponse> {
+        self.get("/api/metrics").await
+    }
+
+    /// GET /api/world/chunks/:x/:y/:z - a loaded chunk by chunk coordinate
+    pub async fn chunk(&self, x: i32, y: i32, z: i32) -> Result<ChunkResponse> {
+        self.get(&format!("/api/world/chunks/{}/{}/{}", x, y, z)).await
+    }
+
+    /// GET /api/world/state
+    pub async fn world_state(&self) -> Result<WorldState> {
+        self.get("/api/world/state").await
+    }
+
+    /// GET /api/events/stream - live events, optionally filtered by type
+    pub async fn stream_events(&self, event_type: Option<&str>) -> Result<EventStream> {
+        let query = EventStreamQuery {
+            event_type: event_type.map(str::to_string),
+        };
+        let response = self
+            .http
+            .get(self.url("/api/events/stream"))
+            .query(&query)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(ClientError::Status(response.status().as_u16()));
+        }
+        Ok(EventStream {
+            response,
+            buffer: String::new(),
+        })
+    }
+
+    /// GET /api/openapi.json
+    pub async fn openapi(&self) -> Result<serde_json::Value> {
+        self.get("/api/openapi.json").await
+    }
+}
+
+/// Live event stream (Server-Sent Events) from `/api/events/stream`
+pub struct EventStream {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl EventStream {
+    /// Wait for the next event; `None` once the server closes the stream
+    pub async fn next(&mut self) -> Result<Option<EventEnvelope>> {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let message: String = self.buffer.drain(..end + 2).collect();
+                let data: Vec<&str> = message
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(str::trim_start)
+                    .collect();
+                if data.is_empty() {
+                    continue; // Keep-alive comment
+                }
+                return Ok(Some(serde_json::from_str(&data.join("\n"))?));
+            }
+
+            match self.response.chunk().await? {
+                Some(chunk) => self.buffer.push_str(&String::from_utf8_lossy(&chunk)),
+                None => return Ok(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use parking_lot::RwLock;
+use std::sync::Arc;
+use world_sim_admin_api::AdminApiServer;
+use world_sim_admin_client::*;
+use world_sim_core::{BlockType, ChunkCoord, GridCoord, Position};
+use world_sim_event_bus::{EventBus, RetryPolicy, WebhookDispatcher};
+use world_sim_meta::DungeonMaster;
+use world_sim_world::{BuildingManager, BuildingType, GridLayer, CHUNK_VOLUME};
+
+/// Start an Admin API server on an ephemeral port and return a client for it
+async fn spawn_server() -> AdminClient {
+    let event_bus = Arc::new(EventBus::new());
+    let server = AdminApiServer::new(event_bus.clone())
+        .with_dungeon_master(Arc::new(DungeonMaster::new(event_bus)))
+        .with_buildings(Arc::new(RwLock::new(BuildingManager::new())));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, server.build_router()).await.unwrap();
+    });
+
+    AdminClient::new(format!("http://{}", addr))
+}
+
+#[tokio::test]
+async fn test_core_endpoints() {
+    let client = spawn_server().await;
+
+    assert!(client.health().await.unwrap());
+    assert_eq!(client.metrics().await.unwrap().agent_count, 0);
+    assert!(client.world_state().await.unwrap().agents.is_empty());
+    assert!(client.history(&HistoryQuery::default()).await.unwrap().message.is_some());
+
+    let injected = client
+        .inject_event(&InjectEventRequest {
+            event_type: "Custom".to_string(),
+            payload: serde_json::json!({ "note": "test" }),
+        })
+        .await
+        .unwrap();
+    assert!(injected.success);
+
+    let memory = client
+        .add_agent_memory("agent-1", &AddMemoryRequest {
+            fact: "The king is planning to betray us".to_string(),
+            source: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(memory.agent_id, "agent-1");
+    assert!(matches!(client.agent("agent-1").await, Err(ClientError::Status(404))));
+    assert!(client.agents().await.unwrap().agents.is_empty());
+
+    // No database or simulation loop attached
+    assert!(matches!(client.list_snapshots().await, Err(ClientError::Status(503))));
+    assert!(matches!(client.create_snapshot(None).await, Err(ClientError::Status(503))));
+    assert!(matches!(client.pause().await, Err(ClientError::Status(503))));
+
+    let spec = client.openapi().await.unwrap();
+    assert!(spec["paths"]["/api/dm/status"].is_object());
+}
+
+#[tokio::test]
+async fn test_dungeon_master_endpoints() {
+    let client = spawn_server().await;
+
+    let events = client.dm_events().await.unwrap().events;
+    assert!(events.iter().any(|e| e.event.id == "wood_blight"));
+
+    let triggered = client
+        .trigger_dm_event("wood_blight", &TriggerDmEventRequest {
+            center: Some(Position::new(10.0, 0.0, 5.0)),
+            radius: Some(25.0),
+            severity: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(triggered.overrides.radius, Some(25.0));
+    assert!(matches!(
+        client.trigger_dm_event("no_such_event", &TriggerDmEventRequest::default()).await,
+        Err(ClientError::Status(404))
+    ));
+
+    let settings = client
+        .update_dm_settings(&UpdateDmSettingsRequest {
+            enabled: Some(false),
+            boredom_threshold: Some(0.5),
+        })
+        .await
+        .unwrap();
+    assert!(!settings.settings.enabled);
+    assert!(matches!(
+        client
+            .update_dm_settings(&UpdateDmSettingsRequest {
+                enabled: None,
+                boredom_threshold: Some(2.0),
+            })
+            .await,
+        Err(ClientError::Status(400))
+    ));
+
+    let status = client.dm_status().await.unwrap();
+    assert!(!status.enabled);
+    assert_eq!(status.boredom_threshold, 0.5);
+}
+
+#[tokio::test]
+async fn test_building_endpoints() {
+    let client = spawn_server().await;
+
+    let placed = client
+        .place_building(&PlaceBuildingRequest {
+            building_type: BuildingType::Tavern,
+            position: Position::new(10.0, 1.0, -5.0),
+            owner: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    let id = placed.building_id;
+    assert!(!placed.detail.is_complete);
+
+    assert_eq!(client.fund_building(id, 500.0).await.unwrap().construction_fund, 500.0);
+    assert!(client.complete_building(id).await.unwrap().detail.is_complete);
+
+    let detail = client.building(id).await.unwrap();
+    assert_eq!(detail.building.name, "Tavern (Admin)");
+
+    let damaged = client.damage_building(id, 40.0).await.unwrap();
+    assert_eq!(damaged.health, 60.0);
+    assert!(!damaged.destroyed);
+
+    assert!(client.demolish_building(id).await.unwrap().success);
+    assert!(matches!(client.building(id).await, Err(ClientError::Status(404))));
+}
+
+#[tokio::test]
+async fn test_webhook_endpoints() {
+    let event_bus = Arc::new(EventBus::new());
+    let dispatcher = Arc::new(WebhookDispatcher::with_retry_policy(RetryPolicy {
+        max_attempts: 1,
+        ..Default::default()
+    }));
+    event_bus.subscribe_all(dispatcher.clone());
+    let server = AdminApiServer::new(event_bus).with_webhooks(dispatcher);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, server.build_router()).await.unwrap();
+    });
+    let client = AdminClient::new(format!("http://{}", addr));
+
+    assert!(matches!(
+        client
+            .register_webhook(&RegisterWebhookRequest {
+                url: "ftp://example.com".to_string(),
+                event_types: Vec::new(),
+                secret: None,
+            })
+            .await,
+        Err(ClientError::Status(400))
+    ));
+
+    // The admin server has no /nowhere route, so the single delivery attempt gets a 404
+    let registered = client
+        .register_webhook(&RegisterWebhookRequest {
+            url: format!("http://{}/nowhere", addr),
+            event_types: vec!["Custom".to_string()],
+            secret: Some("s3cret".to_string()),
+        })
+        .await
+        .unwrap();
+    let hooks = client.webhooks().await.unwrap().webhooks;
+    assert_eq!(hooks.len(), 1);
+    assert!(hooks[0].secret.is_none(), "secrets must not be returned");
+
+    client
+        .inject_event(&InjectEventRequest {
+            event_type: "Custom".to_string(),
+            payload: serde_json::json!({}),
+        })
+        .await
+        .unwrap();
+
+    let mut dead_letters = Vec::new();
+    for _ in 0..100 {
+        dead_letters = client.dead_letters().await.unwrap().dead_letters;
+        if !dead_letters.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(dead_letters[0].event.event_type, "Custom");
+    assert_eq!(dead_letters[0].last_error, "HTTP 404");
+
+    assert_eq!(client.clear_dead_letters().await.unwrap().cleared, 1);
+    assert!(matches!(
+        client.retry_dead_letter(dead_letters[0].id).await,
+        Err(ClientError::Status(404))
+    ));
+    assert!(client.delete_webhook(registered.webhook.id).await.unwrap().success);
+    assert!(client.webhooks().await.unwrap().webhooks.is_empty());
+}
+
+#[tokio::test]
+async fn test_control_markets_and_event_stream() {
+    let event_bus = Arc::new(EventBus::new());
+    let mut markets = world_sim_societal::MarketSystem::new();
+    markets.create_market(
+        "Town Square".to_string(),
+        Position::new(0.0, 1.0, 0.0),
+        world_sim_societal::MarketType::General,
+    );
+    let (commands, mut receiver) = world_sim_admin_api::command_channel();
+    let server = AdminApiServer::new(event_bus)
+        .with_markets(Arc::new(RwLock::new(markets)))
+        .with_commands(commands);
+
+    // Stand-in for the simulation loop
+    let snapshot_id = uuid::Uuid::new_v4();
+    tokio::spawn(async move {
+        while let Some(command) = receiver.recv().await {
+            match command {
+                world_sim_admin_api::SimCommand::CreateSnapshot { reply, .. } => {
+                    let _ = reply.send(Ok(snapshot_id));
+                }
+                world_sim_admin_api::SimCommand::RestoreSnapshot { reply, .. } => {
+                    let _ = reply.send(Err(world_sim_admin_api::CommandError::NotFound));
+                }
+                _ => {}
+            }
+        }
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, server.build_router()).await.unwrap();
+    });
+    let client = AdminClient::new(format!("http://{}", addr));
+
+    assert!(client.pause().await.unwrap().paused);
+    assert!(!client.resume().await.unwrap().paused);
+    assert_eq!(client.create_snapshot(Some("test")).await.unwrap().snapshot_id, Some(snapshot_id));
+    assert!(matches!(
+        client.restore_snapshot(uuid::Uuid::new_v4()).await,
+        Err(ClientError::Status(404))
+    ));
+
+    let markets = client.markets().await.unwrap().markets;
+    assert_eq!(markets[0].name, "Town Square");
+
+    let mut stream = client.stream_events(Some("Custom")).await.unwrap();
+    for event_type in ["Ignored", "Custom"] {
+        client
+            .inject_event(&InjectEventRequest {
+                event_type: event_type.to_string(),
+                payload: serde_json::json!({}),
+            })
+            .await
+            .unwrap();
+    }
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.event_type, "Custom");
+}
+
+#[tokio::test]
+async fn test_chunk_endpoint() {
+    assert!(matches!(spawn_server().await.chunk(0, 0, 0).await, Err(ClientError::Status(503))));
+
+    let grid = Arc::new(GridLayer::new());
+    grid.generate_simple_terrain(GridCoord::new(-8, 0, -8), GridCoord::new(8, 0, 8));
+    grid.set_block(GridCoord::new(3, 1, 4), BlockType::Stone);
+    let server = AdminApiServer::new(Arc::new(EventBus::new())).with_grid(grid.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, server.build_router()).await.unwrap();
+    });
+    let client = AdminClient::new(format!("http://{}", addr));
+
+    let coord = ChunkCoord::new(0, 0, 0);
+    let chunk = client.chunk(0, 0, 0).await.unwrap();
+    let loaded = grid.get_chunk(coord).unwrap();
+    assert_eq!((chunk.coord, chunk.revision), (coord, loaded.revision));
+    assert!((0..CHUNK_VOLUME).all(|index| chunk.blocks.get(index) == loaded.blocks.get(index)));
+    assert_eq!(loaded.get(3, 1, 4), BlockType::Stone);
+    assert!(matches!(client.chunk(9, 9, 9).await, Err(ClientError::Status(404))));
+}
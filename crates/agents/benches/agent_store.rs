@@ -0,0 +1,57 @@
+//! Agent storage at scale: lookups and archiving over 10k agents
+//!
+//! Run with `cargo bench -p world_sim_agents`. Whole scheduler ticks are timed by `sim_server bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use std::sync::Arc;
+use world_sim_agents::{AgentState, AgentStore, LifecycleLayer, SimAgent};
+use world_sim_core::{AgentId, Position};
+use world_sim_event_bus::EventBus;
+
+const AGENTS: usize = 10_000;
+
+fn population() -> Vec<SimAgent> {
+    (0..AGENTS)
+        .map(|i| SimAgent::new(format!("Agent_{}", i), Position::new((i % 100) as f32, 1.0, (i / 100) as f32)))
+        .collect()
+}
+
+fn lifecycle() -> LifecycleLayer {
+    let lifecycle = LifecycleLayer::new(Arc::new(EventBus::new()));
+    for agent in population() {
+        lifecycle.spawn_agent(agent);
+    }
+    lifecycle
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let store: AgentStore = population().into_iter().collect();
+    let ids: Vec<AgentId> = store.iter().step_by(10).map(|a| a.id).collect();
+
+    c.bench_function("store_get_1k_of_10k", |b| {
+        b.iter(|| ids.iter().filter_map(|id| store.get(*id)).map(|a| a.position.x).sum::<f32>())
+    });
+}
+
+fn bench_archive_dead(c: &mut Criterion) {
+    c.bench_function("archive_1k_dead_of_10k", |b| {
+        b.iter_batched(
+            || {
+                let lifecycle = lifecycle();
+                for agent in lifecycle.get_agents_mut().iter_mut().step_by(10) {
+                    agent.state = AgentState::Dead;
+                }
+                lifecycle
+            },
+            // Hand the layer back so dropping 9k agents isn't timed
+            |lifecycle| {
+                black_box(lifecycle.archive_dead());
+                lifecycle
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_lookup, bench_archive_dead);
+criterion_main!(benches);
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use world_sim_core::{AgentId, Attributes, GridCoord, Position, ResourceType, Skill, Trait};
+use rand::Rng;
+use world_sim_core::{sim_rng, AgentId, Attributes, GridCoord, Position, ResourceType, Skill, Trait};
 use crate::{AgentDomain, PersonalityProfile, SkillDatabase};
 
 /// Resources an agent is carrying to a construction site
@@ -18,6 +19,9 @@ pub struct SimAgent {
     pub id: AgentId,
     pub name: String,
     pub position: Position,
+    /// Horizontal direction of the agent's last step (any length); `None` until it first moves
+    #[serde(default)]
+    pub heading: Option<Position>,
     pub age: u32,
     pub attributes: Attributes,
     pub personality: PersonalityProfile,
@@ -99,6 +103,51 @@ pub enum SocialClass {
     Peasant,    // ~44% - farmers, laborers, commoners
 }
 
+/// Starting gold for each social class
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StartingWallets {
+    pub king: f64,
+    pub noble: f64,
+    pub knight: f64,
+    pub soldier: f64,
+    pub merchant: f64,
+    pub burgher: f64,
+    pub cleric: f64,
+    pub peasant: f64,
+}
+
+impl Default for StartingWallets {
+    fn default() -> Self {
+        // INCREASED for economic stability
+        Self {
+            king: 5000.0,   // Can fund major projects
+            noble: 2000.0,  // Can commission buildings
+            knight: 500.0,
+            soldier: 300.0,
+            merchant: 400.0, // Working capital
+            burgher: 400.0,
+            cleric: 200.0,
+            peasant: 300.0, // Enough to save for a house after some work
+        }
+    }
+}
+
+impl StartingWallets {
+    pub fn for_class(&self, social_class: SocialClass) -> f64 {
+        match social_class {
+            SocialClass::King => self.king,
+            SocialClass::Noble => self.noble,
+            SocialClass::Knight => self.knight,
+            SocialClass::Soldier => self.soldier,
+            SocialClass::Merchant => self.merchant,
+            SocialClass::Burgher => self.burgher,
+            SocialClass::Cleric => self.cleric,
+            SocialClass::Peasant => self.peasant,
+        }
+    }
+}
+
 impl SimAgent {
     pub fn new(name: String, position: Position) -> Self {
         Self::new_with_class(name, position, SocialClass::Peasant)
@@ -118,10 +167,10 @@ impl SimAgent {
             SocialClass::Cleric => Job::Unemployed, // Religious duties
             SocialClass::Peasant => {
                 // Peasants: 80% harvesting, 20% building
-                if rand::random::<u32>() % 5 == 0 {
+                if sim_rng().gen::<u32>().is_multiple_of(5) {
                     Job::Builder
                 } else {
-                    match rand::random::<u32>() % 3 {
+                    match sim_rng().gen::<u32>() % 3 {
                         0 => Job::Woodcutter,
                         1 => Job::Miner,
                         _ => Job::Farmer,
@@ -130,16 +179,8 @@ impl SimAgent {
             }
         };
         
-        // Initial wallet based on social class (INCREASED for economic stability)
-        let wallet = match social_class {
-            SocialClass::King => 5000.0,      // Can fund major projects
-            SocialClass::Noble => 2000.0,     // Can commission buildings
-            SocialClass::Knight => 500.0,     
-            SocialClass::Merchant | SocialClass::Burgher => 400.0, // Working capital
-            SocialClass::Soldier => 300.0,    
-            SocialClass::Cleric => 200.0,     
-            SocialClass::Peasant => 300.0,    // Enough to save for a house after some work
-        };
+        // Initial wallet based on social class
+        let wallet = StartingWallets::default().for_class(social_class);
         
         // Basic needs for all agents
         let mut needs = HashMap::new();
@@ -167,7 +208,8 @@ impl SimAgent {
             id: AgentId::new(),
             name,
             position,
-            age: rand::random::<u32>() % 60 + 18, // 18-78 years
+            heading: None,
+            age: sim_rng().gen::<u32>() % 60 + 18, // 18-78 years
             attributes: Attributes::default(),
             personality: PersonalityProfile::random(),
             skills: SkillDatabase::new(),
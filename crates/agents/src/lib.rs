@@ -1,12 +1,14 @@
 /// Agent Layer - Individual agent definitions and behavior
 pub mod agent;
 pub mod lifecycle;
+pub mod store;
 pub mod skills;
 pub mod personality;
 pub mod ownership;
 
-pub use agent::{SimAgent, AgentState, Job, SocialClass, BuildingResources, TransactionType, Loan};
+pub use agent::{SimAgent, AgentState, Job, SocialClass, StartingWallets, BuildingResources, TransactionType, Loan};
 pub use lifecycle::*;
+pub use store::AgentStore;
 pub use skills::*;
 pub use personality::*;
 pub use ownership::*;
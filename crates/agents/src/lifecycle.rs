@@ -1,15 +1,25 @@
-use parking_lot::RwLock;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use rand::Rng;
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
-use world_sim_core::{AgentId, Position};
-use world_sim_event_bus::{AgentBornEvent, AgentDiedEvent, EventBus};
-use crate::{AgentState, SimAgent};
+use world_sim_core::{sim_rng, AgentId, Position};
+use world_sim_event_bus::{AgentBornEvent, AgentDiedEvent, EventBus, KingDiedEvent};
+use world_sim_world::GridLayer;
+use crate::{AgentState, AgentStore, SimAgent, SocialClass, StartingWallets};
+
+/// How many dead agents are kept around for lookups by default
+const DEFAULT_ARCHIVE_LIMIT: usize = 1000;
 
 /// Manages the birth, death, and population of agents
 pub struct LifecycleLayer {
-    agents: Arc<RwLock<Vec<SimAgent>>>,
+    agents: Arc<RwLock<AgentStore>>,
+    /// Most recently removed dead agents, oldest first
+    archive: Arc<RwLock<VecDeque<SimAgent>>>,
+    archive_limit: usize,
     birth_rate: f32,
     death_rate: f32,
+    starting_wallets: StartingWallets,
     event_bus: Arc<EventBus>,
 }
 
@@ -17,33 +27,51 @@ impl LifecycleLayer {
     /// Create with custom rates
     pub fn with_rates(event_bus: Arc<EventBus>, birth_rate: f32, death_rate: f32) -> Self {
         Self {
-            agents: Arc::new(RwLock::new(Vec::new())),
+            agents: Arc::new(RwLock::new(AgentStore::new())),
+            archive: Arc::new(RwLock::new(VecDeque::new())),
+            archive_limit: DEFAULT_ARCHIVE_LIMIT,
             birth_rate,
             death_rate,
+            starting_wallets: StartingWallets::default(),
             event_bus,
         }
     }
+
+    /// Set the starting gold given to newborn agents
+    pub fn with_starting_wallets(mut self, starting_wallets: StartingWallets) -> Self {
+        self.starting_wallets = starting_wallets;
+        self
+    }
+
+    /// Set how many dead agents are kept for lookups after being removed
+    pub fn with_archive_limit(mut self, archive_limit: usize) -> Self {
+        self.archive_limit = archive_limit;
+        self
+    }
 }
 
 impl LifecycleLayer {
     pub fn new(event_bus: Arc<EventBus>) -> Self {
         Self {
-            agents: Arc::new(RwLock::new(Vec::new())),
+            agents: Arc::new(RwLock::new(AgentStore::new())),
+            archive: Arc::new(RwLock::new(VecDeque::new())),
+            archive_limit: DEFAULT_ARCHIVE_LIMIT,
             birth_rate: 0.01,  // Increased from 0.001 (10x)
             death_rate: 0.005, // Increased from 0.001 (5x)
+            starting_wallets: StartingWallets::default(),
             event_bus,
         }
     }
 
     /// Add a new agent to the simulation
     pub fn spawn_agent(&self, agent: SimAgent) {
-        let mut agents = self.agents.write();
-        agents.push(agent);
+        self.agents.write().insert(agent);
     }
 
     /// Create a new agent (birth or immigration)
     pub async fn birth_agent(&self, name: String, position: Position, parents: Vec<AgentId>) {
-        let agent = SimAgent::new(name, position);
+        let mut agent = SimAgent::new(name, position);
+        agent.wallet = self.starting_wallets.for_class(agent.social_class);
         let id = agent.id;
         
         self.spawn_agent(agent);
@@ -60,12 +88,25 @@ impl LifecycleLayer {
 
     /// Kill an agent
     pub async fn kill_agent(&self, agent_id: AgentId, cause: String) {
-        let mut agents = self.agents.write();
+        let died = {
+            let mut agents = self.agents.write();
+            agents.get_mut(agent_id).map(|agent| {
+                agent.state = AgentState::Dead;
+                (agent.position, agent.social_class)
+            })
+        }; // Drop write lock before publishing
         
-        if let Some(agent) = agents.iter_mut().find(|a| a.id == agent_id) {
-            let position = agent.position;
-            agent.state = AgentState::Dead;
-            
+        if let Some((position, social_class)) = died {
+            if social_class == SocialClass::King {
+                self.event_bus
+                    .publish(&KingDiedEvent {
+                        king_id: agent_id,
+                        cause: cause.clone(),
+                        location: position,
+                    })
+                    .await;
+            }
+
             // Publish death event
             self.event_bus
                 .publish(&AgentDiedEvent {
@@ -77,83 +118,119 @@ impl LifecycleLayer {
         }
     }
 
-    /// Process natural births and deaths
-    pub async fn tick(&self) {
-        let mut rng = rand::thread_rng();
-        let agents = self.agents.read();
-        let agent_count = agents.len();
-        drop(agents);
+    /// Process natural births and deaths; newborns arrive standing on `grid`'s surface
+    pub async fn tick(&self, grid: &GridLayer) {
+        let agent_count = self.agents.read().len();
         
         // Random births
-        if rng.gen::<f32>() < self.birth_rate * agent_count as f32 {
-            let position = Position::new(
-                rng.gen_range(-100.0..100.0),
-                1.0,
-                rng.gen_range(-100.0..100.0),
-            );
-            self.birth_agent(format!("Citizen_{}", rng.gen::<u32>()), position, vec![])
-                .await;
+        let birth = {
+            let mut rng = sim_rng();
+            if rng.gen::<f32>() < self.birth_rate * agent_count as f32 {
+                let (x, z) = (rng.gen_range(-100.0..100.0), rng.gen_range(-100.0..100.0));
+                Some((format!("Citizen_{}", rng.gen::<u32>()), grid.standing_position(x, z)))
+            } else {
+                None
+            }
+        };
+        if let Some((name, position)) = birth {
+            self.birth_agent(name, position, vec![]).await;
         }
         
         // Random deaths (natural causes)
-        let agents = self.agents.read();
-        let alive_agents: Vec<AgentId> = agents
-            .iter()
-            .filter(|a| a.is_alive())
-            .map(|a| a.id)
-            .collect();
-        drop(agents);
+        let dying: Vec<AgentId> = {
+            let mut rng = sim_rng();
+            self.agents
+                .read()
+                .iter()
+                .filter(|a| a.is_alive())
+                .map(|a| a.id)
+                .filter(|_| rng.gen::<f32>() < self.death_rate)
+                .collect()
+        };
         
-        for agent_id in alive_agents {
-            if rng.gen::<f32>() < self.death_rate {
-                self.kill_agent(agent_id, "Natural causes".to_string())
-                    .await;
-            }
+        for agent_id in dying {
+            self.kill_agent(agent_id, "Natural causes".to_string())
+                .await;
         }
+        
+        self.archive_dead();
     }
 
-    /// Get all agents
+    /// Move dead agents out of the live store into the archive, returning how many were moved
+    pub fn archive_dead(&self) -> usize {
+        let dead = self.agents.write().remove_dead();
+        let count = dead.len();
+        if count > 0 {
+            let mut archive = self.archive.write();
+            archive.extend(dead);
+            let excess = archive.len().saturating_sub(self.archive_limit);
+            archive.drain(..excess);
+        }
+        count
+    }
+
+    /// Borrow the agent store (returns a read guard - don't take other world locks while holding it)
+    pub fn agents(&self) -> RwLockReadGuard<'_, AgentStore> {
+        self.agents.read()
+    }
+
+    /// Get a copy of all agents (for snapshots that outlive the lock)
     pub fn get_agents(&self) -> Vec<SimAgent> {
-        self.agents.read().clone()
+        self.agents.read().iter().cloned().collect()
     }
     
-    /// Get mutable reference to all agents (returns a write guard)
-    pub fn get_agents_mut(&self) -> parking_lot::RwLockWriteGuard<Vec<SimAgent>> {
-        self.agents.write()
+    /// Get mutable reference to all agents (returns a write guard that re-indexes positions on drop)
+    pub fn get_agents_mut(&self) -> AgentsMut<'_> {
+        AgentsMut(self.agents.write())
     }
 
-    /// Get agent by ID
+    /// Get agent by ID (recently archived dead agents included)
     pub fn get_agent(&self, id: AgentId) -> Option<SimAgent> {
-        self.agents.read().iter().find(|a| a.id == id).cloned()
+        if let Some(agent) = self.agents.read().get(id) {
+            return Some(agent.clone());
+        }
+        self.archive.read().iter().rev().find(|a| a.id == id).cloned()
     }
 
     /// Count living agents
     pub fn count_living(&self) -> usize {
-        self.agents.read().iter().filter(|a| a.is_alive()).count()
+        self.agents.read().living().count()
     }
 
     /// Update agent position
     pub fn update_agent_position(&self, agent_id: AgentId, new_position: Position) {
-        let mut agents = self.agents.write();
-        if let Some(agent) = agents.iter_mut().find(|a| a.id == agent_id) {
-            agent.position = new_position;
-        }
+        self.agents.write().set_position(agent_id, new_position);
     }
 
     /// Update agent state
     pub fn update_agent_state(&self, agent_id: AgentId, new_state: AgentState) {
-        let mut agents = self.agents.write();
-        if let Some(agent) = agents.iter_mut().find(|a| a.id == agent_id) {
+        if let Some(agent) = self.agents.write().get_mut(agent_id) {
             agent.state = new_state;
         }
     }
 
+    /// Apply per-agent updates in the given order under one write lock (unknown ids are skipped)
+    pub fn apply_updates<T, F>(&self, updates: Vec<(AgentId, T)>, mut apply: F)
+    where
+        F: FnMut(&mut SimAgent, T),
+    {
+        if updates.is_empty() {
+            return;
+        }
+        let mut agents = self.get_agents_mut();
+        for (id, update) in updates {
+            if let Some(agent) = agents.get_mut(id) {
+                apply(agent, update);
+            }
+        }
+    }
+
     /// Update all living agents (for movement and behavior)
     pub fn update_living_agents<F>(&self, mut updater: F)
     where
         F: FnMut(&mut SimAgent),
     {
-        let mut agents = self.agents.write();
+        let mut agents = self.get_agents_mut();
         for agent in agents.iter_mut() {
             if agent.is_alive() {
                 updater(agent);
@@ -162,3 +239,123 @@ impl LifecycleLayer {
     }
 }
 
+/// Write access to the agent store; positions changed through it are re-indexed on drop
+pub struct AgentsMut<'a>(RwLockWriteGuard<'a, AgentStore>);
+
+impl Deref for AgentsMut<'_> {
+    type Target = AgentStore;
+
+    fn deref(&self) -> &AgentStore {
+        &self.0
+    }
+}
+
+impl DerefMut for AgentsMut<'_> {
+    fn deref_mut(&mut self) -> &mut AgentStore {
+        &mut self.0
+    }
+}
+
+impl Drop for AgentsMut<'_> {
+    fn drop(&mut self) {
+        self.0.sync_positions();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use world_sim_event_bus::{EventEnvelope, EventSubscriber};
+    use world_sim_core::{BlockType, GridCoord};
+
+    /// Records the type of every event it sees
+    #[derive(Default)]
+    struct Recorder {
+        seen: RwLock<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl EventSubscriber for Recorder {
+        async fn on_event(&self, event: &EventEnvelope) {
+            self.seen.write().push(event.event_type.clone());
+        }
+    }
+
+    fn layer_with_recorder() -> (LifecycleLayer, Arc<Recorder>) {
+        let bus = Arc::new(EventBus::new());
+        let recorder = Arc::new(Recorder::default());
+        bus.subscribe_all(recorder.clone());
+        (LifecycleLayer::with_rates(bus, 0.0, 0.0), recorder)
+    }
+
+    #[tokio::test]
+    async fn test_king_death_publishes_king_died() {
+        let (lifecycle, recorder) = layer_with_recorder();
+        let king = SimAgent::new_with_class("King".to_string(), Position::new(0.0, 1.0, 0.0), SocialClass::King);
+        let peasant = SimAgent::new_with_class("Peasant".to_string(), Position::new(1.0, 1.0, 0.0), SocialClass::Peasant);
+        let (king_id, peasant_id) = (king.id, peasant.id);
+        lifecycle.spawn_agent(king);
+        lifecycle.spawn_agent(peasant);
+
+        lifecycle.kill_agent(peasant_id, "Plague".to_string()).await;
+        assert_eq!(*recorder.seen.read(), ["AgentDied"]);
+
+        lifecycle.kill_agent(king_id, "Assassination".to_string()).await;
+        assert_eq!(*recorder.seen.read(), ["AgentDied", "KingDied", "AgentDied"]);
+    }
+
+    /// Looks the dead agent up in the store while the death is being published
+    struct Coroner {
+        agents: Arc<RwLock<AgentStore>>,
+        found_dead: RwLock<Vec<AgentId>>,
+    }
+
+    #[async_trait]
+    impl EventSubscriber for Coroner {
+        async fn on_event(&self, event: &EventEnvelope) {
+            let id: AgentId = serde_json::from_value(event.payload["agent_id"].clone()).unwrap();
+            if self.agents.read().get(id).is_some_and(|agent| !agent.is_alive()) {
+                self.found_dead.write().push(id);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deaths_publish_without_holding_the_agent_lock() {
+        let bus = Arc::new(EventBus::new());
+        let lifecycle = LifecycleLayer::with_rates(bus.clone(), 0.0, 1.0);
+        let coroner = Arc::new(Coroner {
+            agents: lifecycle.agents.clone(),
+            found_dead: RwLock::new(Vec::new()),
+        });
+        bus.subscribe("AgentDied", coroner.clone());
+
+        let first = SimAgent::new("First".to_string(), Position::new(0.0, 1.0, 0.0));
+        let second = SimAgent::new("Second".to_string(), Position::new(1.0, 1.0, 0.0));
+        let (first_id, second_id) = (first.id, second.id);
+        lifecycle.spawn_agent(first);
+        lifecycle.spawn_agent(second);
+
+        // Would deadlock if the write lock were still held while subscribers run
+        lifecycle.kill_agent(first_id, "Duel".to_string()).await;
+        assert_eq!(*coroner.found_dead.read(), [first_id]);
+
+        // Natural deaths go through the same path (death rate 1: everyone still alive dies)
+        lifecycle.tick(&GridLayer::new()).await;
+        assert_eq!(*coroner.found_dead.read(), [first_id, second_id]);
+    }
+
+    #[tokio::test]
+    async fn test_newborns_stand_on_the_ground() {
+        let lifecycle = LifecycleLayer::with_rates(Arc::new(EventBus::new()), 1.0, 0.0);
+        lifecycle.spawn_agent(SimAgent::new("Parent".to_string(), Position::new(0.0, 5.0, 0.0)));
+        let grid = GridLayer::new();
+        grid.fill_box(GridCoord::new(-100, 0, -100), GridCoord::new(100, 4, 100), BlockType::Grass);
+
+        lifecycle.tick(&grid).await;
+        let agents = lifecycle.agents();
+        let newborn = agents.iter().find(|a| a.name.starts_with("Citizen_")).unwrap();
+        assert_eq!(newborn.position.y, 5.0);
+    }
+}
@@ -1,7 +1,7 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use world_sim_core::Trait;
+use world_sim_core::{sim_rng, Trait};
 
 /// An agent's personality profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,7 +31,7 @@ impl PersonalityProfile {
 
     /// Generate a random personality
     pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
+        let mut rng = sim_rng();
         let mut profile = Self::new();
         
         // Randomly add 2-4 traits
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use world_sim_core::{AgentId, Position, SpatialIndex};
+use crate::SimAgent;
+
+/// Cell size of the agent index - about the combat engagement range
+const AGENT_CELL_SIZE: f32 = 16.0;
+
+/// Dense agent storage with an id index and a spatial index
+///
+/// Agents are kept in insertion order (systems iterate them in that order, so seeded runs
+/// stay reproducible) and looked up by id in O(1). Agent ids must not be changed through
+/// `iter_mut`/`get_mut` - the index is keyed on them. Positions changed that way reach the
+/// spatial index on the next `sync_positions` (the lifecycle write guard calls it on drop),
+/// which re-indexes only the agents handed out since.
+#[derive(Debug, Clone)]
+pub struct AgentStore {
+    agents: Vec<SimAgent>,
+    index: HashMap<AgentId, usize>,
+    spatial: SpatialIndex<AgentId>,
+    /// Agents handed out by `get_mut` since the last sync
+    touched: Vec<AgentId>,
+    /// Whether `iter_mut` handed out every agent since the last sync
+    all_touched: bool,
+}
+
+impl Default for AgentStore {
+    fn default() -> Self {
+        Self {
+            agents: Vec::new(),
+            index: HashMap::new(),
+            spatial: SpatialIndex::new(AGENT_CELL_SIZE),
+            touched: Vec::new(),
+            all_touched: false,
+        }
+    }
+}
+
+impl AgentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an agent (replacing any agent with the same id in place)
+    pub fn insert(&mut self, agent: SimAgent) {
+        self.spatial.insert(agent.id, agent.position);
+        match self.index.get(&agent.id) {
+            Some(&i) => self.agents[i] = agent,
+            None => {
+                self.index.insert(agent.id, self.agents.len());
+                self.agents.push(agent);
+            }
+        }
+    }
+
+    pub fn get(&self, id: AgentId) -> Option<&SimAgent> {
+        self.index.get(&id).map(|&i| &self.agents[i])
+    }
+
+    pub fn get_mut(&mut self, id: AgentId) -> Option<&mut SimAgent> {
+        let &i = self.index.get(&id)?;
+        self.touched.push(id);
+        Some(&mut self.agents[i])
+    }
+
+    pub fn contains(&self, id: AgentId) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.agents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.agents.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, SimAgent> {
+        self.agents.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, SimAgent> {
+        self.all_touched = true;
+        self.agents.iter_mut()
+    }
+
+    /// Living agents, in store order
+    pub fn living(&self) -> impl Iterator<Item = &SimAgent> {
+        self.agents.iter().filter(|a| a.is_alive())
+    }
+
+    /// Move an agent, keeping the spatial index in step
+    pub fn set_position(&mut self, id: AgentId, position: Position) {
+        if let Some(&i) = self.index.get(&id) {
+            self.agents[i].position = position;
+            self.spatial.insert(id, position);
+        }
+    }
+
+    /// Re-index agents whose positions may have changed through `iter_mut`/`get_mut`
+    pub fn sync_positions(&mut self) {
+        let touched = std::mem::take(&mut self.touched);
+        if std::mem::take(&mut self.all_touched) {
+            for agent in &self.agents {
+                self.spatial.insert(agent.id, agent.position);
+            }
+            return;
+        }
+        // Agents archived since they were handed out are no longer indexed
+        for id in touched {
+            if let Some(&i) = self.index.get(&id) {
+                self.spatial.insert(id, self.agents[i].position);
+            }
+        }
+    }
+
+    /// Agents within `radius` of a position (inclusive), in store order
+    pub fn within_radius(&self, center: &Position, radius: f32) -> Vec<&SimAgent> {
+        let mut found: Vec<usize> = self.spatial
+            .within_radius(center, radius)
+            .iter()
+            .map(|id| self.index[id])
+            .collect();
+        found.sort_unstable();
+        found.into_iter().map(|i| &self.agents[i]).collect()
+    }
+
+    /// Nearest agent accepted by `filter`, with its distance (ties go to the lower id)
+    pub fn nearest(&self, center: &Position, filter: impl Fn(&SimAgent) -> bool) -> Option<(&SimAgent, f32)> {
+        self.spatial
+            .nearest(center, |id| filter(&self.agents[self.index[&id]]))
+            .map(|(id, distance)| (&self.agents[self.index[&id]], distance))
+    }
+
+    pub fn spatial_index(&self) -> &SpatialIndex<AgentId> {
+        &self.spatial
+    }
+
+    /// All agents as a contiguous slice (e.g. for parallel iteration)
+    pub fn as_slice(&self) -> &[SimAgent] {
+        &self.agents
+    }
+
+    /// Remove dead agents, keeping the rest in order, and return the removed ones
+    pub fn remove_dead(&mut self) -> Vec<SimAgent> {
+        if self.agents.iter().all(|a| a.is_alive()) {
+            return Vec::new();
+        }
+        let (living, dead): (Vec<_>, Vec<_>) = std::mem::take(&mut self.agents)
+            .into_iter()
+            .partition(|a| a.is_alive());
+        self.agents = living;
+        self.reindex();
+        for agent in &dead {
+            self.spatial.remove(agent.id);
+        }
+        dead
+    }
+
+    fn reindex(&mut self) {
+        self.index = self.agents.iter().enumerate().map(|(i, a)| (a.id, i)).collect();
+    }
+}
+
+impl FromIterator<SimAgent> for AgentStore {
+    fn from_iter<I: IntoIterator<Item = SimAgent>>(iter: I) -> Self {
+        let mut store = Self::new();
+        for agent in iter {
+            store.insert(agent);
+        }
+        store
+    }
+}
+
+impl<'a> IntoIterator for &'a AgentStore {
+    type Item = &'a SimAgent;
+    type IntoIter = std::slice::Iter<'a, SimAgent>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.agents.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AgentState;
+    use world_sim_core::Position;
+
+    #[test]
+    fn test_lookup_and_remove_dead() {
+        let mut store: AgentStore = (0..5)
+            .map(|i| SimAgent::new(format!("Agent_{}", i), Position::new(i as f32, 0.0, 0.0)))
+            .collect();
+        let ids: Vec<AgentId> = store.iter().map(|a| a.id).collect();
+
+        store.get_mut(ids[1]).unwrap().state = AgentState::Dead;
+        store.get_mut(ids[3]).unwrap().state = AgentState::Dead;
+        let dead = store.remove_dead();
+
+        assert_eq!(dead.iter().map(|a| a.id).collect::<Vec<_>>(), vec![ids[1], ids[3]]);
+        assert_eq!(store.iter().map(|a| a.id).collect::<Vec<_>>(), vec![ids[0], ids[2], ids[4]]);
+        assert!(!store.contains(ids[1]));
+        assert_eq!(store.get(ids[4]).unwrap().position.x, 4.0);
+
+        // Moves made through iter_mut show up once synced
+        store.iter_mut().next().unwrap().position.x = 4.5;
+        store.sync_positions();
+        let near: Vec<AgentId> = store.within_radius(&Position::new(4.0, 0.0, 0.0), 1.0).iter().map(|a| a.id).collect();
+        assert_eq!(near, vec![ids[0], ids[4]]);
+        assert_eq!(store.spatial_index().len(), 3);
+
+        // And through get_mut, which re-indexes only the agents handed out
+        store.get_mut(ids[2]).unwrap().position.x = 9.0;
+        store.sync_positions();
+        assert_eq!(store.spatial_index().position(ids[2]).unwrap().x, 9.0);
+        assert_eq!(store.nearest(&Position::new(9.0, 0.0, 0.0), |_| true).unwrap().0.id, ids[2]);
+    }
+}
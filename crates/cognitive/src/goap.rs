@@ -238,7 +238,7 @@ mod tests {
 
         let planner = GOAPPlanner::new(actions);
 
-        let mut current_state = WorldState::new();
+        let current_state = WorldState::new();
         // Agent has nothing
 
         let goal = Goal::new("NotHungry");
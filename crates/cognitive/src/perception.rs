@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use world_sim_core::{AgentId, BlockType, GridCoord, ItemId, Position};
+use world_sim_world::GridLayer;
+
+/// Height of the eyes above an agent's position (agents see from, and are seen at, eye level)
+const EYE_HEIGHT: f32 = 1.6;
 
 /// Represents something perceptible in the world
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +76,9 @@ pub struct AgentPerception {
     pub sight_radius: f32,
     pub hearing_radius: f32,
     pub sight_cone_angle: f32, // In degrees
+    /// Direction the agent looks in (any length); `None` looks all around
+    #[serde(default)]
+    pub facing: Option<Position>,
     pub known_world: KnownWorld,
 }
 
@@ -106,6 +113,7 @@ impl AgentPerception {
             sight_radius: 50.0,
             hearing_radius: 100.0,
             sight_cone_angle: 120.0,
+            facing: None,
             known_world: KnownWorld {
                 known_agents: HashMap::new(),
                 known_items: HashMap::new(),
@@ -116,18 +124,25 @@ impl AgentPerception {
     }
 
     /// Process stimuli and update known world
+    ///
+    /// Visual stimuli must be within sight radius, inside the sight cone and not hidden behind
+    /// solid blocks of `grid`; sounds carry through walls.
     pub fn process_stimuli(
         &mut self,
         agent_position: Position,
         stimuli: &[Stimulus],
+        grid: &GridLayer,
         current_time: u64,
     ) {
+        let eye = Position::new(agent_position.x, agent_position.y + EYE_HEIGHT, agent_position.z);
+        let mut sighted = Vec::new();
         for stimulus in stimuli {
             match stimulus {
                 Stimulus::Visual { source, stimulus_type } => {
                     let distance = agent_position.distance_to(source);
-                    if distance <= self.sight_radius {
-                        self.process_visual_stimulus(stimulus_type, *source, current_time);
+                    let target = visual_target(stimulus_type, *source);
+                    if distance <= self.sight_radius && self.in_sight_cone(eye, target) {
+                        sighted.push((stimulus_type, *source, target));
                     }
                 }
                 Stimulus::Auditory { source, stimulus_type, loudness } => {
@@ -139,10 +154,29 @@ impl AgentPerception {
                 }
             }
         }
-        
+
+        let targets: Vec<Position> = sighted.iter().map(|(_, _, target)| *target).collect();
+        for ((stimulus_type, source, _), visible) in sighted.into_iter().zip(grid.visibility(eye, &targets)) {
+            if visible {
+                self.process_visual_stimulus(stimulus_type, source, current_time);
+            }
+        }
+
         self.known_world.last_updated = current_time;
     }
 
+    /// Whether a point lies within the sight cone around the facing direction
+    fn in_sight_cone(&self, eye: Position, target: Position) -> bool {
+        let Some(facing) = self.facing else {
+            return true;
+        };
+        let (facing, to_target) = (facing.to_vector3(), target.to_vector3() - eye.to_vector3());
+        if facing.norm() == 0.0 || to_target.norm() == 0.0 {
+            return true;
+        }
+        facing.angle(&to_target).to_degrees() <= self.sight_cone_angle / 2.0
+    }
+
     fn process_visual_stimulus(&mut self, stimulus: &VisualStimulus, source: Position, time: u64) {
         match stimulus {
             VisualStimulus::Agent(agent_id) => {
@@ -192,6 +226,15 @@ impl AgentPerception {
     }
 }
 
+/// Point an agent must see to perceive a visual stimulus: another agent's eyes, a block's center
+fn visual_target(stimulus: &VisualStimulus, source: Position) -> Position {
+    match stimulus {
+        VisualStimulus::Agent(_) => Position::new(source.x, source.y + EYE_HEIGHT, source.z),
+        VisualStimulus::Block(coord, _) => coord.to_position(),
+        VisualStimulus::Item(_) | VisualStimulus::Action(_) => source,
+    }
+}
+
 impl Default for AgentPerception {
     fn default() -> Self {
         Self::new()
@@ -213,8 +256,33 @@ mod tests {
             stimulus_type: VisualStimulus::Agent(other_agent),
         };
         
-        perception.process_stimuli(agent_pos, &[stimulus], 0);
+        perception.process_stimuli(agent_pos, &[stimulus], &GridLayer::new(), 0);
         assert!(perception.knows_agent(other_agent));
     }
+
+    #[test]
+    fn test_walls_and_sight_cone_hide_agents() {
+        let grid = GridLayer::new();
+        grid.fill_box(GridCoord::new(5, 0, -2), GridCoord::new(5, 3, 2), BlockType::WallStone);
+        let (behind_wall, beside, behind_back) = (AgentId::new(), AgentId::new(), AgentId::new());
+        let visual = |id, x, z| Stimulus::Visual {
+            source: Position::new(x, 0.0, z),
+            stimulus_type: VisualStimulus::Agent(id),
+        };
+        let stimuli = [visual(behind_wall, 10.0, 0.5), visual(beside, 10.0, 8.5), visual(behind_back, -10.0, 0.5)];
+
+        let mut perception = AgentPerception::new();
+        perception.facing = Some(Position::new(1.0, 0.0, 0.0));
+        perception.process_stimuli(Position::new(0.5, 0.0, 0.5), &stimuli, &grid, 0);
+        assert!(!perception.knows_agent(behind_wall));
+        assert!(perception.knows_agent(beside));
+        assert!(!perception.knows_agent(behind_back));
+
+        // Walls still hide agents from someone who looks all around
+        perception.facing = None;
+        perception.process_stimuli(Position::new(0.5, 0.0, 0.5), &stimuli, &grid, 1);
+        assert!(!perception.knows_agent(behind_wall));
+        assert!(perception.knows_agent(behind_back));
+    }
 }
 
@@ -2,13 +2,13 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Strongly-typed ID wrappers to prevent mixing different entity types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct AgentId(pub Uuid);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ItemId(pub Uuid);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct FactionId(pub Uuid);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -16,19 +16,19 @@ pub struct ChunkId(pub i32, pub i32, pub i32);
 
 impl AgentId {
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(crate::sim_uuid())
     }
 }
 
 impl ItemId {
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(crate::sim_uuid())
     }
 }
 
 impl FactionId {
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(crate::sim_uuid())
     }
 }
 
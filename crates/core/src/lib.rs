@@ -2,9 +2,13 @@
 pub mod types;
 pub mod ids;
 pub mod spatial;
+pub mod spatial_index;
 pub mod math;
+pub mod rng;
 
 pub use types::*;
 pub use ids::*;
 pub use spatial::*;
+pub use spatial_index::SpatialIndex;
+pub use rng::{seed_rng, sim_rng, sim_uuid, stream_rng, SimRng};
 
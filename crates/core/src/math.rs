@@ -1,4 +1,4 @@
-/// Mathematical utilities for the simulation
+//! Mathematical utilities for the simulation
 
 /// Linear interpolation
 pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
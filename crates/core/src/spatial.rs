@@ -21,6 +21,13 @@ impl Position {
         (dx * dx + dy * dy + dz * dz).sqrt()
     }
 
+    /// Distance in the x-z plane, ignoring height
+    pub fn horizontal_distance_to(&self, other: &Position) -> f32 {
+        let dx = self.x - other.x;
+        let dz = self.z - other.z;
+        (dx * dx + dz * dz).sqrt()
+    }
+
     pub fn to_vector3(&self) -> Vector3<f32> {
         Vector3::new(self.x, self.y, self.z)
     }
@@ -35,7 +42,7 @@ impl Position {
 }
 
 /// Integer grid coordinate
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct GridCoord {
     pub x: i32,
     pub y: i32,
@@ -69,7 +76,7 @@ impl GridCoord {
 }
 
 /// Chunk coordinate for spatial partitioning
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ChunkCoord {
     pub x: i32,
     pub y: i32,
@@ -0,0 +1,271 @@
+use ahash::AHashMap;
+use std::cmp::Ordering;
+use std::hash::Hash;
+use crate::{BoundingBox, Position};
+
+/// Horizontal (x, z) grid cell
+type Cell = (i32, i32);
+
+/// Uniform hash grid over entity positions for proximity queries
+///
+/// Cells partition the ground plane (x, z); distances are full 3D `Position::distance_to`.
+/// Entries are updated in place with `insert` as entities move - only a change of cell
+/// touches the grid. Query results never depend on hash order: ties and listings are
+/// ordered by key.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex<K> {
+    cell_size: f32,
+    cells: AHashMap<Cell, Vec<K>>,
+    entries: AHashMap<K, (Position, Cell)>,
+}
+
+impl<K: Copy + Eq + Hash + Ord> SpatialIndex<K> {
+    /// Create an index; pick a cell size near the typical query radius
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "cell size must be positive");
+        Self {
+            cell_size,
+            cells: AHashMap::new(),
+            entries: AHashMap::new(),
+        }
+    }
+
+    fn cell_of(&self, position: &Position) -> Cell {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Add an entry, or move it if the key is already indexed
+    pub fn insert(&mut self, key: K, position: Position) {
+        let cell = self.cell_of(&position);
+        match self.entries.insert(key, (position, cell)) {
+            Some((_, old)) if old == cell => {}
+            Some((_, old)) => {
+                self.detach(key, old);
+                self.cells.entry(cell).or_default().push(key);
+            }
+            None => self.cells.entry(cell).or_default().push(key),
+        }
+    }
+
+    /// Remove an entry, returning its last position
+    pub fn remove(&mut self, key: K) -> Option<Position> {
+        let (position, cell) = self.entries.remove(&key)?;
+        self.detach(key, cell);
+        Some(position)
+    }
+
+    fn detach(&mut self, key: K, cell: Cell) {
+        if let Some(keys) = self.cells.get_mut(&cell) {
+            if let Some(i) = keys.iter().position(|k| *k == key) {
+                keys.swap_remove(i);
+            }
+            if keys.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    pub fn position(&self, key: K) -> Option<Position> {
+        self.entries.get(&key).map(|(position, _)| *position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Keys within `radius` of `center` (inclusive), sorted by key
+    pub fn within_radius(&self, center: &Position, radius: f32) -> Vec<K> {
+        let min = self.cell_of(&Position::new(center.x - radius, center.y, center.z - radius));
+        let max = self.cell_of(&Position::new(center.x + radius, center.y, center.z + radius));
+        let mut keys = Vec::new();
+        self.for_each_in_cells(min, max, |key, position| {
+            if position.distance_to(center) <= radius {
+                keys.push(key);
+            }
+        });
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Keys inside a bounding box (inclusive), sorted by key
+    pub fn within_box(&self, bounds: &BoundingBox) -> Vec<K> {
+        let min = self.cell_of(&bounds.min);
+        let max = self.cell_of(&bounds.max);
+        let mut keys = Vec::new();
+        self.for_each_in_cells(min, max, |key, position| {
+            if bounds.contains(position) {
+                keys.push(key);
+            }
+        });
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Nearest key accepted by `filter`, with its distance
+    pub fn nearest(&self, center: &Position, filter: impl FnMut(K) -> bool) -> Option<(K, f32)> {
+        self.k_nearest(center, 1, filter).into_iter().next()
+    }
+
+    /// Up to `k` nearest keys accepted by `filter`, closest first (equal distances by key)
+    pub fn k_nearest(&self, center: &Position, k: usize, mut filter: impl FnMut(K) -> bool) -> Vec<(K, f32)> {
+        let mut best: Vec<(K, f32)> = Vec::with_capacity(k + 1);
+        if k == 0 {
+            return best;
+        }
+
+        let mut consider = |key: K, position: &Position, best: &mut Vec<(K, f32)>| {
+            if !filter(key) {
+                return;
+            }
+            let distance = position.distance_to(center);
+            let at = best.partition_point(|&(other, d)| by_distance((other, d), (key, distance)) == Ordering::Less);
+            if at < k {
+                best.insert(at, (key, distance));
+                best.truncate(k);
+            }
+        };
+
+        // Search rings of cells outwards from the center's cell. Anything beyond ring r is at
+        // least r cells away, so stop once the k-th best is closer than that.
+        let origin = self.cell_of(center);
+        let mut seen = 0;
+        let mut ring = 0;
+        while seen < self.entries.len() {
+            let side = 2 * ring as i64 + 1;
+            if side * side > self.cells.len() as i64 {
+                // Sparse grid - cheaper to visit the remaining occupied cells directly
+                for (cell, keys) in &self.cells {
+                    if (cell.0 - origin.0).abs().max((cell.1 - origin.1).abs()) >= ring {
+                        for key in keys {
+                            consider(*key, &self.entries[key].0, &mut best);
+                        }
+                    }
+                }
+                break;
+            }
+
+            for cell in ring_cells(origin, ring) {
+                if let Some(keys) = self.cells.get(&cell) {
+                    seen += keys.len();
+                    for key in keys {
+                        consider(*key, &self.entries[key].0, &mut best);
+                    }
+                }
+            }
+
+            if best.len() == k && best[k - 1].1 < ring as f32 * self.cell_size {
+                break;
+            }
+            ring += 1;
+        }
+
+        best
+    }
+
+    /// Visit entries in the cell rectangle `min..=max`
+    fn for_each_in_cells(&self, min: Cell, max: Cell, mut visit: impl FnMut(K, &Position)) {
+        let area = (max.0 as i64 - min.0 as i64 + 1) * (max.1 as i64 - min.1 as i64 + 1);
+        let mut visit_cell = |keys: &Vec<K>| {
+            for key in keys {
+                visit(*key, &self.entries[key].0);
+            }
+        };
+
+        if area > self.cells.len() as i64 {
+            for (cell, keys) in &self.cells {
+                if (min.0..=max.0).contains(&cell.0) && (min.1..=max.1).contains(&cell.1) {
+                    visit_cell(keys);
+                }
+            }
+        } else {
+            for x in min.0..=max.0 {
+                for z in min.1..=max.1 {
+                    if let Some(keys) = self.cells.get(&(x, z)) {
+                        visit_cell(keys);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn by_distance<K: Ord>(a: (K, f32), b: (K, f32)) -> Ordering {
+    a.1.total_cmp(&b.1).then(a.0.cmp(&b.0))
+}
+
+/// Cells at Chebyshev distance exactly `ring` from `origin`
+fn ring_cells(origin: Cell, ring: i32) -> Vec<Cell> {
+    if ring == 0 {
+        return vec![origin];
+    }
+    let mut cells = Vec::with_capacity(8 * ring as usize);
+    for d in -ring..=ring {
+        cells.push((origin.0 + d, origin.1 - ring));
+        cells.push((origin.0 + d, origin.1 + ring));
+    }
+    for d in (-ring + 1)..ring {
+        cells.push((origin.0 - ring, origin.1 + d));
+        cells.push((origin.0 + ring, origin.1 + d));
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sim_rng, seed_rng};
+    use rand::Rng;
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        seed_rng(5);
+        let mut index = SpatialIndex::new(8.0);
+        let mut points: Vec<(u32, Position)> = (0..500)
+            .map(|i| (i, Position::new(sim_rng().gen_range(-100.0..100.0), 1.0, sim_rng().gen_range(-100.0..100.0))))
+            .collect();
+        for (key, position) in &points {
+            index.insert(*key, *position);
+        }
+
+        // Move some entries across cells and drop others
+        for (key, position) in points.iter_mut().take(50) {
+            position.x += 37.0;
+            index.insert(*key, *position);
+        }
+        for (key, _) in points.drain(450..) {
+            index.remove(key);
+        }
+        assert_eq!(index.len(), 450);
+
+        let center = Position::new(3.0, 1.0, -7.0);
+        let mut expected: Vec<u32> = points.iter().filter(|(_, p)| p.distance_to(&center) <= 20.0).map(|(k, _)| *k).collect();
+        expected.sort_unstable();
+        assert_eq!(index.within_radius(&center, 20.0), expected);
+
+        let mut by_dist: Vec<(u32, f32)> = points.iter().filter(|(k, _)| k % 3 == 0).map(|(k, p)| (*k, p.distance_to(&center))).collect();
+        by_dist.sort_by(|a, b| by_distance(*a, *b));
+        assert_eq!(index.k_nearest(&center, 5, |k| k % 3 == 0), by_dist[..5].to_vec());
+
+        // Far outside the populated area
+        let far = Position::new(500.0, 1.0, 500.0);
+        let closest = points.iter().min_by(|a, b| a.1.distance_to(&far).total_cmp(&b.1.distance_to(&far)));
+        assert_eq!(index.nearest(&far, |_| true).map(|(k, _)| k), closest.map(|(k, _)| *k));
+
+        let bounds = BoundingBox { min: Position::new(-10.0, 0.0, -10.0), max: Position::new(10.0, 2.0, 10.0) };
+        let mut inside: Vec<u32> = points.iter().filter(|(_, p)| bounds.contains(p)).map(|(k, _)| *k).collect();
+        inside.sort_unstable();
+        assert_eq!(index.within_box(&bounds), inside);
+    }
+}
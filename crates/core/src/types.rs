@@ -25,10 +25,15 @@ impl BlockType {
     pub fn is_walkable(&self) -> bool {
         matches!(self, BlockType::Air | BlockType::Grass)
     }
+
+    /// Whether fire can catch on this block
+    pub fn is_flammable(&self) -> bool {
+        matches!(self, BlockType::Wood | BlockType::WallWood)
+    }
 }
 
 /// Resource types for the economy
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ResourceType {
     Wood,
     Stone,
@@ -1,9 +1,8 @@
 use crate::{Event, EventEnvelope};
 use async_trait::async_trait;
 use parking_lot::RwLock;
-use std::any::TypeId;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::mpsc;
 
 /// Trait for event subscribers
@@ -15,6 +14,9 @@ pub trait EventSubscriber: Send + Sync {
 /// Type-erased subscriber
 type BoxedSubscriber = Arc<dyn EventSubscriber>;
 
+/// Subscription key that receives every event type
+pub const ALL_EVENTS: &str = "*";
+
 /// The global event bus - singleton managing all pub/sub
 pub struct EventBus {
     subscribers: RwLock<HashMap<String, Vec<BoxedSubscriber>>>,
@@ -38,10 +40,27 @@ impl EventBus {
     pub fn subscribe(&self, event_type: &str, subscriber: BoxedSubscriber) {
         let mut subs = self.subscribers.write();
         subs.entry(event_type.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(subscriber);
     }
 
+    /// Subscribe to every event regardless of type
+    pub fn subscribe_all(&self, subscriber: BoxedSubscriber) {
+        self.subscribe(ALL_EVENTS, subscriber);
+    }
+
+    /// Subscribers for an event type, including wildcard subscribers
+    fn subscribers_for(&self, event_type: &str) -> Vec<BoxedSubscriber> {
+        let subs = self.subscribers.read();
+        let mut matching = subs.get(event_type).cloned().unwrap_or_default();
+        if event_type != ALL_EVENTS {
+            if let Some(all) = subs.get(ALL_EVENTS) {
+                matching.extend(all.iter().cloned());
+            }
+        }
+        matching
+    }
+
     /// Publish an event to all subscribers
     pub async fn publish<E: Event + serde::Serialize>(&self, event: &E) {
         let event_type = event.event_type();
@@ -60,29 +79,15 @@ impl EventBus {
         }
 
         // Notify subscribers
-        let subscribers = {
-            let subs = self.subscribers.read();
-            subs.get(event_type).cloned()
-        };
-
-        if let Some(subscribers) = subscribers {
-            for subscriber in subscribers {
-                subscriber.on_event(&envelope).await;
-            }
+        for subscriber in self.subscribers_for(event_type) {
+            subscriber.on_event(&envelope).await;
         }
     }
 
     /// Publish a raw envelope (for replaying history)
     pub async fn publish_envelope(&self, envelope: EventEnvelope) {
-        let subscribers = {
-            let subs = self.subscribers.read();
-            subs.get(&envelope.event_type).cloned()
-        };
-
-        if let Some(subscribers) = subscribers {
-            for subscriber in subscribers {
-                subscriber.on_event(&envelope).await;
-            }
+        for subscriber in self.subscribers_for(&envelope.event_type) {
+            subscriber.on_event(&envelope).await;
         }
     }
 
@@ -100,16 +105,10 @@ impl Default for EventBus {
 }
 
 /// Global static event bus instance
-static mut EVENT_BUS: Option<Arc<EventBus>> = None;
-static INIT: std::sync::Once = std::sync::Once::new();
+static EVENT_BUS: OnceLock<Arc<EventBus>> = OnceLock::new();
 
 pub fn get_event_bus() -> Arc<EventBus> {
-    unsafe {
-        INIT.call_once(|| {
-            EVENT_BUS = Some(Arc::new(EventBus::new()));
-        });
-        EVENT_BUS.as_ref().unwrap().clone()
-    }
+    EVENT_BUS.get_or_init(|| Arc::new(EventBus::new())).clone()
 }
 
 #[cfg(test)]
@@ -141,5 +140,23 @@ mod tests {
         // For now, just verify the structure works
         assert_eq!(bus.subscriber_count("test_event"), 1);
     }
+
+    #[tokio::test]
+    async fn test_subscribe_all() {
+        let bus = EventBus::new();
+        let received = Arc::new(RwLock::new(Vec::new()));
+        bus.subscribe_all(Arc::new(TestSubscriber {
+            received: received.clone(),
+        }));
+
+        bus.publish_envelope(EventEnvelope::new(
+            "Custom".to_string(),
+            "test".to_string(),
+            serde_json::Value::Null,
+        ))
+        .await;
+
+        assert_eq!(*received.read(), vec!["Custom".to_string()]);
+    }
 }
 
@@ -70,6 +70,23 @@ impl Event for TradeExecutedEvent {
     }
 }
 
+/// Inflation rose sharply since the last spike (or since it was lowest)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InflationSpikeEvent {
+    pub previous_rate: f64,
+    pub inflation_rate: f64,
+    pub total_supply: f64,
+}
+
+impl Event for InflationSpikeEvent {
+    fn event_type(&self) -> &'static str {
+        "InflationSpike"
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
 // ===== Political Events =====
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +121,23 @@ impl Event for PeaceTreatyEvent {
     }
 }
 
+/// A king died; published alongside their `AgentDied`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KingDiedEvent {
+    pub king_id: AgentId,
+    pub cause: String,
+    pub location: Position,
+}
+
+impl Event for KingDiedEvent {
+    fn event_type(&self) -> &'static str {
+        "KingDied"
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
 // ===== Environmental Events =====
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +172,21 @@ impl Event for DroughtStartedEvent {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WildfireStartedEvent {
+    pub center: Position,
+    pub radius: f32,
+}
+
+impl Event for WildfireStartedEvent {
+    fn event_type(&self) -> &'static str {
+        "WildfireStarted"
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SeasonChangeEvent {
     pub old_season: Season,
@@ -153,8 +202,9 @@ impl Event for SeasonChangeEvent {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Season {
+    #[default]
     Spring,
     Summer,
     Autumn,
@@ -1,7 +1,9 @@
 /// Event Bus - The central nervous system for macro-level communication
 mod events;
 mod bus;
+mod webhooks;
 
 pub use events::*;
 pub use bus::*;
+pub use webhooks::*;
 
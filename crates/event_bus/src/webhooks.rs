@@ -0,0 +1,391 @@
+use crate::{EventEnvelope, EventSubscriber};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Header carrying `sha256=<hex HMAC of the body>` when a hook has a secret
+pub const SIGNATURE_HEADER: &str = "X-WorldSim-Signature";
+/// Header carrying the event type
+pub const EVENT_TYPE_HEADER: &str = "X-WorldSim-Event";
+/// Header carrying the event id (stable across retries)
+pub const DELIVERY_HEADER: &str = "X-WorldSim-Delivery";
+
+/// Maximum number of dead letters kept in memory (oldest dropped first)
+const MAX_DEAD_LETTERS: usize = 1000;
+
+/// An outgoing webhook registration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    /// Event types to deliver (empty = every event)
+    pub event_types: Vec<String>,
+    /// Shared secret for HMAC-SHA256 signing (never serialized back out)
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+}
+
+impl Webhook {
+    pub fn new(url: String, event_types: Vec<String>, secret: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            url,
+            event_types,
+            secret,
+        }
+    }
+
+    pub fn matches(&self, event_type: &str) -> bool {
+        self.event_types.is_empty() || self.event_types.iter().any(|t| t == event_type)
+    }
+}
+
+/// Retry schedule for failed deliveries
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub timeout: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (1-based), doubling each time
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// A delivery that exhausted its retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub url: String,
+    pub event: EventEnvelope,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Event bus subscriber that POSTs matching events to registered webhooks
+///
+/// Deliveries run on spawned tasks so a slow endpoint never stalls `publish`.
+pub struct WebhookDispatcher {
+    hooks: RwLock<Vec<Webhook>>,
+    dead_letters: Arc<RwLock<VecDeque<DeadLetter>>>,
+    retry: RetryPolicy,
+    http: reqwest::Client,
+}
+
+impl WebhookDispatcher {
+    pub fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::default())
+    }
+
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
+        Self {
+            hooks: RwLock::new(Vec::new()),
+            dead_letters: Arc::new(RwLock::new(VecDeque::new())),
+            retry,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Register a webhook, returning its id
+    pub fn register(&self, webhook: Webhook) -> Uuid {
+        let id = webhook.id;
+        tracing::info!("🪝 Webhook registered: {} ({:?})", webhook.url, webhook.event_types);
+        self.hooks.write().push(webhook);
+        id
+    }
+
+    pub fn unregister(&self, id: Uuid) -> Option<Webhook> {
+        let mut hooks = self.hooks.write();
+        let index = hooks.iter().position(|h| h.id == id)?;
+        Some(hooks.remove(index))
+    }
+
+    pub fn list(&self) -> Vec<Webhook> {
+        self.hooks.read().clone()
+    }
+
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.read().iter().cloned().collect()
+    }
+
+    pub fn clear_dead_letters(&self) -> usize {
+        let mut dead_letters = self.dead_letters.write();
+        let count = dead_letters.len();
+        dead_letters.clear();
+        count
+    }
+
+    /// Re-deliver a dead letter; it is dead-lettered again if retries fail.
+    /// Returns `false` if the dead letter or its webhook no longer exists; a letter whose
+    /// webhook was unregistered stays queued.
+    pub fn retry_dead_letter(&self, id: Uuid) -> bool {
+        let hooks = self.hooks.read();
+        let mut dead_letters = self.dead_letters.write();
+        let Some(index) = dead_letters.iter().position(|d| d.id == id) else { return false };
+        let Some(hook) = hooks.iter().find(|h| h.id == dead_letters[index].webhook_id).cloned() else {
+            return false;
+        };
+        let letter = dead_letters.remove(index).expect("index was just found");
+        drop(dead_letters);
+        drop(hooks);
+
+        self.spawn_delivery(hook, letter.event);
+        true
+    }
+
+    fn spawn_delivery(&self, hook: Webhook, event: EventEnvelope) {
+        let http = self.http.clone();
+        let retry = self.retry;
+        let dead_letters = self.dead_letters.clone();
+        tokio::spawn(async move {
+            if let Err((attempts, last_error)) = deliver(&http, &retry, &hook, &event).await {
+                tracing::warn!("🪝 Webhook {} gave up on {} after {} attempts: {}", hook.url, event.event_type, attempts, last_error);
+                let mut dead_letters = dead_letters.write();
+                if dead_letters.len() >= MAX_DEAD_LETTERS {
+                    dead_letters.pop_front();
+                }
+                dead_letters.push_back(DeadLetter {
+                    id: Uuid::new_v4(),
+                    webhook_id: hook.id,
+                    url: hook.url,
+                    event,
+                    attempts,
+                    last_error,
+                    failed_at: Utc::now(),
+                });
+            }
+        });
+    }
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for WebhookDispatcher {
+    async fn on_event(&self, event: &EventEnvelope) {
+        let hooks: Vec<Webhook> = self
+            .hooks
+            .read()
+            .iter()
+            .filter(|h| h.matches(&event.event_type))
+            .cloned()
+            .collect();
+        for hook in hooks {
+            self.spawn_delivery(hook, event.clone());
+        }
+    }
+}
+
+/// Hex HMAC-SHA256 of `body`, as sent in the signature header (after `sha256=`)
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// POST one event with retries; on failure returns `(attempts, last error)`
+async fn deliver(
+    http: &reqwest::Client,
+    retry: &RetryPolicy,
+    hook: &Webhook,
+    event: &EventEnvelope,
+) -> Result<(), (u32, String)> {
+    let body = serde_json::to_vec(event).map_err(|e| (0, e.to_string()))?;
+    let signature = hook.secret.as_deref().map(|secret| format!("sha256={}", sign(secret, &body)));
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let mut request = http
+            .post(&hook.url)
+            .timeout(retry.timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_TYPE_HEADER, &event.event_type)
+            .header(DELIVERY_HEADER, event.id.to_string())
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        let (error, retryable) = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                // Client errors won't succeed on retry (except rate limiting)
+                let retryable = status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+                (format!("HTTP {}", status.as_u16()), retryable)
+            }
+            Err(e) => (e.to_string(), true),
+        };
+
+        if !retryable || attempt >= retry.max_attempts {
+            return Err((attempt, error));
+        }
+        tokio::time::sleep(retry.backoff(attempt)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventBus;
+    use axum::{extract::State, http::HeaderMap, http::StatusCode, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// (body, signature header) pairs
+    type Received = Arc<RwLock<Vec<(String, Option<String>)>>>;
+
+    /// Stand-in receiver: fails the first `failures` requests, then records bodies and signatures
+    #[derive(Clone, Default)]
+    struct Receiver {
+        failures: usize,
+        calls: Arc<AtomicUsize>,
+        received: Received,
+    }
+
+    async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: String) -> StatusCode {
+        if receiver.calls.fetch_add(1, Ordering::SeqCst) < receiver.failures {
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+        let signature = headers.get(SIGNATURE_HEADER).map(|v| v.to_str().unwrap().to_string());
+        receiver.received.write().push((body, signature));
+        StatusCode::OK
+    }
+
+    async fn spawn_receiver(receiver: Receiver) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/hook", post(receive)).with_state(receiver);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/hook", addr)
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            timeout: Duration::from_secs(2),
+        }
+    }
+
+    fn envelope(event_type: &str) -> EventEnvelope {
+        EventEnvelope::new(event_type.to_string(), "test".to_string(), serde_json::json!({ "n": 1 }))
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("condition not met in time");
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let retry = fast_retry();
+        assert_eq!(retry.backoff(1), Duration::from_millis(10));
+        assert_eq!(retry.backoff(3), Duration::from_millis(40));
+        assert_eq!(retry.backoff(10), Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_filtered_signed_delivery_with_retry() {
+        let receiver = Receiver {
+            failures: 1,
+            ..Default::default()
+        };
+        let url = spawn_receiver(receiver.clone()).await;
+
+        let bus = EventBus::new();
+        let dispatcher = Arc::new(WebhookDispatcher::with_retry_policy(fast_retry()));
+        dispatcher.register(Webhook::new(url, vec!["WarDeclared".to_string()], Some("s3cret".to_string())));
+        bus.subscribe_all(dispatcher.clone());
+
+        bus.publish_envelope(envelope("PriceChange")).await;
+        bus.publish_envelope(envelope("WarDeclared")).await;
+
+        wait_until(|| !receiver.received.read().is_empty()).await;
+        let (body, signature) = receiver.received.read()[0].clone();
+        let event: EventEnvelope = serde_json::from_str(&body).unwrap();
+        assert_eq!(event.event_type, "WarDeclared");
+        assert_eq!(signature, Some(format!("sha256={}", sign("s3cret", body.as_bytes()))));
+        // One 503, one success, and the filtered event was never sent
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 2);
+        assert!(dispatcher.dead_letters().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_dead_letter() {
+        let receiver = Receiver {
+            failures: usize::MAX,
+            ..Default::default()
+        };
+        let url = spawn_receiver(receiver.clone()).await;
+
+        let dispatcher = WebhookDispatcher::with_retry_policy(fast_retry());
+        dispatcher.register(Webhook::new(url, Vec::new(), None));
+        dispatcher.on_event(&envelope("AgentDied")).await;
+
+        wait_until(|| !dispatcher.dead_letters().is_empty()).await;
+        let letter = dispatcher.dead_letters().remove(0);
+        assert_eq!(letter.attempts, 3);
+        assert_eq!(letter.last_error, "HTTP 503");
+        assert_eq!(receiver.calls.load(Ordering::SeqCst), 3);
+
+        assert!(dispatcher.retry_dead_letter(letter.id));
+        wait_until(|| receiver.calls.load(Ordering::SeqCst) == 6 && !dispatcher.dead_letters().is_empty()).await;
+    }
+
+    #[tokio::test]
+    async fn test_retry_keeps_letter_of_unregistered_hook() {
+        let receiver = Receiver {
+            failures: usize::MAX,
+            ..Default::default()
+        };
+        let url = spawn_receiver(receiver.clone()).await;
+
+        let dispatcher = WebhookDispatcher::with_retry_policy(fast_retry());
+        let hook_id = dispatcher.register(Webhook::new(url, Vec::new(), None));
+        dispatcher.on_event(&envelope("KingDied")).await;
+        wait_until(|| !dispatcher.dead_letters().is_empty()).await;
+
+        dispatcher.unregister(hook_id);
+        let letter = dispatcher.dead_letters().remove(0);
+        assert!(!dispatcher.retry_dead_letter(letter.id));
+        assert_eq!(dispatcher.dead_letters().len(), 1);
+        assert_eq!(dispatcher.dead_letters()[0].id, letter.id);
+    }
+}
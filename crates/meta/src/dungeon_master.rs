@@ -1,16 +1,73 @@
 use parking_lot::RwLock;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use world_sim_core::{Position, ResourceType};
-use world_sim_event_bus::{BlightStartedEvent, DungeonMasterEvent, DroughtStartedEvent, EventBus};
+use world_sim_core::{sim_rng, Position, ResourceType};
+use world_sim_event_bus::{BlightStartedEvent, DungeonMasterEvent, DroughtStartedEvent, EventBus, WildfireStartedEvent};
 
 /// The Dungeon Master - AI storyteller that injects drama
 pub struct DungeonMaster {
     event_bus: Arc<EventBus>,
     metrics: Arc<RwLock<WorldMetrics>>,
     story_events: Vec<StoryEvent>,
-    boredom_threshold: f32,
+    settings: RwLock<DungeonMasterSettings>,
+    clock: RwLock<f32>, // Seconds the DM has been ticking
+    last_triggered: RwLock<HashMap<String, f32>>, // Event id -> clock time it last fired
+}
+
+/// Tunable Dungeon Master settings (adjustable at runtime from the Admin API)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DungeonMasterSettings {
+    pub enabled: bool,
+    pub boredom_threshold: f32,
+}
+
+impl Default for DungeonMasterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            boredom_threshold: 0.1, // Lowered from 0.3 for more frequent events
+        }
+    }
+}
+
+/// Optional overrides applied when manually triggering a story event
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventOverrides {
+    pub center: Option<Position>,
+    pub radius: Option<f32>,
+    pub severity: Option<f32>,
+}
+
+impl EventOverrides {
+    /// Whether every given override is usable: a finite center, and a finite, non-negative
+    /// radius and severity
+    pub fn is_valid(&self) -> bool {
+        let usable = |value: f32| value.is_finite() && value >= 0.0;
+        self.center.is_none_or(|c| c.x.is_finite() && c.y.is_finite() && c.z.is_finite())
+            && self.radius.is_none_or(usable)
+            && self.severity.is_none_or(usable)
+    }
+}
+
+/// The Dungeon Master's running state (see [`DungeonMaster::state`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DungeonMasterState {
+    pub settings: DungeonMasterSettings,
+    pub metrics: WorldMetrics,
+    /// Seconds the DM has been ticking
+    pub clock: f32,
+    /// Event id -> clock time it last fired
+    pub last_triggered: BTreeMap<String, f32>,
+}
+
+/// A story event together with its remaining cooldown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoryEventStatus {
+    #[serde(flatten)]
+    pub event: StoryEvent,
+    pub cooldown_remaining: f32,
 }
 
 /// Tracks world state for boredom detection
@@ -63,7 +120,9 @@ impl DungeonMaster {
             event_bus,
             metrics: Arc::new(RwLock::new(WorldMetrics::default())),
             story_events,
-            boredom_threshold: 0.1, // Lowered from 0.3 for more frequent events
+            settings: RwLock::new(DungeonMasterSettings::default()),
+            clock: RwLock::new(0.0),
+            last_triggered: RwLock::new(HashMap::new()),
         }
     }
 
@@ -113,6 +172,15 @@ impl DungeonMaster {
                 },
                 cooldown: 1200.0,
             },
+            StoryEvent {
+                id: "wildfire".to_string(),
+                name: "Wildfire".to_string(),
+                description: "Fire sweeps through the woods and anything wooden in its path.".to_string(),
+                impact: ImpactType::NaturalDisaster {
+                    disaster_type: "wildfire".to_string(),
+                },
+                cooldown: 900.0,
+            },
             StoryEvent {
                 id: "gold_discovery".to_string(),
                 name: "Gold Discovery".to_string(),
@@ -166,31 +234,53 @@ impl DungeonMaster {
             let mut metrics = self.metrics.write();
             metrics.time_since_last_event += delta_time;
         }
+        *self.clock.write() += delta_time;
+
+        let settings = self.get_settings();
+        if !settings.enabled {
+            return;
+        }
 
         let boredom = self.calculate_boredom();
         
-        if boredom > self.boredom_threshold {
+        if boredom > settings.boredom_threshold {
             // The world is boring, inject some drama!
             self.inject_random_event().await;
         }
     }
 
-    /// Inject a random story event
+    /// Inject a random story event that is off cooldown
     pub async fn inject_random_event(&self) {
-        let mut rng = rand::thread_rng();
-        let event_index = rng.gen_range(0..self.story_events.len());
-        let event = &self.story_events[event_index];
+        let ready: Vec<&StoryEvent> = self
+            .story_events
+            .iter()
+            .filter(|e| self.cooldown_remaining(e) <= 0.0)
+            .collect();
+        if ready.is_empty() {
+            return;
+        }
+
+        let mut rng = sim_rng();
+        let event = ready[rng.gen_range(0..ready.len())];
 
         self.inject_event(event).await;
     }
 
     /// Inject a specific story event
     pub async fn inject_event(&self, event: &StoryEvent) {
-        // Reset time counter
+        self.inject_event_with_overrides(event, &EventOverrides::default())
+            .await;
+    }
+
+    /// Inject a specific story event, overriding its location and severity
+    pub async fn inject_event_with_overrides(&self, event: &StoryEvent, overrides: &EventOverrides) {
+        // Reset time counter and start the cooldown
         {
             let mut metrics = self.metrics.write();
             metrics.time_since_last_event = 0.0;
         }
+        let now = *self.clock.read();
+        self.last_triggered.write().insert(event.id.clone(), now);
 
         // Publish DM event
         self.event_bus
@@ -206,8 +296,8 @@ impl DungeonMaster {
             ImpactType::Blight { resource } => {
                 self.event_bus
                     .publish(&BlightStartedEvent {
-                        center: Position::new(0.0, 0.0, 0.0), // TODO: Choose strategically
-                        radius: 100.0,
+                        center: overrides.center.unwrap_or(Position::new(0.0, 0.0, 0.0)), // TODO: Choose strategically
+                        radius: overrides.radius.unwrap_or(100.0),
                         affected_resource: *resource,
                     })
                     .await;
@@ -216,7 +306,7 @@ impl DungeonMaster {
                 self.event_bus
                     .publish(&DroughtStartedEvent {
                         region: "global".to_string(),
-                        severity: *severity,
+                        severity: overrides.severity.unwrap_or(*severity),
                         expected_duration_days: 30,
                     })
                     .await;
@@ -227,6 +317,14 @@ impl DungeonMaster {
             ImpactType::Uprising { region: _ } => {
                 // TODO: Implement uprising system
             }
+            ImpactType::NaturalDisaster { disaster_type } if disaster_type == "wildfire" => {
+                self.event_bus
+                    .publish(&WildfireStartedEvent {
+                        center: overrides.center.unwrap_or(Position::new(0.0, 0.0, 0.0)),
+                        radius: overrides.radius.unwrap_or(15.0),
+                    })
+                    .await;
+            }
             ImpactType::NaturalDisaster { disaster_type: _ } => {
                 // TODO: Implement disaster system
             }
@@ -236,6 +334,71 @@ impl DungeonMaster {
         }
     }
 
+    /// Manually trigger a story event by id, ignoring its cooldown (from Admin API)
+    pub async fn trigger_event(&self, event_id: &str, overrides: &EventOverrides) -> Option<StoryEvent> {
+        let event = self.story_events.iter().find(|e| e.id == event_id)?;
+        self.inject_event_with_overrides(event, overrides).await;
+        Some(event.clone())
+    }
+
+    /// Seconds until a story event may fire again on its own
+    pub fn cooldown_remaining(&self, event: &StoryEvent) -> f32 {
+        let now = *self.clock.read();
+        match self.last_triggered.read().get(&event.id) {
+            Some(last) => (event.cooldown - (now - last)).max(0.0),
+            None => 0.0,
+        }
+    }
+
+    /// List the story event library with remaining cooldowns
+    pub fn list_story_events(&self) -> Vec<StoryEventStatus> {
+        self.story_events
+            .iter()
+            .map(|event| StoryEventStatus {
+                event: event.clone(),
+                cooldown_remaining: self.cooldown_remaining(event),
+            })
+            .collect()
+    }
+
+    /// Get the current world metrics
+    pub fn get_metrics(&self) -> WorldMetrics {
+        self.metrics.read().clone()
+    }
+
+    /// Get the current settings
+    pub fn get_settings(&self) -> DungeonMasterSettings {
+        self.settings.read().clone()
+    }
+
+    /// Enable or disable autonomous event injection
+    pub fn set_enabled(&self, enabled: bool) {
+        self.settings.write().enabled = enabled;
+    }
+
+    /// Set the boredom score above which the DM injects events
+    pub fn set_boredom_threshold(&self, threshold: f32) {
+        self.settings.write().boredom_threshold = threshold;
+    }
+
+    /// Settings, metrics and cooldowns, for a world snapshot
+    pub fn state(&self) -> DungeonMasterState {
+        DungeonMasterState {
+            settings: self.get_settings(),
+            metrics: self.get_metrics(),
+            clock: *self.clock.read(),
+            last_triggered: self.last_triggered.read().iter().map(|(id, at)| (id.clone(), *at)).collect(),
+        }
+    }
+
+    /// Replace settings, metrics and cooldowns with a snapshot's
+    pub fn restore(&self, state: DungeonMasterState) {
+        *self.settings.write() = state.settings;
+        *self.metrics.write() = state.metrics;
+        *self.clock.write() = state.clock;
+        *self.last_triggered.write() = state.last_triggered.into_iter().collect();
+    }
+
     /// Manually inject an event by name (from Admin API)
     pub async fn inject_event_by_name(&self, event_name: &str) {
         if let Some(event) = self.story_events.iter().find(|e| e.id == event_name) {
@@ -253,14 +416,55 @@ mod tests {
         let event_bus = Arc::new(EventBus::new());
         let dm = DungeonMaster::new(event_bus.clone());
 
-        let mut metrics = WorldMetrics::default();
-        metrics.time_since_last_event = 400.0;
-        metrics.active_conflicts = 0;
+        let metrics = WorldMetrics {
+            time_since_last_event: 400.0,
+            active_conflicts: 0,
+            ..Default::default()
+        };
 
         dm.update_metrics(metrics);
 
         let boredom = dm.calculate_boredom();
         assert!(boredom > 0.3); // Should be bored
     }
+
+    #[tokio::test]
+    async fn test_trigger_event_starts_cooldown() {
+        let event_bus = Arc::new(EventBus::new());
+        let dm = DungeonMaster::new(event_bus);
+
+        let overrides = EventOverrides {
+            severity: Some(0.5),
+            ..Default::default()
+        };
+        assert!(overrides.is_valid());
+        assert!(dm.trigger_event("great_drought", &overrides).await.is_some());
+        assert!(dm.trigger_event("no_such_event", &overrides).await.is_none());
+
+        let status = dm
+            .list_story_events()
+            .into_iter()
+            .find(|s| s.event.id == "great_drought")
+            .unwrap();
+        assert_eq!(status.cooldown_remaining, 600.0);
+
+        // Disabled DM still advances its clock
+        dm.set_enabled(false);
+        dm.tick(100.0).await;
+        assert_eq!(dm.cooldown_remaining(&status.event), 500.0);
+    }
+
+    #[test]
+    fn test_overrides_must_be_finite_and_non_negative() {
+        let with = |radius: Option<f32>, severity: Option<f32>| EventOverrides { center: None, radius, severity };
+        assert!(EventOverrides::default().is_valid());
+        assert!(with(Some(0.0), Some(1.0)).is_valid());
+        assert!(!with(Some(-1.0), None).is_valid());
+        assert!(!with(Some(f32::INFINITY), None).is_valid());
+        assert!(!with(None, Some(f32::NAN)).is_valid());
+        assert!(!with(None, Some(-0.5)).is_valid());
+        let center = EventOverrides { center: Some(Position::new(f32::NAN, 0.0, 0.0)), ..Default::default() };
+        assert!(!center.is_valid());
+    }
 }
 
@@ -0,0 +1,145 @@
+use crate::Result;
+use parking_lot::RwLock;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use world_sim_core::ChunkCoord;
+
+/// Serialized chunks paged out of the live grid, one file per chunk in a directory
+///
+/// Only the set of stored coordinates is kept in memory; chunk data lives on disk from the
+/// moment it is written. A chunk stays stored after it is loaded again, so a chunk that comes
+/// back unchanged can be unloaded without writing it a second time.
+#[derive(Debug)]
+pub struct ChunkStore {
+    dir: PathBuf,
+    stored: RwLock<BTreeSet<ChunkCoord>>,
+    /// Whether the directory is ours to remove when the store is dropped
+    scratch: bool,
+}
+
+impl ChunkStore {
+    /// Store chunks in `dir`, creating it if needed
+    ///
+    /// Chunk files already there are pages of an earlier run's world and are removed.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "chunk") {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(Self { dir, stored: RwLock::default(), scratch: false })
+    }
+
+    /// Store chunks in a fresh directory under the system temp directory, removed on drop
+    pub fn scratch() -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("world_sim_chunks_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, stored: RwLock::default(), scratch: true })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, coord: ChunkCoord) -> PathBuf {
+        self.dir.join(format!("{}_{}_{}.chunk", coord.x, coord.y, coord.z))
+    }
+
+    /// Store a chunk, replacing any earlier copy
+    pub fn put(&self, coord: ChunkCoord, data: &[u8]) -> Result<()> {
+        // Written aside and renamed into place, so a failed write leaves the earlier copy
+        let path = self.path(coord);
+        let partial = path.with_extension("partial");
+        fs::write(&partial, data)?;
+        fs::rename(partial, path)?;
+        self.stored.write().insert(coord);
+        Ok(())
+    }
+
+    pub fn get(&self, coord: ChunkCoord) -> Result<Option<Vec<u8>>> {
+        if !self.contains(coord) {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(self.path(coord))?))
+    }
+
+    pub fn contains(&self, coord: ChunkCoord) -> bool {
+        self.stored.read().contains(&coord)
+    }
+
+    /// Stored chunks in the column of chunks at `x`, `z`, bottom first
+    pub fn column(&self, x: i32, z: i32) -> Vec<ChunkCoord> {
+        let mut column: Vec<ChunkCoord> = self.stored.read().iter().filter(|c| c.x == x && c.z == z).copied().collect();
+        column.sort_unstable_by_key(|c| c.y);
+        column
+    }
+
+    /// Every stored chunk coordinate, in order
+    pub fn coords(&self) -> Vec<ChunkCoord> {
+        self.stored.read().iter().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stored.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stored.read().is_empty()
+    }
+
+    /// Remove every stored chunk
+    pub fn clear(&self) -> Result<()> {
+        let mut stored = self.stored.write();
+        while let Some(coord) = stored.pop_first() {
+            fs::remove_file(self.path(coord))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ChunkStore {
+    fn drop(&mut self) {
+        if self.scratch {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_round_trip_through_disk() {
+        let store = ChunkStore::scratch().unwrap();
+        let dir = store.dir().to_path_buf();
+        let (a, b) = (ChunkCoord::new(0, -1, 2), ChunkCoord::new(0, 0, 2));
+        store.put(b, &[1, 2, 3]).unwrap();
+        store.put(a, &[4]).unwrap();
+        store.put(b, &[5, 6]).unwrap();
+
+        assert_eq!(store.get(a).unwrap(), Some(vec![4]));
+        assert_eq!(store.get(b).unwrap(), Some(vec![5, 6]));
+        assert_eq!(store.get(ChunkCoord::new(9, 9, 9)).unwrap(), None);
+        assert_eq!(store.column(0, 2), vec![a, b]);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        store.clear().unwrap();
+        assert!(store.is_empty() && !store.path(a).exists());
+
+        // Reopening a directory starts over, and only scratch directories are removed on drop
+        store.put(a, &[4]).unwrap();
+        let reopened = ChunkStore::open(&dir).unwrap();
+        assert!(reopened.is_empty());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        drop(reopened);
+        assert!(dir.exists());
+        drop(store);
+        assert!(!dir.exists());
+    }
+}
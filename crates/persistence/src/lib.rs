@@ -1,7 +1,9 @@
 /// Persistence layer for saving/loading simulation state
+mod chunks;
 mod database;
 mod snapshot;
 
+pub use chunks::*;
 pub use database::*;
 pub use snapshot::*;
 
@@ -18,6 +20,9 @@ pub enum PersistenceError {
     #[error("Bincode error: {0}")]
     Bincode(#[from] bincode::Error),
     
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    
     #[error("Not found: {0}")]
     NotFound(String),
 }
@@ -1,5 +1,4 @@
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Currency system with inflation tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +13,12 @@ pub struct CurrencySystem {
     pub deflation_events: u32,
     /// Transaction count (for velocity of money calculation)
     pub transaction_count: u64,
+    /// Gold created so far (wages, sales to markets, births, ...)
+    #[serde(default)]
+    pub minted: f64,
+    /// Gold destroyed so far (purchases from markets, archived estates, ...)
+    #[serde(default)]
+    pub burned: f64,
 }
 
 impl Default for CurrencySystem {
@@ -24,6 +29,8 @@ impl Default for CurrencySystem {
             inflation_rate: 0.0,
             deflation_events: 0,
             transaction_count: 0,
+            minted: 0.0,
+            burned: 0.0,
         }
     }
 }
@@ -39,16 +46,23 @@ impl CurrencySystem {
     /// Inject new currency (from mining, trading, etc.)
     pub fn mint_currency(&mut self, amount: f64) {
         self.total_supply += amount;
+        self.minted += amount;
         self.recalculate_inflation();
     }
     
     /// Remove currency from circulation (taxes, destruction, etc.)
     pub fn burn_currency(&mut self, amount: f64) {
         self.total_supply = (self.total_supply - amount).max(0.0);
+        self.burned += amount;
         self.deflation_events += 1;
         self.recalculate_inflation();
     }
     
+    /// Gold minted minus gold burned - what the holders' total should have grown by
+    pub fn net_issued(&self) -> f64 {
+        self.minted - self.burned
+    }
+    
     /// Record a transaction
     pub fn record_transaction(&mut self, _amount: f64) {
         self.transaction_count += 1;
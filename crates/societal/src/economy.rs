@@ -116,14 +116,11 @@ impl EconomySubsystem {
 #[async_trait]
 impl EventSubscriber for EconomySubsystem {
     async fn on_event(&self, event: &EventEnvelope) {
-        match event.event_type.as_str() {
-            "BlightStarted" => {
-                // Parse the event and react
-                if let Ok(blight_event) = serde_json::from_value::<BlightStartedEvent>(event.payload.clone()) {
-                    self.on_blight_started(blight_event.affected_resource).await;
-                }
+        if event.event_type == "BlightStarted" {
+            // Parse the event and react
+            if let Ok(blight_event) = serde_json::from_value::<BlightStartedEvent>(event.payload.clone()) {
+                self.on_blight_started(blight_event.affected_resource).await;
             }
-            _ => {}
         }
     }
 }
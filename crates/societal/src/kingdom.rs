@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use uuid::Uuid;
-use world_sim_core::{AgentId, FactionId, Position};
+use world_sim_core::{sim_uuid, AgentId, FactionId, Position};
 use world_sim_world::BuildingType;
 
 /// Strategic goals for a kingdom/faction
@@ -32,7 +32,7 @@ pub struct Kingdom {
 impl Kingdom {
     pub fn new(king_id: AgentId, territory_center: Position) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: sim_uuid(),
             faction_id: None,
             king_id,
             nobles: Vec::new(),
@@ -81,7 +81,7 @@ pub enum OrderStatus {
 impl NobleOrder {
     pub fn new(noble_id: AgentId, building_type: BuildingType, location: Position, priority: f32) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: sim_uuid(),
             noble_id,
             building_type,
             location,
@@ -94,16 +94,18 @@ impl NobleOrder {
 }
 
 /// Manager for kingdom-level strategic planning
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KingdomManager {
-    kingdoms: HashMap<Uuid, Kingdom>,
-    noble_orders: HashMap<Uuid, NobleOrder>,
+    // BTreeMaps keep iteration order stable for seeded runs
+    kingdoms: BTreeMap<Uuid, Kingdom>,
+    noble_orders: BTreeMap<Uuid, NobleOrder>,
 }
 
 impl KingdomManager {
     pub fn new() -> Self {
         Self {
-            kingdoms: HashMap::new(),
-            noble_orders: HashMap::new(),
+            kingdoms: BTreeMap::new(),
+            noble_orders: BTreeMap::new(),
         }
     }
     
@@ -163,3 +165,8 @@ impl KingdomManager {
     }
 }
 
+impl Default for KingdomManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
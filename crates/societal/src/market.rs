@@ -1,8 +1,7 @@
-use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
-use world_sim_core::{Position, ResourceType};
+use world_sim_core::{sim_uuid, Position, ResourceType, SpatialIndex};
 
 /// A physical market in the world where trade happens
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,7 +60,7 @@ pub enum OrderType {
 impl Market {
     pub fn new(name: String, position: Position, market_type: MarketType) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: sim_uuid(),
             position,
             name,
             market_type,
@@ -124,7 +123,7 @@ impl Market {
                     let price = (buy.price_per_unit + sell.price_per_unit) / 2.0;
                     
                     executions.push(TradeExecution {
-                        id: Uuid::new_v4(),
+                        id: sim_uuid(),
                         buyer_id: buy.agent_id,
                         seller_id: sell.agent_id,
                         resource: buy.resource,
@@ -203,21 +202,32 @@ pub struct TradeExecution {
     pub market_id: Uuid,
 }
 
+/// Cell size of the market index - markets are far apart
+const MARKET_CELL_SIZE: f32 = 32.0;
+
 /// Manager for all markets in the world
 pub struct MarketSystem {
-    markets: AHashMap<Uuid, Market>,
+    markets: BTreeMap<Uuid, Market>,
+    /// Market positions (fixed once added)
+    spatial: SpatialIndex<Uuid>,
 }
 
 impl MarketSystem {
     pub fn new() -> Self {
         Self {
-            markets: AHashMap::new(),
+            markets: BTreeMap::new(),
+            spatial: SpatialIndex::new(MARKET_CELL_SIZE),
         }
     }
     
     pub fn create_market(&mut self, name: String, position: Position, market_type: MarketType) -> Uuid {
-        let market = Market::new(name, position, market_type);
+        self.add_market(Market::new(name, position, market_type))
+    }
+    
+    /// Insert an existing market (e.g. when restoring a snapshot)
+    pub fn add_market(&mut self, market: Market) -> Uuid {
         let id = market.id;
+        self.spatial.insert(id, market.position);
         self.markets.insert(id, market);
         id
     }
@@ -239,14 +249,14 @@ impl MarketSystem {
     }
     
     pub fn find_nearest_market(&self, position: &Position, market_type: Option<MarketType>) -> Option<&Market> {
-        self.markets
-            .values()
-            .filter(|m| market_type.is_none() || market_type == Some(m.market_type))
-            .min_by(|a, b| {
-                let dist_a = a.position.distance_to(position);
-                let dist_b = b.position.distance_to(position);
-                dist_a.partial_cmp(&dist_b).unwrap_or(std::cmp::Ordering::Equal)
-            })
+        self.spatial
+            .nearest(position, |id| market_type.is_none() || market_type == Some(self.markets[&id].market_type))
+            .map(|(id, _)| &self.markets[&id])
+    }
+    
+    /// Market positions by id
+    pub fn spatial_index(&self) -> &SpatialIndex<Uuid> {
+        &self.spatial
     }
     
     /// Process all market orders
@@ -1,14 +1,14 @@
 use ahash::AHashMap;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use world_sim_core::{AgentId, ChunkCoord, FactionId};
 use world_sim_event_bus::{EventBus, WarDeclaredEvent, PeaceTreatyEvent};
 
 /// Manages factions and political relationships
 pub struct PoliticalLayer {
-    factions: Arc<RwLock<AHashMap<FactionId, Faction>>>,
+    factions: Arc<RwLock<BTreeMap<FactionId, Faction>>>,
     territory: Arc<RwLock<TerritoryManager>>,
     event_bus: Arc<EventBus>,
 }
@@ -16,7 +16,7 @@ pub struct PoliticalLayer {
 impl PoliticalLayer {
     pub fn new(event_bus: Arc<EventBus>) -> Self {
         Self {
-            factions: Arc::new(RwLock::new(AHashMap::new())),
+            factions: Arc::new(RwLock::new(BTreeMap::new())),
             territory: Arc::new(RwLock::new(TerritoryManager::new())),
             event_bus,
         }
@@ -112,6 +112,28 @@ impl PoliticalLayer {
     pub fn get_territory_owner(&self, chunk: ChunkCoord) -> Option<FactionId> {
         self.territory.read().get_owner(chunk)
     }
+
+    /// Factions and claimed territory, for a world snapshot
+    pub fn state(&self) -> PoliticsState {
+        let mut territory: Vec<(ChunkCoord, FactionId)> =
+            self.territory.read().territory_map.iter().map(|(chunk, owner)| (*chunk, *owner)).collect();
+        territory.sort_unstable();
+        PoliticsState { factions: self.get_all_factions(), territory }
+    }
+
+    /// Replace all factions and territory with a snapshot's
+    pub fn restore(&self, state: PoliticsState) {
+        *self.factions.write() = state.factions.into_iter().map(|f| (f.id, f)).collect();
+        self.territory.write().territory_map = state.territory.into_iter().collect();
+    }
+}
+
+/// Everything the political layer tracks (see [`PoliticalLayer::state`])
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PoliticsState {
+    pub factions: Vec<Faction>,
+    /// Claimed chunks and the faction holding each
+    pub territory: Vec<(ChunkCoord, FactionId)>,
 }
 
 /// A political faction
@@ -109,7 +109,7 @@ impl RelationshipManager {
     pub fn set(&mut self, agent_a: AgentId, agent_b: AgentId, relationship: Relationship) {
         self.relationships
             .entry(agent_a)
-            .or_insert_with(AHashMap::new)
+            .or_default()
             .insert(agent_b, relationship);
     }
 
@@ -117,9 +117,9 @@ impl RelationshipManager {
         let relationship = self
             .relationships
             .entry(agent_a)
-            .or_insert_with(AHashMap::new)
+            .or_default()
             .entry(agent_b)
-            .or_insert_with(Relationship::new);
+            .or_default();
 
         relationship.affinity += delta;
         relationship.affinity = relationship.affinity.clamp(-100.0, 100.0);
@@ -173,7 +173,7 @@ impl MemoryManager {
     pub fn add(&mut self, agent_id: AgentId, memory: MemoryFact) {
         self.memories
             .entry(agent_id)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(memory);
     }
 
@@ -1,8 +1,15 @@
-use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
-use world_sim_core::{AgentId, FactionId, Position, ResourceType};
+use world_sim_core::{sim_uuid, AgentId, BlockType, FactionId, GridCoord, Position, ResourceType, SpatialIndex};
+
+use crate::grid::{GridLayer, Prefab};
+
+/// Health of an undamaged building
+pub const FULL_HEALTH: f32 = 100.0;
+
+/// Most the ground of a building site may rise or fall from its floor; builders level the rest
+pub const MAX_GRADE: i32 = 2;
 
 /// A physical building in the world
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,7 +29,7 @@ pub struct Building {
     pub construction_fund: f64,  // Gold allocated by planner to buy materials
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BuildingType {
     Warehouse,      // Stores large quantities of resources
     Market,         // Already handled by market system
@@ -39,7 +46,8 @@ pub enum BuildingType {
 }
 
 impl BuildingType {
-    /// Get the resource requirements for constructing this building type
+    /// Built-in resource requirements for constructing this building type (a world's
+    /// [`BuildingManager`] may override them)
     pub fn required_resources(&self) -> HashMap<ResourceType, u32> {
         let mut requirements = HashMap::new();
         
@@ -106,6 +114,148 @@ impl BuildingType {
         
         requirements
     }
+
+    /// Blocks the building occupies: (width along x, height, depth along z)
+    pub fn size(&self) -> (i32, i32, i32) {
+        match self {
+            BuildingType::Warehouse => (8, 4, 6),
+            BuildingType::Market => (8, 3, 8),
+            BuildingType::Barracks => (8, 4, 6),
+            BuildingType::Workshop => (6, 4, 5),
+            BuildingType::Farm => (8, 2, 8),
+            BuildingType::Mine => (4, 3, 4),
+            BuildingType::NobleEstate => (10, 5, 8),
+            BuildingType::Church => (7, 6, 9),
+            BuildingType::Tavern => (6, 4, 6),
+            BuildingType::Walls => (12, 4, 1),
+            BuildingType::PeasantHouse => (4, 3, 4),
+            BuildingType::FarmingShed => (3, 3, 3),
+        }
+    }
+
+    /// Columns from the building's position to the first one past its footprint along its longer side
+    /// (how close a cart can pull up to unload)
+    pub fn approach(&self) -> i32 {
+        let (width, _, depth) = self.size();
+        width.max(depth) / 2 + 1
+    }
+
+    /// Block walls and roofs are built from
+    pub fn material(&self) -> BlockType {
+        match self {
+            BuildingType::Warehouse
+            | BuildingType::Barracks
+            | BuildingType::Mine
+            | BuildingType::NobleEstate
+            | BuildingType::Church
+            | BuildingType::Walls => BlockType::WallStone,
+            BuildingType::Market
+            | BuildingType::Workshop
+            | BuildingType::Farm
+            | BuildingType::Tavern
+            | BuildingType::PeasantHouse
+            | BuildingType::FarmingShed => BlockType::WallWood,
+        }
+    }
+
+    /// Blocks of the finished building in construction order, by offset from the lowest corner
+    /// of its footprint: the floor laid into the ground first, then the walls layer by layer
+    /// (leaving a doorway in the front), then the roof.
+    pub fn blueprint(&self) -> Prefab {
+        let (width, height, depth) = self.size();
+        let material = self.material();
+        let (max_x, max_z) = (width - 1, depth - 1);
+        let floor = |prefab: Prefab, block| prefab.with_box(GridCoord::new(0, 0, 0), GridCoord::new(max_x, 0, max_z), block);
+        let roof = |prefab: Prefab| prefab.with_box(GridCoord::new(0, height, 0), GridCoord::new(max_x, height, max_z), material);
+
+        match self {
+            // A solid run of masonry from the foundation up
+            BuildingType::Walls => Prefab::new().with_box(GridCoord::new(0, 0, 0), GridCoord::new(max_x, height, max_z), material),
+            // Tilled soil inside a low fence
+            BuildingType::Farm => ring(floor(Prefab::new(), BlockType::Dirt), width, depth, 1..height, material, true),
+            // Open stalls: corner posts under a canopy
+            BuildingType::Market => {
+                let mut prefab = floor(Prefab::new(), material);
+                for y in 1..height {
+                    for (x, z) in [(0, 0), (max_x, 0), (0, max_z), (max_x, max_z)] {
+                        prefab = prefab.with_block(GridCoord::new(x, y, z), material);
+                    }
+                }
+                roof(prefab)
+            }
+            _ => roof(ring(floor(Prefab::new(), material), width, depth, 1..height, material, true)),
+        }
+    }
+
+    /// Move a building with its footprint's lowest corner at `origin` from `from` standing
+    /// blueprint blocks to `to`: the next blocks go up in order, or the last ones come down
+    /// (those still standing - a burned wall stays burned). The site is levelled before the first
+    /// block goes up, and taking up the floor leaves grass. Returns how many blocks changed, or
+    /// None (changing nothing) if the site reaches into an unloaded chunk.
+    pub fn materialize(&self, grid: &GridLayer, origin: GridCoord, from: usize, to: usize) -> Option<usize> {
+        let blueprint = self.blueprint();
+        let at = |offset: GridCoord| GridCoord::new(origin.x + offset.x, origin.y + offset.y, origin.z + offset.z);
+        let to = to.min(blueprint.blocks.len());
+        if to >= from {
+            let levelled = if from == 0 && to > 0 { self.level(grid, origin)? } else { 0 };
+            let placed = Prefab { blocks: blueprint.blocks[from..to].to_vec() };
+            return Some(levelled + grid.stamp(origin, &placed)?);
+        }
+
+        let mut cleared = Prefab::new();
+        for (offset, block) in blueprint.blocks[to..from.min(blueprint.blocks.len())].iter().rev() {
+            if grid.get_block(at(*offset)) == *block {
+                let bare = if offset.y == 0 { BlockType::Grass } else { BlockType::Air };
+                cleared = cleared.with_block(*offset, bare);
+            }
+        }
+        grid.stamp(origin, &cleared)
+    }
+
+    /// Cut the ground of the site down to its floor and fill hollows up to it with dirt
+    fn level(&self, grid: &GridLayer, origin: GridCoord) -> Option<usize> {
+        let (width, _, depth) = self.size();
+        let mut earthworks = Prefab::new();
+        for z in 0..depth {
+            for x in 0..width {
+                let Some(ground) = grid.surface_height(origin.x + x, origin.z + z) else { continue };
+                let (fill, from, to) = if ground > origin.y {
+                    (BlockType::Air, 1, ground - origin.y)
+                } else {
+                    (BlockType::Dirt, ground - origin.y + 1, -1)
+                };
+                if from <= to {
+                    earthworks = earthworks.with_box(GridCoord::new(x, from, z), GridCoord::new(x, to, z), fill);
+                }
+            }
+        }
+        grid.stamp(origin, &earthworks)
+    }
+
+    /// Corners of the footprint this type would have if placed at `position` (see [`Building::footprint`])
+    pub fn footprint_at(&self, position: &Position) -> (GridCoord, GridCoord) {
+        let (width, height, depth) = self.size();
+        let base = position.to_grid_coord();
+        let min = GridCoord::new(base.x - width / 2, base.y - 1, base.z - depth / 2);
+        (min, GridCoord::new(min.x + width - 1, base.y + height - 1, min.z + depth - 1))
+    }
+}
+
+/// Add the perimeter of a `width` x `depth` rectangle for each layer in `layers`,
+/// leaving a two-high doorway in the middle of the front (z = 0) side if asked
+fn ring(mut prefab: Prefab, width: i32, depth: i32, layers: std::ops::Range<i32>, block: BlockType, doorway: bool) -> Prefab {
+    for y in layers {
+        for z in 0..depth {
+            for x in 0..width {
+                let edge = x == 0 || z == 0 || x == width - 1 || z == depth - 1;
+                let door = doorway && z == 0 && x == width / 2 && y <= 2;
+                if edge && !door {
+                    prefab = prefab.with_block(GridCoord::new(x, y, z), block);
+                }
+            }
+        }
+    }
+    prefab
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -138,7 +288,7 @@ impl ResourceStorage {
         self.capacity.saturating_sub(self.current_usage())
     }
     
-    pub fn can_store(&self, resource: ResourceType, quantity: u32) -> bool {
+    pub fn can_store(&self, _resource: ResourceType, quantity: u32) -> bool {
         self.available_space() >= quantity
     }
     
@@ -189,13 +339,13 @@ impl Building {
         let required_resources = building_type.required_resources();
         
         Self {
-            id: Uuid::new_v4(),
+            id: sim_uuid(),
             building_type,
             position,
             name,
             owner,
             construction_progress: 0.0,
-            health: 100.0,
+            health: FULL_HEALTH,
             storage: ResourceStorage::new(capacity),
             required_resources: required_resources.clone(),
             current_resources: HashMap::new(), // Start empty
@@ -203,6 +353,26 @@ impl Building {
         }
     }
     
+    /// Lowest and highest corners of the blocks the building occupies, centred on its position:
+    /// from the floor in the ground beneath the position up to the roof
+    pub fn footprint(&self) -> (GridCoord, GridCoord) {
+        self.building_type.footprint_at(&self.position)
+    }
+
+    /// How many blueprint blocks should be standing: construction puts them up in order and
+    /// damage takes them down from the roof
+    pub fn standing_blocks(&self) -> usize {
+        let blocks = self.building_type.blueprint().blocks.len();
+        let built = self.construction_progress.min(self.health / FULL_HEALTH).clamp(0.0, 1.0);
+        (blocks as f32 * built).ceil() as usize
+    }
+
+    /// Whether a block lies within the building's footprint
+    pub fn occupies(&self, coord: GridCoord) -> bool {
+        let (min, max) = self.footprint();
+        (min.x..=max.x).contains(&coord.x) && (min.y..=max.y).contains(&coord.y) && (min.z..=max.z).contains(&coord.z)
+    }
+
     /// Check if building has enough resources for construction
     pub fn has_sufficient_resources(&self) -> bool {
         for (resource_type, required) in &self.required_resources {
@@ -262,6 +432,16 @@ impl Building {
         self.construction_progress = (self.construction_progress + amount).min(1.0);
     }
     
+    /// Finish construction immediately (admin override)
+    pub fn complete_construction(&mut self) {
+        self.construction_progress = 1.0;
+    }
+    
+    /// Add gold to the construction fund
+    pub fn add_construction_fund(&mut self, amount: f64) {
+        self.construction_fund += amount;
+    }
+    
     /// Construct with resource consumption (returns true if construction occurred)
     pub fn construct_with_resources(&mut self, progress_amount: f32) -> bool {
         // Check if we have enough resources for this tick
@@ -303,20 +483,54 @@ impl Building {
     }
 }
 
+/// Cell size of the building index - a few building footprints across
+const BUILDING_CELL_SIZE: f32 = 32.0;
+
+/// Buildings whose footprints overlap have centres closer than this (the two widest side by side)
+const OVERLAP_REACH: f32 = 16.0;
+
 /// Manager for all buildings in the world
 pub struct BuildingManager {
-    buildings: AHashMap<Uuid, Building>,
+    buildings: BTreeMap<Uuid, Building>, // Sorted by id so seeded runs iterate in the same order
+    /// Building positions (buildings don't move once placed)
+    spatial: SpatialIndex<Uuid>,
+    /// Construction costs replacing the built-in ones for this world
+    costs: HashMap<BuildingType, HashMap<ResourceType, u32>>,
 }
 
 impl BuildingManager {
     pub fn new() -> Self {
         Self {
-            buildings: AHashMap::new(),
+            buildings: BTreeMap::new(),
+            spatial: SpatialIndex::new(BUILDING_CELL_SIZE),
+            costs: HashMap::new(),
         }
     }
+
+    /// Replace construction costs for the given types (others keep their built-in costs)
+    pub fn with_costs(mut self, costs: HashMap<BuildingType, HashMap<ResourceType, u32>>) -> Self {
+        self.costs = costs;
+        self
+    }
+
+    /// Resources needed to construct a building type in this world
+    pub fn required_resources(&self, building_type: BuildingType) -> HashMap<ResourceType, u32> {
+        match self.costs.get(&building_type) {
+            Some(cost) => cost.clone(),
+            None => building_type.required_resources(),
+        }
+    }
+
+    /// A new, unbuilt building costing what this world charges for its type (not yet added)
+    pub fn new_building(&self, building_type: BuildingType, position: Position, name: String, owner: BuildingOwner) -> Building {
+        let mut building = Building::new(building_type, position, name, owner);
+        building.required_resources = self.required_resources(building_type);
+        building
+    }
     
     pub fn add_building(&mut self, building: Building) -> Uuid {
         let id = building.id;
+        self.spatial.insert(id, building.position);
         self.buildings.insert(id, building);
         id
     }
@@ -339,17 +553,76 @@ impl BuildingManager {
         building_type: Option<BuildingType>,
         only_complete: bool,
     ) -> Option<&Building> {
-        self.buildings
-            .values()
-            .filter(|b| {
-                (building_type.is_none() || building_type == Some(b.building_type))
-                    && (!only_complete || b.is_complete())
-            })
-            .min_by(|a, b| {
-                let dist_a = a.position.distance_to(position);
-                let dist_b = b.position.distance_to(position);
-                dist_a.partial_cmp(&dist_b).unwrap_or(std::cmp::Ordering::Equal)
+        self.find_nearest_building_matching(position, |b| {
+            (building_type.is_none() || building_type == Some(b.building_type))
+                && (!only_complete || b.is_complete())
+        })
+    }
+    
+    /// Nearest building accepted by `filter` (ties go to the lower id)
+    pub fn find_nearest_building_matching(
+        &self,
+        position: &Position,
+        filter: impl Fn(&Building) -> bool,
+    ) -> Option<&Building> {
+        self.spatial
+            .nearest(position, |id| filter(&self.buildings[&id]))
+            .map(|(id, _)| &self.buildings[&id])
+    }
+    
+    /// Buildings within `radius` of a position, in id order
+    pub fn buildings_within(&self, position: &Position, radius: f32) -> Vec<&Building> {
+        self.spatial
+            .within_radius(position, radius)
+            .into_iter()
+            .map(|id| &self.buildings[&id])
+            .collect()
+    }
+    
+    /// Whether a building of this type fits at `position`: every column of its footprint must
+    /// be walkable ground within [`MAX_GRADE`] blocks of the ground beneath the position, and no
+    /// other building's footprint may overlap it
+    pub fn can_place(&self, grid: &GridLayer, building_type: BuildingType, position: &Position) -> bool {
+        let (min, max) = building_type.footprint_at(position);
+        let overlaps = |other: &Building| {
+            let (other_min, other_max) = other.footprint();
+            min.x <= other_max.x && other_min.x <= max.x && min.z <= other_max.z && other_min.z <= max.z
+        };
+        if self.buildings_within(position, OVERLAP_REACH).into_iter().any(overlaps) {
+            return false;
+        }
+        (min.z..=max.z).all(|z| {
+            (min.x..=max.x).all(|x| {
+                grid.surface_height(x, z).is_some_and(|height| {
+                    let ground = grid.get_block(GridCoord::new(x, height, z));
+                    (height - min.y).abs() <= MAX_GRADE && ground.is_solid() && ground.is_walkable()
+                })
             })
+        })
+    }
+
+    /// The placeable site nearest `near` within `radius` blocks, standing on the ground
+    /// (ties go to the lower x, then z)
+    pub fn find_site(&self, grid: &GridLayer, building_type: BuildingType, near: &Position, radius: i32) -> Option<Position> {
+        let center = near.to_grid_coord();
+        let mut offsets: Vec<(i32, i32)> = (-radius..=radius)
+            .flat_map(|dx| (-radius..=radius).map(move |dz| (dx, dz)))
+            .filter(|(dx, dz)| dx * dx + dz * dz <= radius * radius)
+            .collect();
+        offsets.sort_by_key(|(dx, dz)| (dx * dx + dz * dz, *dx, *dz));
+
+        offsets.into_iter().find_map(|(dx, dz)| {
+            let (x, z) = (center.x + dx, center.z + dz);
+            let ground = grid.surface_height(x, z)?;
+            let site = Position::new(x as f32 + 0.5, (ground + 1) as f32, z as f32 + 0.5);
+            self.can_place(grid, building_type, &site).then_some(site)
+        })
+    }
+
+    /// Remove a building (demolition)
+    pub fn remove_building(&mut self, id: Uuid) -> Option<Building> {
+        self.spatial.remove(id);
+        self.buildings.remove(&id)
     }
     
     pub fn remove_destroyed_buildings(&mut self) -> Vec<Uuid> {
@@ -361,7 +634,7 @@ impl BuildingManager {
             .collect();
         
         for id in &destroyed {
-            self.buildings.remove(id);
+            self.remove_building(*id);
         }
         
         destroyed
@@ -374,3 +647,94 @@ impl Default for BuildingManager {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_and_demolish() {
+        let mut manager = BuildingManager::new();
+        let mut building = Building::new(
+            BuildingType::Tavern,
+            Position::new(0.0, 1.0, 0.0),
+            "Test Tavern".to_string(),
+            BuildingOwner::Public,
+        );
+        building.add_construction_fund(250.0);
+        building.complete_construction();
+        assert!(building.is_complete());
+        assert_eq!(building.construction_fund, 250.0);
+
+        let id = manager.add_building(building);
+        assert!(manager.remove_building(id).is_some());
+        assert!(manager.get_building(id).is_none());
+    }
+
+    #[test]
+    fn test_blueprint_goes_up_with_progress_and_down_with_damage() {
+        let grid = GridLayer::new();
+        grid.generate_simple_terrain(GridCoord::new(-20, 0, -20), GridCoord::new(20, 0, 20));
+        let mut manager = BuildingManager::new();
+
+        // Off the edge of the terrain and on top of another building are both refused
+        let site = manager.find_site(&grid, BuildingType::PeasantHouse, &Position::new(3.2, 1.0, 3.7), 5).unwrap();
+        assert_eq!(site, Position::new(3.5, 1.0, 3.5));
+        assert!(!manager.can_place(&grid, BuildingType::PeasantHouse, &Position::new(20.5, 1.0, 0.5)));
+        // A bump the builders can level, and a cliff they can't
+        grid.fill_box(GridCoord::new(3, 1, 3), GridCoord::new(3, 2, 3), BlockType::Grass);
+        grid.fill_box(GridCoord::new(-10, 1, 0), GridCoord::new(-10, 3, 0), BlockType::Grass);
+        assert!(!manager.can_place(&grid, BuildingType::PeasantHouse, &Position::new(-10.5, 1.0, 0.5)));
+        let mut house = Building::new(BuildingType::PeasantHouse, site, "House".to_string(), BuildingOwner::Public);
+        let origin = house.footprint().0;
+        let blueprint = BuildingType::PeasantHouse.blueprint();
+        assert_eq!(blueprint.blocks.len(), 16 + 2 * 12 - 2 + 16);
+
+        house.construction_progress = 0.5;
+        let half = house.standing_blocks();
+        // Levelling the bump takes two more changes
+        assert_eq!(BuildingType::PeasantHouse.materialize(&grid, origin, 0, half), Some(half + 2));
+        assert_eq!(grid.get_block(origin), BlockType::WallWood);
+        assert_eq!(grid.get_block(GridCoord::new(3, 2, 3)), BlockType::Air);
+        assert_eq!(grid.surface_height(origin.x + 1, origin.z + 1), Some(0));
+        let id = manager.add_building(house);
+        assert!(!manager.can_place(&grid, BuildingType::FarmingShed, &Position::new(5.5, 1.0, 5.5)));
+
+        let house = manager.get_building_mut(id).unwrap();
+        house.complete_construction();
+        let whole = house.standing_blocks();
+        BuildingType::PeasantHouse.materialize(&grid, origin, half, whole);
+        assert_eq!(grid.surface_height(origin.x + 1, origin.z + 1), Some(3));
+        // The doorway stays open
+        assert_eq!(grid.get_block(GridCoord::new(origin.x + 2, 1, origin.z)), BlockType::Air);
+
+        // Losing half its health takes the roof off, then the top of the walls
+        house.damage(FULL_HEALTH / 2.0);
+        BuildingType::PeasantHouse.materialize(&grid, origin, whole, house.standing_blocks());
+        assert_eq!(grid.surface_height(origin.x + 1, origin.z + 1), Some(0));
+        assert_eq!(grid.surface_height(origin.x, origin.z), Some(1));
+        BuildingType::PeasantHouse.materialize(&grid, origin, house.standing_blocks(), 0);
+        assert_eq!(grid.surface_height(origin.x, origin.z), Some(0));
+        assert_eq!(grid.get_block(origin), BlockType::Grass);
+    }
+
+    #[test]
+    fn test_cost_overrides() {
+        let cost = HashMap::from([(ResourceType::Wood, 7)]);
+        let manager = BuildingManager::new().with_costs(HashMap::from([(BuildingType::FarmingShed, cost.clone())]));
+
+        assert_eq!(manager.required_resources(BuildingType::FarmingShed), cost);
+        assert_eq!(
+            manager.required_resources(BuildingType::Warehouse),
+            BuildingType::Warehouse.required_resources()
+        );
+        let shed = manager.new_building(BuildingType::FarmingShed, Position::new(0.0, 1.0, 0.0), "Shed".to_string(), BuildingOwner::Public);
+        assert_eq!(shed.required_resources, cost);
+
+        // Another world keeps the built-in costs
+        assert_eq!(
+            BuildingManager::new().required_resources(BuildingType::FarmingShed),
+            BuildingType::FarmingShed.required_resources()
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
-use world_sim_core::{ResourceType, Skill, Trait};
+use world_sim_core::{BlockType, ResourceType, Skill, Trait};
 
 /// Defines a GOAP action
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,12 +46,44 @@ pub struct TraitDefinition {
     pub action_modifiers: Vec<(String, f32)>, // (action_id, cost_multiplier)
 }
 
+/// Tools some blocks can't be dug out without
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tool {
+    Shovel,
+    Axe,
+    Pickaxe,
+}
+
+/// Defines what it takes to dig a block out and what it leaves behind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockDefinition {
+    pub block_type: BlockType,
+    /// Seconds of work to dig it out
+    pub hardness: f32,
+    /// Tool needed to dig it at all (bare hands will do if none)
+    pub tool: Option<Tool>,
+    /// Resources it yields once dug out
+    pub drops: Vec<(ResourceType, u32)>,
+}
+
+impl BlockDefinition {
+    pub fn can_dig_with(&self, tools: &[Tool]) -> bool {
+        self.tool.is_none_or(|tool| tools.contains(&tool))
+    }
+
+    /// Units of a resource it yields
+    pub fn yield_of(&self, resource: ResourceType) -> u32 {
+        self.drops.iter().filter(|(r, _)| *r == resource).map(|(_, quantity)| quantity).sum()
+    }
+}
+
 /// Central content database - the "schema" of all possible content
 pub struct ContentDefinitionLayer {
     actions: AHashMap<String, ActionDefinition>,
     items: AHashMap<String, ItemDefinition>,
     recipes: AHashMap<String, Recipe>,
     traits: AHashMap<Trait, TraitDefinition>,
+    blocks: AHashMap<BlockType, BlockDefinition>,
 }
 
 impl ContentDefinitionLayer {
@@ -61,6 +93,7 @@ impl ContentDefinitionLayer {
             items: AHashMap::new(),
             recipes: AHashMap::new(),
             traits: AHashMap::new(),
+            blocks: AHashMap::new(),
         };
         
         layer.initialize_default_content();
@@ -149,6 +182,21 @@ impl ContentDefinitionLayer {
             },
         );
 
+        // Block properties: air, water and fire can't be dug
+        let blocks = [
+            (BlockType::Grass, 1.0, None, vec![]),
+            (BlockType::Dirt, 1.0, None, vec![]),
+            (BlockType::Stone, 2.0, Some(Tool::Pickaxe), vec![(ResourceType::Stone, 10)]),
+            (BlockType::Iron, 3.0, Some(Tool::Pickaxe), vec![(ResourceType::Iron, 10)]),
+            (BlockType::Gold, 4.0, Some(Tool::Pickaxe), vec![(ResourceType::Gold, 2)]),
+            (BlockType::Wood, 2.0, Some(Tool::Axe), vec![(ResourceType::Wood, 10)]),
+            (BlockType::WallWood, 3.0, Some(Tool::Axe), vec![(ResourceType::Wood, 5)]),
+            (BlockType::WallStone, 5.0, Some(Tool::Pickaxe), vec![(ResourceType::Stone, 5)]),
+        ];
+        for (block_type, hardness, tool, drops) in blocks {
+            self.blocks.insert(block_type, BlockDefinition { block_type, hardness, tool, drops });
+        }
+
         // Add trait definitions
         self.traits.insert(
             Trait::Brave,
@@ -180,6 +228,11 @@ impl ContentDefinitionLayer {
         self.traits.get(trait_type)
     }
 
+    /// How a block is dug out, or `None` if it can't be
+    pub fn get_block(&self, block_type: BlockType) -> Option<&BlockDefinition> {
+        self.blocks.get(&block_type)
+    }
+
     pub fn all_actions(&self) -> Vec<&ActionDefinition> {
         self.actions.values().collect()
     }
@@ -187,6 +240,10 @@ impl ContentDefinitionLayer {
     pub fn all_items(&self) -> Vec<&ItemDefinition> {
         self.items.values().collect()
     }
+
+    pub fn all_blocks(&self) -> Vec<&BlockDefinition> {
+        self.blocks.values().collect()
+    }
 }
 
 impl Default for ContentDefinitionLayer {
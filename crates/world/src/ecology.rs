@@ -2,7 +2,7 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
-use world_sim_core::{BlockType, GridCoord};
+use world_sim_core::{sim_rng, sim_uuid, BlockType, GridCoord};
 use world_sim_event_bus::{DroughtStartedEvent, EventBus, Season, SeasonChangeEvent};
 
 use crate::GridLayer;
@@ -58,8 +58,9 @@ impl Default for SeasonalSubsystem {
 }
 
 /// Weather states
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WeatherState {
+    #[default]
     Clear,
     Rain,
     Drought,
@@ -89,7 +90,7 @@ impl WeatherSubsystem {
         
         if self.duration_remaining == 0 {
             // Change weather randomly
-            let mut rng = rand::thread_rng();
+            let mut rng = sim_rng();
             self.current_weather = match rng.gen_range(0..4) {
                 0 => WeatherState::Clear,
                 1 => WeatherState::Rain,
@@ -132,24 +133,25 @@ impl ResourceLifeCycle {
         }
     }
 
-    /// Process natural growth (trees, grass, etc.)
+    /// Process natural growth (trees, grass, etc.) in the loaded chunks
     pub fn tick(&self) {
-        let chunks = self.grid.get_loaded_chunks();
-        let mut rng = rand::thread_rng();
+        // Sorted so seeded runs draw for the same chunks in the same order
+        let mut chunks = self.grid.get_loaded_chunks();
+        chunks.sort_unstable();
+        let mut rng = sim_rng();
         
         for chunk_coord in chunks {
             // Randomly grow trees in this chunk
             if rng.gen::<f32>() < self.growth_rate {
                 let x = chunk_coord.x * crate::grid::CHUNK_SIZE + rng.gen_range(0..32);
                 let z = chunk_coord.z * crate::grid::CHUNK_SIZE + rng.gen_range(0..32);
-                let coord = GridCoord::new(x, 1, z);
                 
-                // If there's grass, maybe grow a tree
-                if self.grid.get_block(coord) == BlockType::Air {
-                    let below = GridCoord::new(x, 0, z);
-                    if self.grid.get_block(below) == BlockType::Grass {
-                        self.grid.set_block(coord, BlockType::Wood);
-                    }
+                // If the column's surface is grass in this chunk, maybe grow a tree on it
+                let Some(surface) = self.grid.surface_height(x, z) else { continue };
+                if surface.div_euclid(crate::grid::CHUNK_SIZE) == chunk_coord.y
+                    && self.grid.get_block(GridCoord::new(x, surface, z)) == BlockType::Grass
+                {
+                    self.grid.set_block(GridCoord::new(x, surface + 1, z), BlockType::Wood);
                 }
             }
         }
@@ -178,17 +180,21 @@ impl FaunaSubsystem {
 
     pub fn spawn_animal(&mut self, species: String, position: GridCoord) {
         self.agents.push(FaunaAgent {
-            id: Uuid::new_v4(),
+            id: sim_uuid(),
             species,
             position,
             health: 100.0,
         });
     }
 
+    /// Wander animals in loaded chunks (the rest wait for theirs to be loaded again)
     pub fn tick(&mut self, grid: &GridLayer) {
-        let mut rng = rand::thread_rng();
+        let mut rng = sim_rng();
         
         for agent in &mut self.agents {
+            if !grid.has_chunk(agent.position.to_chunk_coord(crate::grid::CHUNK_SIZE)) {
+                continue;
+            }
             // Simple random movement
             let dx = rng.gen_range(-1..=1);
             let dz = rng.gen_range(-1..=1);
@@ -241,3 +247,42 @@ impl EcologyLayer {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Chunk;
+    use world_sim_core::ChunkCoord;
+
+    #[test]
+    fn test_growth_and_fauna_only_in_loaded_chunks() {
+        let grid = Arc::new(GridLayer::new());
+        grid.generate_simple_terrain(GridCoord::new(0, 0, 0), GridCoord::new(63, 0, 31));
+        let paged_out: Vec<Chunk> = [ChunkCoord::new(1, -1, 0), ChunkCoord::new(1, 0, 0)]
+            .into_iter()
+            .map(|coord| grid.unload_chunk(coord).unwrap().0)
+            .collect();
+
+        let mut fauna = FaunaSubsystem::new();
+        fauna.spawn_animal("deer".to_string(), GridCoord::new(10, 1, 10));
+        fauna.spawn_animal("deer".to_string(), GridCoord::new(40, 1, 10));
+        let mut wandered = false;
+        for _ in 0..50 {
+            fauna.tick(&grid);
+            wandered |= fauna.get_agents()[0].position != GridCoord::new(10, 1, 10);
+        }
+        assert!(wandered);
+        assert_eq!(fauna.get_agents()[1].position, GridCoord::new(40, 1, 10));
+
+        let growth = ResourceLifeCycle { grid: grid.clone(), growth_rate: 1.0 };
+        for _ in 0..20 {
+            growth.tick();
+        }
+        for chunk in paged_out {
+            grid.insert_chunk(chunk);
+        }
+        let wooded = grid.chunks_containing(BlockType::Wood);
+        assert!(!wooded.is_empty());
+        assert!(wooded.iter().all(|c| c.x == 0), "{:?}", wooded);
+    }
+}
@@ -0,0 +1,331 @@
+//! Fire: ignition, spread through wood, and burn-out
+//!
+//! A fire is a `BurningWood` block in the grid; this module remembers what each one was before
+//! it caught (so a doused fire leaves its wood standing) and how long it has left to burn. Each
+//! step a fire may catch the flammable blocks beside it - wood and wooden walls - readily in
+//! drought and summer, barely in rain and winter, and more easily upwards than down. A block that
+//! burns through becomes `Air`, and rain puts fires out.
+//!
+//! Fires start from lightning during storms, or from [`FireSimulation::ignite`] (disasters,
+//! arson). `BurningWood` blocks the simulation hasn't seen - a restored snapshot, a hand edit -
+//! are picked up as fresh fires in wood.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use world_sim_core::{sim_rng, BlockType, GridCoord};
+use world_sim_event_bus::Season;
+
+use crate::ecology::WeatherState;
+use crate::grid::{GridLayer, CHUNK_SIZE};
+
+/// Face neighbours a fire can spread to
+const NEIGHBOURS: [(i32, i32, i32); 6] = [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+
+/// Columns on each side of where lightning comes down that it may jump to (it finds the tallest)
+const STRIKE_REACH: i32 = 4;
+
+/// Fire simulation settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FireConfig {
+    /// Chance per step that a fire catches each flammable neighbour, in clear weather in autumn
+    /// (doubled upwards; weather and season scale it)
+    pub spread_chance: f32,
+    /// Steps a block of wood burns before it is gone
+    pub burn_steps: u32,
+    /// Chance per step of lightning striking somewhere during a storm
+    pub lightning_chance: f32,
+    /// Chance per step that rain puts out each fire
+    pub rain_douse_chance: f32,
+    /// Building health lost per step for each burning block within its footprint
+    pub building_damage: f32,
+    /// Chance per step that a rebellious peasant beside someone else's building sets it alight
+    pub arson_chance: f32,
+    /// Agents on each bucket line
+    pub bucket_line_crew: usize,
+    /// How far away agents notice a fire and come to fight it
+    pub alarm_radius: f32,
+}
+
+impl Default for FireConfig {
+    fn default() -> Self {
+        Self {
+            spread_chance: 0.1,
+            burn_steps: 30,
+            lightning_chance: 0.05,
+            rain_douse_chance: 0.1,
+            building_damage: 1.0,
+            arson_chance: 0.0005,
+            bucket_line_crew: 6,
+            alarm_radius: 30.0,
+        }
+    }
+}
+
+/// What one step did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FireStep {
+    /// Blocks that caught from a neighbour
+    pub ignited: usize,
+    /// Fires that burnt their block away
+    pub burned_out: usize,
+    /// Fires put out by rain
+    pub doused: usize,
+    /// Where lightning started a fire, if it did
+    pub lightning: Option<GridCoord>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Fire {
+    /// What the block was before it caught (`Air` for kindling)
+    fuel: BlockType,
+    /// Steps left before it burns through
+    remaining: u32,
+}
+
+/// Burning blocks and how long each has left
+#[derive(Debug, Clone)]
+pub struct FireSimulation {
+    config: FireConfig,
+    /// Columns on each side of the origin lightning may strike
+    extent: Option<i32>,
+    fires: BTreeMap<GridCoord, Fire>,
+    /// Grid revision as of the last step
+    seen_revision: u64,
+}
+
+impl FireSimulation {
+    pub fn new(config: FireConfig) -> Self {
+        Self {
+            config,
+            extent: None,
+            fires: BTreeMap::new(),
+            seen_revision: 0,
+        }
+    }
+
+    /// Strike lightning within columns `-half_extent..=half_extent` (the generated world)
+    pub fn with_extent(mut self, half_extent: i32) -> Self {
+        self.extent = Some(half_extent);
+        self
+    }
+
+    pub fn config(&self) -> &FireConfig {
+        &self.config
+    }
+
+    pub fn is_burning(&self, coord: GridCoord) -> bool {
+        self.fires.contains_key(&coord)
+    }
+
+    /// Burning blocks, in coordinate order
+    pub fn burning(&self) -> impl Iterator<Item = GridCoord> + '_ {
+        self.fires.keys().copied()
+    }
+
+    /// Set a block alight; empty air takes a short-lived kindling fire. Returns whether it caught.
+    pub fn ignite(&mut self, grid: &GridLayer, coord: GridCoord) -> bool {
+        let block = grid.get_block(coord);
+        let remaining = match block {
+            block if block.is_flammable() => self.config.burn_steps,
+            BlockType::Air => (self.config.burn_steps / 4).max(1),
+            _ => return false,
+        };
+        if grid.set_block(coord, BlockType::BurningWood).is_none() {
+            return false;
+        }
+        self.fires.insert(coord, Fire { fuel: block, remaining });
+        true
+    }
+
+    /// Put a fire out, leaving what is left of its block. Returns whether anything was burning.
+    pub fn douse(&mut self, grid: &GridLayer, coord: GridCoord) -> bool {
+        let Some(fire) = self.fires.remove(&coord) else {
+            return false;
+        };
+        if grid.get_block(coord) == BlockType::BurningWood {
+            grid.set_block(coord, fire.fuel);
+        }
+        true
+    }
+
+    /// Advance every fire one step under the given weather and season
+    pub fn step(&mut self, grid: &GridLayer, weather: WeatherState, season: Season) -> FireStep {
+        let mut step = FireStep::default();
+        self.adopt(grid);
+        let mut rng = sim_rng();
+
+        if weather == WeatherState::Storm && rng.gen::<f32>() < self.config.lightning_chance {
+            if let Some(coord) = self.strike(grid, &mut rng) {
+                if self.ignite(grid, coord) {
+                    step.lightning = Some(coord);
+                }
+            }
+        }
+
+        let raining = matches!(weather, WeatherState::Rain | WeatherState::Storm);
+        let chance = self.config.spread_chance * weather_factor(weather) * season_factor(season);
+        let fires: Vec<(GridCoord, Fire)> = self.fires.iter().map(|(coord, fire)| (*coord, *fire)).collect();
+        let mut catching = BTreeSet::new();
+        for (coord, fire) in fires {
+            if raining && rng.gen::<f32>() < self.config.rain_douse_chance {
+                self.douse(grid, coord);
+                step.doused += 1;
+                continue;
+            }
+
+            for (dx, dy, dz) in NEIGHBOURS {
+                let next = GridCoord::new(coord.x + dx, coord.y + dy, coord.z + dz);
+                let chance = if dy > 0 { chance * 2.0 } else { chance };
+                if grid.get_block(next).is_flammable() && rng.gen::<f32>() < chance {
+                    catching.insert(next);
+                }
+            }
+
+            if fire.remaining <= 1 {
+                self.fires.remove(&coord);
+                grid.set_block(coord, BlockType::Air);
+                step.burned_out += 1;
+            } else if let Some(fire) = self.fires.get_mut(&coord) {
+                fire.remaining -= 1;
+            }
+        }
+        for coord in catching {
+            if self.ignite(grid, coord) {
+                step.ignited += 1;
+            }
+        }
+
+        self.seen_revision = grid.revision();
+        step
+    }
+
+    /// Where lightning hits: the tallest column around a random spot, if its top will burn
+    fn strike(&self, grid: &GridLayer, rng: &mut impl Rng) -> Option<GridCoord> {
+        let (x, z) = match self.extent {
+            Some(e) => (rng.gen_range(-e..=e), rng.gen_range(-e..=e)),
+            None => {
+                let mut chunks = grid.get_loaded_chunks();
+                if chunks.is_empty() {
+                    return None;
+                }
+                chunks.sort_unstable();
+                let chunk = chunks[rng.gen_range(0..chunks.len())];
+                (chunk.x * CHUNK_SIZE + rng.gen_range(0..CHUNK_SIZE), chunk.z * CHUNK_SIZE + rng.gen_range(0..CHUNK_SIZE))
+            }
+        };
+        let top = (z - STRIKE_REACH..=z + STRIKE_REACH)
+            .flat_map(|z| (x - STRIKE_REACH..=x + STRIKE_REACH).map(move |x| (x, z)))
+            .filter_map(|(x, z)| grid.surface_height(x, z).map(|y| GridCoord::new(x, y, z)))
+            .max_by_key(|top| (top.y, std::cmp::Reverse(*top)))?;
+        grid.get_block(top).is_flammable().then_some(top)
+    }
+
+    /// Track `BurningWood` blocks placed by someone else, and forget fires whose block was replaced
+    fn adopt(&mut self, grid: &GridLayer) {
+        let changed = grid.changed_since(self.seen_revision);
+        if changed.is_empty() {
+            return;
+        }
+        self.fires.retain(|coord, _| grid.get_block(*coord) == BlockType::BurningWood);
+
+        let burning: BTreeSet<_> = grid.chunks_containing(BlockType::BurningWood).into_iter().collect();
+        for chunk_coord in changed.into_iter().filter(|c| burning.contains(c)) {
+            let Some(chunk) = grid.get_chunk(chunk_coord) else { continue };
+            for z in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        if chunk.get(x, y, z) == BlockType::BurningWood {
+                            let coord = GridCoord::new(
+                                chunk_coord.x * CHUNK_SIZE + x,
+                                chunk_coord.y * CHUNK_SIZE + y,
+                                chunk_coord.z * CHUNK_SIZE + z,
+                            );
+                            self.fires.entry(coord).or_insert(Fire {
+                                fuel: BlockType::Wood,
+                                remaining: self.config.burn_steps,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How weather scales the chance of fire spreading
+fn weather_factor(weather: WeatherState) -> f32 {
+    match weather {
+        WeatherState::Clear => 1.0,
+        WeatherState::Rain => 0.3,
+        WeatherState::Storm => 0.5,
+        WeatherState::Drought => 2.0,
+    }
+}
+
+/// How the season scales the chance of fire spreading
+fn season_factor(season: Season) -> f32 {
+    match season {
+        Season::Spring => 0.8,
+        Season::Summer => 1.5,
+        Season::Autumn => 1.0,
+        Season::Winter => 0.4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world_sim_core::seed_rng;
+
+    /// A stone floor with a row of wooden wall along x = 0..10 and a stone block at its end
+    fn woodpile() -> GridLayer {
+        let grid = GridLayer::new();
+        grid.fill_box(GridCoord::new(-2, -1, -2), GridCoord::new(12, 0, 2), BlockType::Stone);
+        grid.fill_box(GridCoord::new(0, 1, 0), GridCoord::new(9, 1, 0), BlockType::WallWood);
+        grid.set_block(GridCoord::new(10, 1, 0), BlockType::Stone);
+        grid
+    }
+
+    #[test]
+    fn test_fire_spreads_through_wood_and_burns_out() {
+        seed_rng(46);
+        let grid = woodpile();
+        let config = FireConfig { spread_chance: 1.0, burn_steps: 3, ..FireConfig::default() };
+        let mut fire = FireSimulation::new(config);
+        assert!(!fire.ignite(&grid, GridCoord::new(10, 1, 0)));
+        assert!(fire.ignite(&grid, GridCoord::new(0, 1, 0)));
+
+        let mut steps = 0;
+        while fire.burning().next().is_some() && steps < 100 {
+            fire.step(&grid, WeatherState::Drought, Season::Summer);
+            steps += 1;
+        }
+        assert!(steps < 100, "fire never burned out");
+        assert!((0..10).all(|x| grid.get_block(GridCoord::new(x, 1, 0)) == BlockType::Air));
+        assert_eq!(grid.get_block(GridCoord::new(10, 1, 0)), BlockType::Stone);
+        assert_eq!(grid.get_block(GridCoord::new(5, 0, 0)), BlockType::Stone);
+    }
+
+    #[test]
+    fn test_rain_douses_and_unseen_fires_are_adopted() {
+        seed_rng(46);
+        let grid = woodpile();
+        let config = FireConfig { spread_chance: 0.0, rain_douse_chance: 1.0, ..FireConfig::default() };
+        let mut fire = FireSimulation::new(config);
+
+        // A fire already in the grid (from a snapshot, say) is picked up on the next step
+        grid.set_block(GridCoord::new(4, 1, 0), BlockType::BurningWood);
+        fire.step(&grid, WeatherState::Clear, Season::Winter);
+        assert!(fire.is_burning(GridCoord::new(4, 1, 0)));
+
+        // Rain puts it out and the wood is left standing; kindling leaves nothing behind
+        assert!(fire.ignite(&grid, GridCoord::new(4, 2, 0)));
+        let step = fire.step(&grid, WeatherState::Rain, Season::Winter);
+        assert_eq!(step.doused, 2);
+        assert_eq!(grid.get_block(GridCoord::new(4, 1, 0)), BlockType::Wood);
+        assert_eq!(grid.get_block(GridCoord::new(4, 2, 0)), BlockType::Air);
+        assert!(!fire.douse(&grid, GridCoord::new(4, 1, 0)));
+    }
+}
@@ -1,10 +1,12 @@
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
+use nalgebra::Vector3;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use uuid::Uuid;
 use world_sim_core::{BlockType, ChunkCoord, GridCoord, Position};
 
+use crate::palette::{ChunkBlocks, CHUNK_VOLUME};
+
 /// Size of each chunk (32x32x32 blocks)
 pub const CHUNK_SIZE: i32 = 32;
 
@@ -12,15 +14,23 @@ pub const CHUNK_SIZE: i32 = 32;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chunk {
     pub coord: ChunkCoord,
-    pub blocks: Vec<BlockType>, // Flattened 3D array [CHUNK_SIZE³]
+    pub blocks: ChunkBlocks, // Flattened 3D array [CHUNK_SIZE³], palette-compressed
+    /// Grid revision of the last change to this chunk
+    #[serde(default)]
+    pub revision: u64,
 }
 
 impl Chunk {
     pub fn new(coord: ChunkCoord) -> Self {
-        let size = (CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE) as usize;
+        Self::filled(coord, BlockType::Air)
+    }
+
+    /// A chunk of a single block type
+    pub fn filled(coord: ChunkCoord, block: BlockType) -> Self {
         Self {
             coord,
-            blocks: vec![BlockType::Air; size],
+            blocks: ChunkBlocks::Uniform(block),
+            revision: 0,
         }
     }
 
@@ -31,139 +41,545 @@ impl Chunk {
 
     /// Get block at local chunk coordinates (0-31)
     pub fn get(&self, x: i32, y: i32, z: i32) -> BlockType {
-        if x < 0 || x >= CHUNK_SIZE || y < 0 || y >= CHUNK_SIZE || z < 0 || z >= CHUNK_SIZE {
+        if !(0..CHUNK_SIZE).contains(&x) || !(0..CHUNK_SIZE).contains(&y) || !(0..CHUNK_SIZE).contains(&z) {
             return BlockType::Air;
         }
-        self.blocks[Self::index(x, y, z)]
+        self.blocks.get(Self::index(x, y, z))
     }
 
     /// Set block at local chunk coordinates
     pub fn set(&mut self, x: i32, y: i32, z: i32, block: BlockType) {
-        if x < 0 || x >= CHUNK_SIZE || y < 0 || y >= CHUNK_SIZE || z < 0 || z >= CHUNK_SIZE {
+        if !(0..CHUNK_SIZE).contains(&x) || !(0..CHUNK_SIZE).contains(&y) || !(0..CHUNK_SIZE).contains(&z) {
             return;
         }
-        self.blocks[Self::index(x, y, z)] = block;
+        self.blocks.set(Self::index(x, y, z), block);
     }
 }
 
-/// The 3D voxel grid - the physical world
-pub struct GridLayer {
-    chunks: Arc<RwLock<AHashMap<ChunkCoord, Chunk>>>,
+/// A structure of blocks placed relative to an origin; Air entries carve out space
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Prefab {
+    /// Blocks by offset from the origin, placed in order (later entries win)
+    pub blocks: Vec<(GridCoord, BlockType)>,
 }
 
-impl GridLayer {
+impl Prefab {
     pub fn new() -> Self {
-        Self {
-            chunks: Arc::new(RwLock::new(AHashMap::new())),
+        Self::default()
+    }
+
+    pub fn with_block(mut self, offset: GridCoord, block: BlockType) -> Self {
+        self.blocks.push((offset, block));
+        self
+    }
+
+    /// Add a solid box between two corner offsets (inclusive)
+    pub fn with_box(mut self, a: GridCoord, b: GridCoord, block: BlockType) -> Self {
+        let (min, max) = corners(a, b);
+        for y in min.y..=max.y {
+            for z in min.z..=max.z {
+                for x in min.x..=max.x {
+                    self.blocks.push((GridCoord::new(x, y, z), block));
+                }
+            }
         }
+        self
     }
+}
 
-    /// Get or create a chunk
-    fn get_or_create_chunk(&self, chunk_coord: ChunkCoord) -> Chunk {
-        let mut chunks = self.chunks.write();
-        chunks
-            .entry(chunk_coord)
-            .or_insert_with(|| Chunk::new(chunk_coord))
-            .clone()
+/// Loaded chunks and their change tracking, kept under one lock
+#[derive(Default)]
+struct ChunkMap {
+    chunks: AHashMap<ChunkCoord, Chunk>,
+    /// Chunks edited since the last `take_dirty`
+    dirty: AHashSet<ChunkCoord>,
+    /// Bumped once per edit operation that changed something
+    revision: u64,
+    /// Chunks paged out of the grid: they read as Air and refuse edits until they are inserted again
+    unloaded: AHashSet<ChunkCoord>,
+}
+
+impl ChunkMap {
+    fn get(&self, coord: GridCoord) -> BlockType {
+        let (chunk_coord, x, y, z) = split(coord);
+        self.chunks.get(&chunk_coord).map_or(BlockType::Air, |chunk| chunk.get(x, y, z))
     }
 
-    /// Get block at world coordinates
-    pub fn get_block(&self, coord: GridCoord) -> BlockType {
-        let chunk_coord = coord.to_chunk_coord(CHUNK_SIZE);
-        let chunks = self.chunks.read();
-        
-        if let Some(chunk) = chunks.get(&chunk_coord) {
-            let local_x = coord.x.rem_euclid(CHUNK_SIZE);
-            let local_y = coord.y.rem_euclid(CHUNK_SIZE);
-            let local_z = coord.z.rem_euclid(CHUNK_SIZE);
-            chunk.get(local_x, local_y, local_z)
-        } else {
-            BlockType::Air
-        }
-    }
-
-    /// Set block at world coordinates
-    pub fn set_block(&self, coord: GridCoord, block: BlockType) {
-        let chunk_coord = coord.to_chunk_coord(CHUNK_SIZE);
-        let mut chunk = self.get_or_create_chunk(chunk_coord);
-        
-        let local_x = coord.x.rem_euclid(CHUNK_SIZE);
-        let local_y = coord.y.rem_euclid(CHUNK_SIZE);
-        let local_z = coord.z.rem_euclid(CHUNK_SIZE);
-        chunk.set(local_x, local_y, local_z, block);
-        
-        let mut chunks = self.chunks.write();
-        chunks.insert(chunk_coord, chunk);
+    /// First solid block along a ray (Amanatides-Woo voxel traversal)
+    fn raycast(&self, origin: Position, direction: Vector3<f32>, max_distance: f32) -> Option<RayHit> {
+        // The walk ends only once it passes `max_distance`, which an infinite or NaN distance never does
+        let finite_origin = origin.x.is_finite() && origin.y.is_finite() && origin.z.is_finite();
+        if !(max_distance.is_finite() && max_distance >= 0.0 && finite_origin) {
+            return None;
+        }
+        let start = origin.to_grid_coord();
+        let mut cell = [start.x, start.y, start.z];
+        let mut normal = [0; 3];
+        let mut distance = 0.0;
+
+        let length = direction.norm();
+        let usable = length > 0.0 && length.is_finite();
+        let origin = [origin.x, origin.y, origin.z];
+        let mut step = [0; 3];
+        let mut next = [f32::INFINITY; 3]; // Ray distance to the next boundary per axis
+        let mut delta = [f32::INFINITY; 3]; // Ray distance between boundaries per axis
+        if usable {
+            for axis in 0..3 {
+                let d = direction[axis] / length;
+                if d != 0.0 {
+                    step[axis] = if d > 0.0 { 1 } else { -1 };
+                    let boundary = (cell[axis] + i32::from(d > 0.0)) as f32;
+                    next[axis] = (boundary - origin[axis]) / d;
+                    delta[axis] = 1.0 / d.abs();
+                }
+            }
+        }
+
+        loop {
+            let coord = GridCoord::new(cell[0], cell[1], cell[2]);
+            let block = self.get(coord);
+            if block.is_solid() {
+                return Some(RayHit { coord, block, distance, normal: GridCoord::new(normal[0], normal[1], normal[2]) });
+            }
+            if !usable {
+                return None;
+            }
+
+            let axis = (0..3).min_by(|a, b| next[*a].total_cmp(&next[*b])).unwrap_or(0);
+            distance = next[axis];
+            if distance > max_distance {
+                return None;
+            }
+            cell[axis] += step[axis];
+            next[axis] += delta[axis];
+            normal = [0; 3];
+            normal[axis] = -step[axis];
+        }
     }
 
-    /// Check if a position is walkable
-    pub fn is_walkable(&self, coord: GridCoord) -> bool {
-        let block = self.get_block(coord);
-        block.is_walkable()
+    /// Whether nothing solid lies between two points; the block containing `to` itself does not block
+    fn line_of_sight(&self, from: Position, to: Position) -> bool {
+        let direction = to.to_vector3() - from.to_vector3();
+        match self.raycast(from, direction, direction.norm()) {
+            Some(hit) => hit.coord == to.to_grid_coord(),
+            None => true,
+        }
     }
 
-    /// Get all loaded chunks
-    pub fn get_loaded_chunks(&self) -> Vec<ChunkCoord> {
-        self.chunks.read().keys().copied().collect()
+    /// Write one block in place as part of edit `revision`, returning the block it replaced
+    /// (or None, writing nothing, if its chunk is unloaded)
+    fn write(&mut self, coord: GridCoord, block: BlockType, revision: u64) -> Option<BlockType> {
+        let (chunk_coord, x, y, z) = split(coord);
+        if self.unloaded.contains(&chunk_coord) {
+            return None;
+        }
+        let previous = self.chunks.get(&chunk_coord).map_or(BlockType::Air, |chunk| chunk.get(x, y, z));
+        if previous != block {
+            let chunk = self.chunks.entry(chunk_coord).or_insert_with(|| Chunk::new(chunk_coord));
+            chunk.set(x, y, z, block);
+            chunk.revision = revision;
+            self.dirty.insert(chunk_coord);
+        }
+        Some(previous)
     }
 
-    /// Generate simple terrain (for testing)
-    pub fn generate_simple_terrain(&self, min: GridCoord, max: GridCoord) {
-        for x in min.x..=max.x {
-            for z in min.z..=max.z {
-                // Ground level
-                self.set_block(GridCoord::new(x, 0, z), BlockType::Grass);
-                
-                // Below ground
-                for y in -5..0 {
-                    self.set_block(GridCoord::new(x, y, z), BlockType::Dirt);
-                }
+    /// Whether any chunk between two chunk corners (inclusive) is unloaded
+    fn reaches_unloaded(&self, low: ChunkCoord, high: ChunkCoord) -> bool {
+        self.unloaded.iter().any(|c| {
+            (low.x..=high.x).contains(&c.x) && (low.y..=high.y).contains(&c.y) && (low.z..=high.z).contains(&c.z)
+        })
+    }
+
+    /// Run an edit under a single new revision (kept only if a block changed); returns blocks changed
+    fn edit(&mut self, apply: impl FnOnce(&mut Self, u64) -> usize) -> usize {
+        let revision = self.revision + 1;
+        let changed = apply(self, revision);
+        if changed > 0 {
+            self.revision = revision;
+        }
+        changed
+    }
+
+    /// `edit` for bulk operations: afterwards the chunks it changed are compacted
+    fn bulk_edit(&mut self, apply: impl FnOnce(&mut Self, u64) -> usize) -> usize {
+        let changed = self.edit(apply);
+        if changed > 0 {
+            let revision = self.revision;
+            for chunk in self.chunks.values_mut().filter(|c| c.revision == revision) {
+                chunk.blocks.compact();
             }
         }
+        changed
     }
-}
 
-impl Default for GridLayer {
-    fn default() -> Self {
-        Self::new()
+    /// Make a whole (loaded) chunk one block type as part of edit `revision`, returning how many
+    /// blocks changed
+    fn fill_chunk(&mut self, chunk_coord: ChunkCoord, block: BlockType, revision: u64) -> usize {
+        let changed = match self.chunks.get(&chunk_coord) {
+            Some(chunk) => match chunk.blocks.uniform() {
+                Some(current) if current == block => 0,
+                Some(_) => CHUNK_VOLUME,
+                None => (0..CHUNK_VOLUME).filter(|i| chunk.blocks.get(*i) != block).count(),
+            },
+            None if block == BlockType::Air => 0,
+            None => CHUNK_VOLUME,
+        };
+        if changed > 0 {
+            let mut chunk = Chunk::filled(chunk_coord, block);
+            chunk.revision = revision;
+            self.chunks.insert(chunk_coord, chunk);
+            self.dirty.insert(chunk_coord);
+        }
+        changed
     }
 }
 
-/// Dynamic objects (non-voxel entities like ships, catapults)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DynamicObject {
-    pub id: Uuid,
-    pub object_type: String,
-    pub position: Position,
-    pub rotation: f32,
-    pub data: serde_json::Value,
+/// Chunk of a world coordinate and the local coordinates inside it
+fn split(coord: GridCoord) -> (ChunkCoord, i32, i32, i32) {
+    (
+        coord.to_chunk_coord(CHUNK_SIZE),
+        coord.x.rem_euclid(CHUNK_SIZE),
+        coord.y.rem_euclid(CHUNK_SIZE),
+        coord.z.rem_euclid(CHUNK_SIZE),
+    )
 }
 
-pub struct DynamicObjectList {
-    objects: Arc<RwLock<Vec<DynamicObject>>>,
+/// Where a ray first met a solid block
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub coord: GridCoord,
+    pub block: BlockType,
+    /// Distance along the ray to where it entered the block (0 if it started inside)
+    pub distance: f32,
+    /// Unit offset of the face the ray entered through (zero if it started inside)
+    pub normal: GridCoord,
 }
 
-impl DynamicObjectList {
+/// Order two opposite corners of a box as (min, max)
+fn corners(a: GridCoord, b: GridCoord) -> (GridCoord, GridCoord) {
+    (
+        GridCoord::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+        GridCoord::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+    )
+}
+
+/// The 3D voxel grid - the physical world
+///
+/// Edits happen in place under the grid's write lock; every edit that changes blocks gets a
+/// new grid revision, stamped on the chunks it touched, and marks them dirty until the next
+/// `take_dirty` (persistence) - `changed_since` serves readers that poll by revision.
+/// Chunks paged out with `unload_chunk` read as Air until inserted again; edits reaching into
+/// them return None and write nothing.
+pub struct GridLayer {
+    chunks: Arc<RwLock<ChunkMap>>,
+}
+
+impl GridLayer {
     pub fn new() -> Self {
         Self {
-            objects: Arc::new(RwLock::new(Vec::new())),
+            chunks: Arc::new(RwLock::new(ChunkMap::default())),
+        }
+    }
+
+    /// Get block at world coordinates
+    pub fn get_block(&self, coord: GridCoord) -> BlockType {
+        self.chunks.read().get(coord)
+    }
+
+    /// Set block at world coordinates, returning the block it replaced (None if its chunk is unloaded)
+    pub fn set_block(&self, coord: GridCoord, block: BlockType) -> Option<BlockType> {
+        let mut previous = None;
+        self.chunks.write().edit(|map, revision| {
+            previous = map.write(coord, block, revision);
+            usize::from(previous.is_some_and(|previous| previous != block))
+        });
+        previous
+    }
+
+    /// Fill a box between two corners (inclusive), returning how many blocks changed (None,
+    /// changing nothing, if the box reaches into an unloaded chunk)
+    ///
+    /// Chunks the box covers entirely become uniform without touching their blocks one by one.
+    pub fn fill_box(&self, a: GridCoord, b: GridCoord, block: BlockType) -> Option<usize> {
+        let (min, max) = corners(a, b);
+        let (low, high) = (min.to_chunk_coord(CHUNK_SIZE), max.to_chunk_coord(CHUNK_SIZE));
+        let mut map = self.chunks.write();
+        if map.reaches_unloaded(low, high) {
+            return None;
+        }
+        Some(map.bulk_edit(|map, revision| {
+            let mut changed = 0;
+            for cy in low.y..=high.y {
+                for cz in low.z..=high.z {
+                    for cx in low.x..=high.x {
+                        let chunk_coord = ChunkCoord::new(cx, cy, cz);
+                        let origin = GridCoord::new(cx * CHUNK_SIZE, cy * CHUNK_SIZE, cz * CHUNK_SIZE);
+                        let end = GridCoord::new(origin.x + CHUNK_SIZE - 1, origin.y + CHUNK_SIZE - 1, origin.z + CHUNK_SIZE - 1);
+                        let (from, to) = (
+                            GridCoord::new(origin.x.max(min.x), origin.y.max(min.y), origin.z.max(min.z)),
+                            GridCoord::new(end.x.min(max.x), end.y.min(max.y), end.z.min(max.z)),
+                        );
+                        if from == origin && to == end {
+                            changed += map.fill_chunk(chunk_coord, block, revision);
+                            continue;
+                        }
+                        for y in from.y..=to.y {
+                            for z in from.z..=to.z {
+                                for x in from.x..=to.x {
+                                    changed += usize::from(map.write(GridCoord::new(x, y, z), block, revision) != Some(block));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            changed
+        }))
+    }
+
+    /// Turn every `from` block in a box into `to`, returning how many blocks changed (see `replace_where`)
+    pub fn replace_in_box(&self, a: GridCoord, b: GridCoord, from: BlockType, to: BlockType) -> Option<usize> {
+        self.replace_where(a, b, |block| (block == from).then_some(to))
+    }
+
+    /// Rewrite the blocks of a box for which `replace` returns a new block, returning how many
+    /// changed (None, changing nothing, if the box reaches into an unloaded chunk)
+    pub fn replace_where(&self, a: GridCoord, b: GridCoord, replace: impl Fn(BlockType) -> Option<BlockType>) -> Option<usize> {
+        let (min, max) = corners(a, b);
+        let mut map = self.chunks.write();
+        if map.reaches_unloaded(min.to_chunk_coord(CHUNK_SIZE), max.to_chunk_coord(CHUNK_SIZE)) {
+            return None;
+        }
+        Some(map.bulk_edit(|map, revision| {
+            let mut changed = 0;
+            for y in min.y..=max.y {
+                for z in min.z..=max.z {
+                    for x in min.x..=max.x {
+                        let coord = GridCoord::new(x, y, z);
+                        if let Some(block) = replace(map.get(coord)) {
+                            changed += usize::from(map.write(coord, block, revision) != Some(block));
+                        }
+                    }
+                }
+            }
+            changed
+        }))
+    }
+
+    /// Place a prefab with its offsets relative to `origin`, returning how many of its writes
+    /// changed a block (None, placing nothing, if any of them falls in an unloaded chunk)
+    pub fn stamp(&self, origin: GridCoord, prefab: &Prefab) -> Option<usize> {
+        let at = |offset: &GridCoord| GridCoord::new(origin.x + offset.x, origin.y + offset.y, origin.z + offset.z);
+        let mut map = self.chunks.write();
+        if prefab.blocks.iter().any(|(offset, _)| map.unloaded.contains(&at(offset).to_chunk_coord(CHUNK_SIZE))) {
+            return None;
+        }
+        Some(map.bulk_edit(|map, revision| {
+            prefab
+                .blocks
+                .iter()
+                .map(|(offset, block)| usize::from(map.write(at(offset), *block, revision) != Some(*block)))
+                .sum()
+        }))
+    }
+
+    /// Add a whole chunk, replacing any chunk at the same coordinate (bulk world generation)
+    ///
+    /// The chunk gets a new revision but is not dirty: it matches wherever it was loaded from.
+    pub fn insert_chunk(&self, mut chunk: Chunk) {
+        chunk.blocks.compact();
+        let mut map = self.chunks.write();
+        map.revision += 1;
+        chunk.revision = map.revision;
+        map.dirty.remove(&chunk.coord);
+        map.unloaded.remove(&chunk.coord);
+        map.chunks.insert(chunk.coord, chunk);
+    }
+
+    /// Take a chunk out of the grid to page it out, with whether it was edited since it was
+    /// loaded (or last taken by `take_dirty`); it stays unloaded until inserted again
+    pub fn unload_chunk(&self, coord: ChunkCoord) -> Option<(Chunk, bool)> {
+        let mut map = self.chunks.write();
+        let chunk = map.chunks.remove(&coord)?;
+        let dirty = map.dirty.remove(&coord);
+        map.unloaded.insert(coord);
+        Some((chunk, dirty))
+    }
+
+    pub fn is_unloaded(&self, coord: ChunkCoord) -> bool {
+        self.chunks.read().unloaded.contains(&coord)
+    }
+
+    /// Replace every loaded chunk (restoring a snapshot); all of them get a new revision and are clean
+    pub fn replace_all_chunks(&self, chunks: Vec<Chunk>) {
+        let mut map = self.chunks.write();
+        map.revision += 1;
+        let revision = map.revision;
+        map.dirty.clear();
+        map.unloaded.clear();
+        map.chunks = chunks
+            .into_iter()
+            .map(|mut chunk| {
+                chunk.blocks.compact();
+                chunk.revision = revision;
+                (chunk.coord, chunk)
+            })
+            .collect();
+    }
+
+    /// Copies of all loaded chunks, in coordinate order
+    pub fn get_all_chunks(&self) -> Vec<Chunk> {
+        let mut chunks: Vec<Chunk> = self.chunks.read().chunks.values().cloned().collect();
+        chunks.sort_unstable_by_key(|c| c.coord);
+        chunks
+    }
+
+    pub fn has_chunk(&self, coord: ChunkCoord) -> bool {
+        self.chunks.read().chunks.contains_key(&coord)
+    }
+
+    /// Chunks that may hold a block type (by palette, so possibly a few that no longer do), in coordinate order
+    pub fn chunks_containing(&self, block: BlockType) -> Vec<ChunkCoord> {
+        let map = self.chunks.read();
+        let mut found: Vec<ChunkCoord> =
+            map.chunks.values().filter(|c| c.blocks.palette().contains(&block)).map(|c| c.coord).collect();
+        found.sort_unstable();
+        found
+    }
+
+    /// Closest block of a type within `max_distance` blocks along each axis (equal distances by coordinate)
+    pub fn nearest_block(&self, center: GridCoord, block: BlockType, max_distance: i32) -> Option<GridCoord> {
+        let (min, max) = (
+            GridCoord::new(center.x - max_distance, center.y - max_distance, center.z - max_distance),
+            GridCoord::new(center.x + max_distance, center.y + max_distance, center.z + max_distance),
+        );
+        let (min_chunk, max_chunk) = (min.to_chunk_coord(CHUNK_SIZE), max.to_chunk_coord(CHUNK_SIZE));
+        let map = self.chunks.read();
+
+        let mut best: Option<(i32, GridCoord)> = None;
+        for chunk in map.chunks.values() {
+            let c = chunk.coord;
+            let inside = (min_chunk.x..=max_chunk.x).contains(&c.x)
+                && (min_chunk.y..=max_chunk.y).contains(&c.y)
+                && (min_chunk.z..=max_chunk.z).contains(&c.z);
+            if !inside || !chunk.blocks.palette().contains(&block) {
+                continue;
+            }
+            let base = GridCoord::new(c.x * CHUNK_SIZE, c.y * CHUNK_SIZE, c.z * CHUNK_SIZE);
+            let range = |lo: i32, hi: i32, base: i32| (lo - base).max(0)..=(hi - base).min(CHUNK_SIZE - 1);
+            for z in range(min.z, max.z, base.z) {
+                for y in range(min.y, max.y, base.y) {
+                    for x in range(min.x, max.x, base.x) {
+                        if chunk.get(x, y, z) != block {
+                            continue;
+                        }
+                        let coord = GridCoord::new(base.x + x, base.y + y, base.z + z);
+                        let (dx, dy, dz) = (coord.x - center.x, coord.y - center.y, coord.z - center.z);
+                        let distance = dx * dx + dy * dy + dz * dz;
+                        if best.is_none_or(|best| (distance, coord) < best) {
+                            best = Some((distance, coord));
+                        }
+                    }
+                }
+            }
         }
+        best.map(|(_, coord)| coord)
+    }
+
+    /// Copy of a loaded chunk
+    pub fn get_chunk(&self, coord: ChunkCoord) -> Option<Chunk> {
+        self.chunks.read().chunks.get(&coord).cloned()
+    }
+
+    /// Current grid revision (the revision of the latest change)
+    pub fn revision(&self) -> u64 {
+        self.chunks.read().revision
     }
 
-    pub fn add(&self, object: DynamicObject) {
-        self.objects.write().push(object);
+    /// Chunks changed after `revision`, in coordinate order
+    pub fn changed_since(&self, revision: u64) -> Vec<ChunkCoord> {
+        let map = self.chunks.read();
+        let mut changed: Vec<ChunkCoord> = map.chunks.values().filter(|c| c.revision > revision).map(|c| c.coord).collect();
+        changed.sort_unstable();
+        changed
     }
 
-    pub fn get_all(&self) -> Vec<DynamicObject> {
-        self.objects.read().clone()
+    pub fn is_dirty(&self, coord: ChunkCoord) -> bool {
+        self.chunks.read().dirty.contains(&coord)
     }
 
-    pub fn remove(&self, id: Uuid) {
-        self.objects.write().retain(|obj| obj.id != id);
+    /// Chunks edited since the last call, in coordinate order, marking them clean
+    pub fn take_dirty(&self) -> Vec<ChunkCoord> {
+        let mut dirty: Vec<ChunkCoord> = self.chunks.write().dirty.drain().collect();
+        dirty.sort_unstable();
+        dirty
+    }
+
+    /// Height of the topmost non-Air block in a column, if any chunk of it is loaded
+    pub fn surface_height(&self, x: i32, z: i32) -> Option<i32> {
+        let map = self.chunks.read();
+        let column = GridCoord::new(x, 0, z).to_chunk_coord(CHUNK_SIZE);
+        let mut stack: Vec<&Chunk> = map
+            .chunks
+            .values()
+            .filter(|c| c.coord.x == column.x && c.coord.z == column.z)
+            .collect();
+        stack.sort_by_key(|c| std::cmp::Reverse(c.coord.y));
+
+        let (local_x, local_z) = (x.rem_euclid(CHUNK_SIZE), z.rem_euclid(CHUNK_SIZE));
+        stack.into_iter().find_map(|chunk| {
+            (0..CHUNK_SIZE)
+                .rev()
+                .find(|y| chunk.get(local_x, *y, local_z) != BlockType::Air)
+                .map(|y| chunk.coord.y * CHUNK_SIZE + y)
+        })
+    }
+
+    /// Where something placed at `x`, `z` stands: on top of the column's surface, or at y 1
+    /// (on flat ground) where no chunk of the column is loaded
+    pub fn standing_position(&self, x: f32, z: f32) -> Position {
+        let y = self.surface_height(x.floor() as i32, z.floor() as i32).map_or(1.0, |top| (top + 1) as f32);
+        Position::new(x, y, z)
+    }
+
+    /// First solid block along a ray within `max_distance`, if any (none for an infinite, NaN or
+    /// negative distance, or a non-finite origin)
+    pub fn raycast(&self, origin: Position, direction: Vector3<f32>, max_distance: f32) -> Option<RayHit> {
+        self.chunks.read().raycast(origin, direction, max_distance)
+    }
+
+    /// Whether nothing solid lies between two points; the block containing `to` itself does not block
+    /// (so a block is in sight of whoever can see its surface)
+    pub fn line_of_sight(&self, from: Position, to: Position) -> bool {
+        self.chunks.read().line_of_sight(from, to)
+    }
+
+    /// Line of sight from one point to each of `targets`, under a single read of the grid
+    pub fn visibility(&self, from: Position, targets: &[Position]) -> Vec<bool> {
+        let map = self.chunks.read();
+        targets.iter().map(|to| map.line_of_sight(from, *to)).collect()
+    }
+
+    /// Check if a position is walkable
+    pub fn is_walkable(&self, coord: GridCoord) -> bool {
+        let block = self.get_block(coord);
+        block.is_walkable()
+    }
+
+    /// Get all loaded chunks
+    pub fn get_loaded_chunks(&self) -> Vec<ChunkCoord> {
+        self.chunks.read().chunks.keys().copied().collect()
+    }
+
+    /// Generate simple terrain (for testing)
+    pub fn generate_simple_terrain(&self, min: GridCoord, max: GridCoord) {
+        // Ground level, with dirt below
+        self.fill_box(GridCoord::new(min.x, 0, min.z), GridCoord::new(max.x, 0, max.z), BlockType::Grass);
+        self.fill_box(GridCoord::new(min.x, -5, min.z), GridCoord::new(max.x, -1, max.z), BlockType::Dirt);
     }
 }
 
-impl Default for DynamicObjectList {
+impl Default for GridLayer {
     fn default() -> Self {
         Self::new()
     }
@@ -187,5 +603,102 @@ mod tests {
         grid.set_block(coord, BlockType::Wood);
         assert_eq!(grid.get_block(coord), BlockType::Wood);
     }
+
+    #[test]
+    fn test_raycast_and_line_of_sight() {
+        let grid = GridLayer::new();
+        grid.set_block(GridCoord::new(4, 1, -3), BlockType::Stone);
+        grid.set_block(GridCoord::new(2, 1, 0), BlockType::Water);
+
+        // Along -z from above the origin; water does not stop the ray
+        let hit = grid.raycast(Position::new(4.5, 1.5, 0.5), Vector3::new(0.0, 0.0, -1.0), 10.0).unwrap();
+        assert_eq!((hit.coord, hit.block), (GridCoord::new(4, 1, -3), BlockType::Stone));
+        assert!((hit.distance - 2.5).abs() < 1e-5);
+        assert_eq!(hit.normal, GridCoord::new(0, 0, 1));
+        assert_eq!(grid.raycast(Position::new(4.5, 1.5, 0.5), Vector3::new(0.0, 0.0, -1.0), 2.0), None);
+        assert_eq!(grid.raycast(Position::new(0.5, 1.5, 0.5), Vector3::new(1.0, 0.0, 0.0), 10.0), None);
+
+        // Unbounded rays through empty space would never end
+        for max_distance in [f32::INFINITY, f32::NAN, -1.0] {
+            assert_eq!(grid.raycast(Position::new(0.5, 9.5, 0.5), Vector3::new(1.0, 0.0, 0.0), max_distance), None);
+        }
+        assert_eq!(grid.raycast(Position::new(f32::NAN, 1.5, 0.5), Vector3::new(1.0, 0.0, 0.0), 10.0), None);
+
+        // Diagonal rays; a block is in sight of its own surface but hides what lies behind it
+        let from = Position::new(0.2, 1.5, 0.9);
+        let targets = [GridCoord::new(4, 1, -3).to_position(), Position::new(7.5, 1.5, -6.5), Position::new(-3.0, 1.5, 4.0)];
+        assert_eq!(grid.visibility(from, &targets), vec![true, false, true]);
+        assert!(!grid.line_of_sight(Position::new(7.5, 1.5, -6.5), from));
+    }
+
+    #[test]
+    fn test_bulk_edits_track_changes() {
+        let grid = GridLayer::new();
+        let (a, b) = (GridCoord::new(-2, 0, -2), GridCoord::new(33, 1, 1));
+        assert_eq!(grid.fill_box(b, a, BlockType::Stone), Some(36 * 2 * 4));
+        assert_eq!(grid.revision(), 1);
+        let touched = grid.take_dirty();
+        let xz: Vec<(i32, i32)> = touched.iter().map(|c| (c.x, c.z)).collect();
+        assert_eq!(xz, vec![(-1, -1), (-1, 0), (0, -1), (0, 0), (1, -1), (1, 0)]);
+        assert!(grid.take_dirty().is_empty());
+
+        // Re-filling with the same block changes nothing and keeps the revision
+        assert_eq!(grid.fill_box(a, b, BlockType::Stone), Some(0));
+        assert_eq!(grid.revision(), 1);
+
+        assert_eq!(grid.replace_in_box(GridCoord::new(0, 0, 0), GridCoord::new(1, 5, 0), BlockType::Stone, BlockType::Iron), Some(4));
+        assert_eq!(grid.get_block(GridCoord::new(1, 1, 0)), BlockType::Iron);
+        assert_eq!(grid.get_block(GridCoord::new(1, 2, 0)), BlockType::Air);
+        assert_eq!(grid.changed_since(1), vec![ChunkCoord::new(0, 0, 0)]);
+        assert!(grid.is_dirty(ChunkCoord::new(0, 0, 0)));
+
+        let hut = Prefab::new()
+            .with_box(GridCoord::new(0, 0, 0), GridCoord::new(2, 2, 2), BlockType::Wood)
+            .with_block(GridCoord::new(1, 1, 1), BlockType::Air);
+        assert_eq!(grid.stamp(GridCoord::new(30, 2, 0), &hut), Some(28));
+        assert_eq!(grid.get_block(GridCoord::new(32, 4, 2)), BlockType::Wood);
+        assert_eq!(grid.get_block(GridCoord::new(31, 3, 1)), BlockType::Air);
+        assert_eq!(grid.changed_since(2), vec![ChunkCoord::new(0, 0, 0), ChunkCoord::new(1, 0, 0)]);
+        assert_eq!(grid.set_block(GridCoord::new(30, 2, 0), BlockType::Stone), Some(BlockType::Wood));
+        assert_eq!(grid.revision(), 4);
+
+        // A box covering whole chunks leaves them uniform
+        let filled = grid.fill_box(GridCoord::new(64, -32, 0), GridCoord::new(95, 31, 31), BlockType::Dirt);
+        assert_eq!(filled, Some(2 * CHUNK_VOLUME));
+        assert_eq!(grid.get_chunk(ChunkCoord::new(2, -1, 0)).unwrap().blocks.uniform(), Some(BlockType::Dirt));
+        assert_eq!(grid.replace_in_box(GridCoord::new(64, 0, 0), GridCoord::new(95, 31, 31), BlockType::Dirt, BlockType::Air), Some(CHUNK_VOLUME));
+        assert_eq!(grid.get_chunk(ChunkCoord::new(2, 0, 0)).unwrap().blocks.uniform(), Some(BlockType::Air));
+    }
+
+    #[test]
+    fn test_unloaded_chunks_refuse_edits_until_loaded_again() {
+        let grid = GridLayer::new();
+        let coord = ChunkCoord::new(0, 0, 0);
+        grid.fill_box(GridCoord::new(0, 0, 0), GridCoord::new(3, 0, 3), BlockType::Stone);
+
+        let (chunk, dirty) = grid.unload_chunk(coord).unwrap();
+        assert!(dirty);
+        assert!(grid.is_unloaded(coord) && !grid.has_chunk(coord));
+        assert_eq!(grid.get_block(GridCoord::new(1, 0, 1)), BlockType::Air);
+        assert_eq!(grid.set_block(GridCoord::new(1, 1, 1), BlockType::Wood), None);
+        assert_eq!(grid.fill_box(GridCoord::new(0, 0, 0), GridCoord::new(31, 31, 31), BlockType::Water), None);
+        let hut = Prefab::new().with_block(GridCoord::new(0, 0, 0), BlockType::Wood);
+        assert_eq!(grid.stamp(GridCoord::new(1, 1, 1), &hut), None);
+
+        // An edit reaching across into the unloaded chunk changes nothing on either side
+        let revision = grid.revision();
+        let across = (GridCoord::new(30, 0, 0), GridCoord::new(33, 0, 0));
+        assert_eq!(grid.replace_where(across.0, across.1, |_| Some(BlockType::Iron)), None);
+        assert_eq!(grid.get_block(GridCoord::new(32, 0, 0)), BlockType::Air);
+        assert!(!grid.has_chunk(ChunkCoord::new(1, 0, 0)));
+        assert_eq!(grid.revision(), revision);
+
+        // Loaded again it is as it was, and clean
+        grid.insert_chunk(chunk);
+        assert!(!grid.is_unloaded(coord));
+        assert_eq!(grid.get_block(GridCoord::new(1, 0, 1)), BlockType::Stone);
+        assert_eq!(grid.get_block(GridCoord::new(1, 1, 1)), BlockType::Air);
+        assert!(!grid.unload_chunk(coord).unwrap().1);
+    }
 }
 
@@ -5,6 +5,12 @@ pub mod content;
 pub mod pathfinding;
 pub mod resources;
 pub mod buildings;
+pub mod terrain;
+pub mod palette;
+pub mod water;
+pub mod fire;
+pub mod objects;
+pub mod mining;
 
 pub use grid::*;
 pub use ecology::*;
@@ -12,4 +18,10 @@ pub use content::*;
 pub use pathfinding::*;
 pub use resources::*;
 pub use buildings::*;
+pub use terrain::*;
+pub use palette::*;
+pub use water::*;
+pub use fire::*;
+pub use objects::*;
+pub use mining::*;
 
@@ -0,0 +1,305 @@
+//! Mining: digging blocks out of the grid at rock and ore nodes
+//!
+//! A mine takes in the columns within [`MINE_RADIUS`] of its node, from just above the highest
+//! surface among them down [`MINE_DEPTH`] blocks. Miners dig the cheapest way in to the nearest
+//! block holding what they're after, so whatever lies in the way comes out first and a worked
+//! mine is a pit and tunnels in the ground. How long a block takes, which tool it needs and what
+//! it drops come from the block table in [`ContentDefinitionLayer`].
+
+use ahash::{AHashMap, AHashSet};
+use std::collections::{BTreeMap, VecDeque};
+use world_sim_core::{BlockType, ChunkCoord, GridCoord, Position, ResourceType};
+
+use crate::content::{ContentDefinitionLayer, Tool};
+use crate::grid::{GridLayer, CHUNK_SIZE};
+use crate::resources::ResourceManager;
+
+/// Columns on each side of its node a mine takes in
+pub const MINE_RADIUS: i32 = 3;
+
+/// How far a mine reaches below the highest surface around it
+pub const MINE_DEPTH: i32 = 12;
+
+/// Blocks miners dig through: natural ground, never walls or trees
+const GROUND: [BlockType; 5] = [BlockType::Grass, BlockType::Dirt, BlockType::Stone, BlockType::Iron, BlockType::Gold];
+
+/// The ground around a rock or ore node that its miners dig
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mine {
+    pub center: (i32, i32),
+    /// Highest layer, the air just over the highest surface in the mine
+    pub top: i32,
+    pub bottom: i32,
+}
+
+impl Mine {
+    /// The mine around a node (`None` if there's no ground there)
+    pub fn around(grid: &GridLayer, position: &Position) -> Option<Self> {
+        let at = position.to_grid_coord();
+        let (x, z) = (at.x, at.z);
+        let surface = (z - MINE_RADIUS..=z + MINE_RADIUS)
+            .flat_map(|z| (x - MINE_RADIUS..=x + MINE_RADIUS).map(move |x| (x, z)))
+            .filter_map(|(x, z)| grid.surface_height(x, z))
+            .max()?;
+        Some(Self { center: (x, z), top: surface + 1, bottom: surface + 1 - MINE_DEPTH })
+    }
+
+    pub fn contains(&self, coord: GridCoord) -> bool {
+        (coord.x - self.center.0).abs() <= MINE_RADIUS
+            && (coord.z - self.center.1).abs() <= MINE_RADIUS
+            && (self.bottom..=self.top).contains(&coord.y)
+    }
+
+    /// Whether all of it is in the grid (paged-out chunks read as air)
+    pub fn is_loaded(&self, grid: &GridLayer) -> bool {
+        let (x, z) = self.center;
+        let low = GridCoord::new(x - MINE_RADIUS, self.bottom, z - MINE_RADIUS).to_chunk_coord(CHUNK_SIZE);
+        let high = GridCoord::new(x + MINE_RADIUS, self.top, z + MINE_RADIUS).to_chunk_coord(CHUNK_SIZE);
+        (low.y..=high.y).all(|y| {
+            (low.z..=high.z).all(|z| (low.x..=high.x).all(|x| !grid.is_unloaded(ChunkCoord::new(x, y, z))))
+        })
+    }
+
+    /// Units of a resource still in the ground
+    pub fn reserve(&self, grid: &GridLayer, content: &ContentDefinitionLayer, resource: ResourceType) -> u32 {
+        let (x, z) = self.center;
+        let mut total = 0;
+        for y in self.bottom..=self.top {
+            for z in z - MINE_RADIUS..=z + MINE_RADIUS {
+                for x in x - MINE_RADIUS..=x + MINE_RADIUS {
+                    let block = grid.get_block(GridCoord::new(x, y, z));
+                    if GROUND.contains(&block) {
+                        total += content.get_block(block).map_or(0, |b| b.yield_of(resource));
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    /// The next block to dig on the way in to the nearest block yielding `resource` (the way
+    /// through the fewest blocks from the air over the node), or `None` once there's none left
+    /// that these tools can reach
+    pub fn face(
+        &self,
+        grid: &GridLayer,
+        content: &ContentDefinitionLayer,
+        resource: ResourceType,
+        tools: &[Tool],
+    ) -> Option<GridCoord> {
+        let mut blocks: AHashMap<GridCoord, BlockType> = AHashMap::new();
+        let mut block = |coord: GridCoord| *blocks.entry(coord).or_insert_with(|| grid.get_block(coord));
+        let diggable = |block: BlockType| {
+            GROUND.contains(&block) && content.get_block(block).is_some_and(|b| b.can_dig_with(tools))
+        };
+
+        // Breadth-first by blocks dug through: open air costs nothing, ground one block each
+        let start = GridCoord::new(self.center.0, self.top, self.center.1);
+        let mut cost: AHashMap<GridCoord, u32> = AHashMap::from_iter([(start, 0)]);
+        let mut parent: AHashMap<GridCoord, GridCoord> = AHashMap::new();
+        let mut settled: AHashSet<GridCoord> = AHashSet::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(at) = queue.pop_front() {
+            if !settled.insert(at) {
+                continue;
+            }
+            let here = block(at);
+            if at != start && content.get_block(here).is_some_and(|b| b.yield_of(resource) > 0) {
+                // Walk back to the first block in the way
+                let mut face = at;
+                let mut step = at;
+                while let Some(previous) = parent.get(&step) {
+                    step = *previous;
+                    if block(step) != BlockType::Air {
+                        face = step;
+                    }
+                }
+                return Some(face);
+            }
+
+            for (dx, dy, dz) in [(0, -1, 0), (1, 0, 0), (-1, 0, 0), (0, 0, 1), (0, 0, -1), (0, 1, 0)] {
+                let next = GridCoord::new(at.x + dx, at.y + dy, at.z + dz);
+                if !self.contains(next) || settled.contains(&next) {
+                    continue;
+                }
+                let step = match block(next) {
+                    BlockType::Air => 0,
+                    other if diggable(other) => 1,
+                    _ => continue,
+                };
+                let total = cost[&at] + step;
+                if cost.get(&next).is_none_or(|known| total < *known) {
+                    cost.insert(next, total);
+                    parent.insert(next, at);
+                    if step == 0 {
+                        queue.push_front(next);
+                    } else {
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Work done on blocks being dug out
+#[derive(Debug, Default)]
+pub struct Mining {
+    progress: BTreeMap<GridCoord, (BlockType, f32)>,
+}
+
+impl Mining {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Put `seconds` of work into a block. Once it has had its hardness in work it becomes air
+    /// and is returned; a block these tools can't dig never comes out.
+    pub fn dig(
+        &mut self,
+        grid: &GridLayer,
+        content: &ContentDefinitionLayer,
+        coord: GridCoord,
+        tools: &[Tool],
+        seconds: f32,
+    ) -> Option<BlockType> {
+        let block = grid.get_block(coord);
+        let definition = content.get_block(block).filter(|b| b.can_dig_with(tools))?;
+
+        // Work on whatever was here before doesn't count
+        let work = match self.progress.get(&coord) {
+            Some((worked, done)) if *worked == block => done + seconds,
+            _ => seconds,
+        };
+        if work < definition.hardness {
+            self.progress.insert(coord, (block, work));
+            return None;
+        }
+        self.progress.remove(&coord);
+        grid.set_block(coord, BlockType::Air)?;
+        Some(block)
+    }
+}
+
+/// Set each mine node's quantity to what its ground still holds
+pub fn survey_mines(grid: &GridLayer, content: &ContentDefinitionLayer, resources: &ResourceManager) {
+    for node in resources.get_nodes() {
+        let Some(ore) = node.resource_type.ore() else { continue };
+        let reserve = Mine::around(grid, &node.position).map_or(0, |mine| mine.reserve(grid, content, ore));
+        resources.set_quantity(node.id, reserve);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_miners_dig_down_through_the_ground_to_the_ore() {
+        let grid = GridLayer::new();
+        grid.generate_simple_terrain(GridCoord::new(-10, 0, -10), GridCoord::new(10, 0, 10));
+        grid.fill_box(GridCoord::new(1, -4, 0), GridCoord::new(1, -3, 0), BlockType::Iron);
+        let content = ContentDefinitionLayer::new();
+        let mine = Mine::around(&grid, &Position::new(0.5, 1.0, 0.5)).unwrap();
+        assert_eq!((mine.top, mine.bottom), (1, -11));
+        assert_eq!(mine.reserve(&grid, &content, ResourceType::Iron), 20);
+
+        // Bare hands can't get at iron; with a pickaxe the shaft goes straight down over the ore
+        assert_eq!(mine.face(&grid, &content, ResourceType::Iron, &[]), None);
+        let tools = [Tool::Pickaxe, Tool::Shovel];
+        assert_eq!(mine.face(&grid, &content, ResourceType::Iron, &tools), Some(GridCoord::new(1, 0, 0)));
+
+        let mut mining = Mining::new();
+        let mut dug = Vec::new();
+        while let Some(face) = mine.face(&grid, &content, ResourceType::Iron, &tools) {
+            if let Some(block) = mining.dig(&grid, &content, face, &tools, 0.5) {
+                dug.push(block);
+            }
+            assert!(dug.len() < 10, "dug too much: {:?}", dug);
+        }
+        // Grass, two layers of dirt, then both iron blocks
+        assert_eq!(dug, [BlockType::Grass, BlockType::Dirt, BlockType::Dirt, BlockType::Iron, BlockType::Iron]);
+        assert_eq!(mine.reserve(&grid, &content, ResourceType::Iron), 0);
+        assert_eq!(grid.get_block(GridCoord::new(1, -2, 0)), BlockType::Air);
+        assert_eq!(grid.get_block(GridCoord::new(0, -2, 0)), BlockType::Dirt);
+        assert_eq!(grid.get_block(GridCoord::new(1, -4, 0)), BlockType::Air);
+    }
+
+    #[test]
+    fn test_block_table_tools_and_drops() {
+        let content = ContentDefinitionLayer::new();
+        let gold = content.get_block(BlockType::Gold).unwrap();
+        assert_eq!(gold.drops, [(ResourceType::Gold, 2)]);
+        assert!(!gold.can_dig_with(&[Tool::Shovel]));
+        assert!(content.get_block(BlockType::Dirt).unwrap().drops.is_empty());
+        assert!(content.get_block(BlockType::Water).is_none());
+
+        let grid = GridLayer::new();
+        let at = GridCoord::new(0, 0, 0);
+        grid.set_block(at, BlockType::Stone);
+        let mut mining = Mining::new();
+
+        // No pickaxe, no progress, however long it takes
+        assert_eq!(mining.dig(&grid, &content, at, &[Tool::Shovel], 100.0), None);
+        assert_eq!(grid.get_block(at), BlockType::Stone);
+
+        // Stone takes two seconds of work with one
+        assert_eq!(mining.dig(&grid, &content, at, &[Tool::Pickaxe], 1.5), None);
+        assert_eq!(mining.dig(&grid, &content, at, &[Tool::Pickaxe], 0.5), Some(BlockType::Stone));
+        assert_eq!(grid.get_block(at), BlockType::Air);
+
+        // Work on a block doesn't carry over to what replaces it
+        grid.set_block(at, BlockType::Dirt);
+        assert_eq!(mining.dig(&grid, &content, at, &[], 0.5), None);
+        grid.set_block(at, BlockType::Grass);
+        assert_eq!(mining.dig(&grid, &content, at, &[], 0.5), None);
+        assert_eq!(mining.dig(&grid, &content, at, &[], 0.5), Some(BlockType::Grass));
+    }
+
+    #[test]
+    fn test_gold_veins_are_mined_out() {
+        let grid = GridLayer::new();
+        grid.generate_simple_terrain(GridCoord::new(-10, 0, -10), GridCoord::new(10, 0, 10));
+        grid.fill_box(GridCoord::new(-1, -2, 0), GridCoord::new(0, -2, 0), BlockType::Gold);
+        let content = ContentDefinitionLayer::new();
+        let mine = Mine::around(&grid, &Position::new(0.5, 1.0, 0.5)).unwrap();
+        assert_eq!(mine.reserve(&grid, &content, ResourceType::Gold), 4);
+
+        let tools = [Tool::Pickaxe, Tool::Shovel];
+        let mut mining = Mining::new();
+        let mut gold = 0;
+        while let Some(face) = mine.face(&grid, &content, ResourceType::Gold, &tools) {
+            if let Some(block) = mining.dig(&grid, &content, face, &tools, 1.0) {
+                gold += content.get_block(block).unwrap().yield_of(ResourceType::Gold);
+            }
+        }
+        assert_eq!(gold, 4);
+        assert_eq!(mine.reserve(&grid, &content, ResourceType::Gold), 0);
+    }
+
+    #[test]
+    fn test_survey_sets_mine_nodes_to_their_reserves() {
+        use crate::resources::{ResourceNode, ResourceNodeType};
+
+        let grid = GridLayer::new();
+        grid.generate_simple_terrain(GridCoord::new(-30, 0, -10), GridCoord::new(30, 0, 10));
+        let resources = ResourceManager::new();
+        let rock = ResourceNode::new(ResourceNodeType::Rock, Position::new(-20.5, 1.0, 0.5), 999);
+        let gold = ResourceNode::new(ResourceNodeType::GoldDeposit, Position::new(0.5, 1.0, 0.5), 999);
+        let tree = ResourceNode::new(ResourceNodeType::Tree, Position::new(20.5, 1.0, 0.5), 120);
+        let ids = [rock.id, gold.id, tree.id];
+        for node in [rock, gold, tree] {
+            resources.add_node(node);
+        }
+        grid.fill_box(GridCoord::new(-21, -3, 0), GridCoord::new(-20, -3, 0), BlockType::Stone);
+        grid.set_block(GridCoord::new(0, -3, 0), BlockType::Gold);
+
+        survey_mines(&grid, &ContentDefinitionLayer::new(), &resources);
+        let quantity = |id| resources.get_nodes().into_iter().find(|n| n.id == id).unwrap().quantity;
+        assert_eq!(quantity(ids[0]), 20);
+        assert_eq!(quantity(ids[1]), 2);
+        // Trees aren't mines
+        assert_eq!(quantity(ids[2]), 120);
+    }
+}
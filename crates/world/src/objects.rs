@@ -0,0 +1,324 @@
+//! Dynamic objects: carts, boats and siege engines
+//!
+//! Unlike buildings they are not made of blocks. Each has a position, a heading and a speed,
+//! follows a route of surface blocks planned with [`find_surface_path`] and only moves while
+//! it has its full crew aboard: carts and siege engines roll over open ground, boats sail on water.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use world_sim_core::{sim_uuid, AgentId, BlockType, GridCoord, Position, ResourceType};
+
+use crate::buildings::BuildingOwner;
+use crate::grid::GridLayer;
+use crate::pathfinding::find_surface_path;
+
+/// Most columns a route search may expand before giving up
+const ROUTE_SEARCH_LIMIT: usize = 20_000;
+
+/// Closer than this to a waypoint counts as passing it
+const WAYPOINT_RADIUS: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ObjectKind {
+    Cart,
+    Boat,
+    Catapult,
+}
+
+impl ObjectKind {
+    /// Units of goods it can carry
+    pub fn capacity(&self) -> u32 {
+        match self {
+            ObjectKind::Cart => 120,
+            ObjectKind::Boat => 400,
+            ObjectKind::Catapult => 0,
+        }
+    }
+
+    /// Agents needed aboard to move it
+    pub fn crew(&self) -> usize {
+        match self {
+            ObjectKind::Cart => 1,
+            ObjectKind::Boat => 2,
+            ObjectKind::Catapult => 4,
+        }
+    }
+
+    /// Top speed in blocks per second
+    pub fn max_speed(&self) -> f32 {
+        match self {
+            ObjectKind::Cart => 5.0,
+            ObjectKind::Boat => 4.0,
+            ObjectKind::Catapult => 1.5,
+        }
+    }
+
+    /// Speed gained, or shed when stopping, per second
+    pub fn acceleration(&self) -> f32 {
+        match self {
+            ObjectKind::Cart => 2.5,
+            ObjectKind::Boat => 1.0,
+            ObjectKind::Catapult => 0.5,
+        }
+    }
+
+    /// Whether it can move from the top block of one column onto the top block of the next
+    pub fn can_cross(&self, from: (GridCoord, BlockType), to: (GridCoord, BlockType)) -> bool {
+        match self {
+            ObjectKind::Boat => to.1 == BlockType::Water,
+            ObjectKind::Cart | ObjectKind::Catapult => {
+                (to.0.y - from.0.y).abs() <= 1 && matches!(to.1, BlockType::Grass | BlockType::Dirt | BlockType::Stone)
+            }
+        }
+    }
+}
+
+/// A cart, boat or siege engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicObject {
+    pub id: Uuid,
+    pub kind: ObjectKind,
+    pub position: Position,
+    /// Direction of travel in the x-z plane, radians from +x towards +z
+    pub heading: f32,
+    /// Blocks per second along the heading
+    pub speed: f32,
+    pub owner: BuildingOwner,
+    /// Agents aboard (driver or helmsman first)
+    pub crew: Vec<AgentId>,
+    pub cargo: BTreeMap<ResourceType, u32>,
+    /// Surface blocks still to pass over, next first
+    pub route: Vec<GridCoord>,
+}
+
+impl DynamicObject {
+    pub fn new(kind: ObjectKind, position: Position, owner: BuildingOwner) -> Self {
+        Self {
+            id: sim_uuid(),
+            kind,
+            position,
+            heading: 0.0,
+            speed: 0.0,
+            owner,
+            crew: Vec::new(),
+            cargo: BTreeMap::new(),
+            route: Vec::new(),
+        }
+    }
+
+    /// Velocity along x and z in blocks per second
+    pub fn velocity(&self) -> (f32, f32) {
+        (self.speed * self.heading.cos(), self.speed * self.heading.sin())
+    }
+
+    pub fn is_crewed(&self) -> bool {
+        self.crew.len() >= self.kind.crew()
+    }
+
+    pub fn load(&self) -> u32 {
+        self.cargo.values().sum()
+    }
+
+    pub fn free_capacity(&self) -> u32 {
+        self.kind.capacity().saturating_sub(self.load())
+    }
+
+    /// Load up to `quantity` of a resource, returning how much fit
+    pub fn load_cargo(&mut self, resource: ResourceType, quantity: u32) -> u32 {
+        let loaded = quantity.min(self.free_capacity());
+        if loaded > 0 {
+            *self.cargo.entry(resource).or_insert(0) += loaded;
+        }
+        loaded
+    }
+
+    /// Unload up to `quantity` of a resource, returning how much came off
+    pub fn unload_cargo(&mut self, resource: ResourceType, quantity: u32) -> u32 {
+        let Some(held) = self.cargo.get_mut(&resource) else { return 0 };
+        let unloaded = quantity.min(*held);
+        *held -= unloaded;
+        if *held == 0 {
+            self.cargo.remove(&resource);
+        }
+        unloaded
+    }
+
+    /// Plan a route over the surface to within `reach` columns of `goal`, replacing the current one
+    /// (false, leaving no route, if there is no way there)
+    pub fn plan_route(&mut self, grid: &GridLayer, goal: GridCoord, reach: i32) -> bool {
+        let kind = self.kind;
+        let path = find_surface_path(grid, self.position.to_grid_coord(), goal, reach, ROUTE_SEARCH_LIMIT, |from, to| {
+            kind.can_cross(from, to)
+        });
+        match path {
+            Some(mut path) => {
+                path.remove(0); // The block it is on
+                self.route = path;
+                true
+            }
+            None => {
+                self.route.clear();
+                false
+            }
+        }
+    }
+
+    pub fn has_arrived(&self) -> bool {
+        self.route.is_empty()
+    }
+
+    /// Advance `seconds` along the route: speeding up towards top speed while crewed and far from
+    /// the end, slowing down to stop at it (or wherever it is if the crew leaves).
+    /// Returns whether it reached the end of its route this step.
+    pub fn step(&mut self, seconds: f32) -> bool {
+        let (top_speed, acceleration) = (self.kind.max_speed(), self.kind.acceleration());
+        if self.route.is_empty() || !self.is_crewed() {
+            self.speed = (self.speed - acceleration * seconds).max(0.0);
+            return false;
+        }
+
+        // Brake in time to stop at the last waypoint, but never stall short of it
+        let remaining = self.distance_to_end();
+        let stopping = self.speed * self.speed / (2.0 * acceleration);
+        self.speed = if remaining <= stopping {
+            (self.speed - acceleration * seconds).max(acceleration * seconds)
+        } else {
+            (self.speed + acceleration * seconds).min(top_speed)
+        };
+
+        let mut travel = self.speed * seconds;
+        while let Some(next) = self.route.first() {
+            let target = stand_on(*next);
+            let (dx, dz) = (target.x - self.position.x, target.z - self.position.z);
+            let distance = (dx * dx + dz * dz).sqrt();
+            if distance > WAYPOINT_RADIUS {
+                self.heading = dz.atan2(dx);
+            }
+            if distance > travel {
+                self.position.x += dx / distance * travel;
+                self.position.z += dz / distance * travel;
+                break;
+            }
+            travel -= distance;
+            self.position = target;
+            self.route.remove(0);
+        }
+
+        if self.route.is_empty() {
+            self.speed = 0.0;
+            return true;
+        }
+        false
+    }
+
+    /// Distance left along the route
+    fn distance_to_end(&self) -> f32 {
+        let mut at = self.position;
+        let mut total = 0.0;
+        for waypoint in &self.route {
+            let next = stand_on(*waypoint);
+            total += ((next.x - at.x).powi(2) + (next.z - at.z).powi(2)).sqrt();
+            at = next;
+        }
+        total
+    }
+}
+
+/// Where an object rests on a surface block: centred on top of it
+pub fn stand_on(block: GridCoord) -> Position {
+    Position::new(block.x as f32 + 0.5, (block.y + 1) as f32, block.z as f32 + 0.5)
+}
+
+/// All dynamic objects in the world
+#[derive(Debug, Default)]
+pub struct ObjectManager {
+    objects: BTreeMap<Uuid, DynamicObject>, // Sorted by id so seeded runs iterate in the same order
+}
+
+impl ObjectManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, object: DynamicObject) -> Uuid {
+        let id = object.id;
+        self.objects.insert(id, object);
+        id
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&DynamicObject> {
+        self.objects.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: Uuid) -> Option<&mut DynamicObject> {
+        self.objects.get_mut(&id)
+    }
+
+    pub fn get_all(&self) -> Vec<&DynamicObject> {
+        self.objects.values().collect()
+    }
+
+    pub fn get_all_mut(&mut self) -> impl Iterator<Item = &mut DynamicObject> {
+        self.objects.values_mut()
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Option<DynamicObject> {
+        self.objects.remove(&id)
+    }
+
+    /// The object an agent is aboard, if any
+    pub fn aboard(&self, agent: AgentId) -> Option<&DynamicObject> {
+        self.objects.values().find(|o| o.crew.contains(&agent))
+    }
+
+    /// Nearest object of a kind with nobody aboard within `radius` across the ground
+    /// (ties go to the lower id)
+    pub fn nearest_free(&self, position: &Position, kind: ObjectKind, radius: f32) -> Option<&DynamicObject> {
+        self.objects
+            .values()
+            .filter(|o| o.kind == kind && o.crew.is_empty())
+            .map(|o| (o.position.horizontal_distance_to(position), o))
+            .filter(|(distance, _)| *distance <= radius)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, o)| o)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_carts_drive_around_water_and_boats_stay_on_it() {
+        let grid = GridLayer::new();
+        grid.generate_simple_terrain(GridCoord::new(0, 0, 0), GridCoord::new(20, 0, 20));
+        // A pond across the middle with a dry crossing at z = 18
+        grid.fill_box(GridCoord::new(10, 0, 0), GridCoord::new(11, 0, 17), BlockType::Water);
+
+        let mut cart = DynamicObject::new(ObjectKind::Cart, Position::new(2.5, 1.0, 2.5), BuildingOwner::Public);
+        assert!(cart.plan_route(&grid, GridCoord::new(18, 0, 2), 0));
+        assert!(cart.route.iter().all(|c| grid.get_block(*c) == BlockType::Grass));
+        assert!(cart.route.iter().any(|c| c.z == 18));
+
+        // Nobody aboard: it stays put
+        assert!(!cart.step(1.0));
+        assert_eq!(cart.position, Position::new(2.5, 1.0, 2.5));
+        cart.crew.push(AgentId::new());
+        let mut seconds = 0;
+        while !cart.step(0.1) {
+            seconds += 1;
+            assert!(cart.speed <= ObjectKind::Cart.max_speed());
+            assert!(seconds < 1000, "cart never arrived");
+        }
+        assert_eq!(cart.position, Position::new(18.5, 1.0, 2.5));
+        assert_eq!(cart.speed, 0.0);
+
+        let mut boat = DynamicObject::new(ObjectKind::Boat, Position::new(10.5, 1.0, 1.5), BuildingOwner::Public);
+        assert!(boat.plan_route(&grid, GridCoord::new(11, 0, 16), 0));
+        assert!(!boat.plan_route(&grid, GridCoord::new(18, 0, 2), 0));
+        assert_eq!(boat.load_cargo(ResourceType::Wood, 500), 400);
+        assert_eq!(boat.unload_cargo(ResourceType::Wood, 100), 100);
+        assert_eq!(boat.free_capacity(), 100);
+    }
+}
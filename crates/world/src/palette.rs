@@ -0,0 +1,292 @@
+//! Palette-compressed block storage for chunks
+//!
+//! A chunk rarely holds more than a handful of block types, so instead of one `BlockType` per
+//! block it stores a palette of the types present and a bit-packed index into it per block
+//! (1, 2, 4 or 8 bits). A chunk of a single type - open air, solid rock - needs no indices at all.
+//!
+//! On the wire (snapshots, the network API) blocks are run-length encoded over the palette:
+//! `{"palette": [...], "runs": [[index, length], ...]}` in flattened (x fastest, then y, then z) order.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use world_sim_core::BlockType;
+
+use crate::grid::CHUNK_SIZE;
+
+/// Blocks in a chunk (the flattened length of the storage)
+pub const CHUNK_VOLUME: usize = (CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+/// Blocks of one chunk, in flattened order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkBlocks {
+    /// Every block is the same
+    Uniform(BlockType),
+    /// Bit-packed palette indices; the palette may hold types no longer used until `compact`
+    Paletted {
+        palette: Vec<BlockType>,
+        bits: u32,
+        words: Vec<u64>,
+    },
+}
+
+impl Default for ChunkBlocks {
+    fn default() -> Self {
+        Self::Uniform(BlockType::Air)
+    }
+}
+
+impl ChunkBlocks {
+    pub fn get(&self, index: usize) -> BlockType {
+        match self {
+            Self::Uniform(block) => *block,
+            Self::Paletted { palette, bits, words } => palette[read(words, *bits, index)],
+        }
+    }
+
+    pub fn set(&mut self, index: usize, block: BlockType) {
+        if let Self::Uniform(current) = *self {
+            if current == block {
+                return;
+            }
+            *self = Self::Paletted {
+                palette: vec![current],
+                bits: 1,
+                words: vec![0; words_for(1)],
+            };
+        }
+        let Self::Paletted { palette, bits, words } = self else {
+            unreachable!("uniform storage was just converted");
+        };
+
+        let entry = match palette.iter().position(|b| *b == block) {
+            Some(entry) => entry,
+            None => {
+                palette.push(block);
+                if palette.len() > 1 << *bits {
+                    let wider = *bits * 2;
+                    *words = repack(words, *bits, wider, |entry| entry);
+                    *bits = wider;
+                }
+                palette.len() - 1
+            }
+        };
+        write(words, *bits, index, entry);
+    }
+
+    /// The block type, if every block is the same
+    pub fn uniform(&self) -> Option<BlockType> {
+        match self {
+            Self::Uniform(block) => Some(*block),
+            Self::Paletted { .. } => None,
+        }
+    }
+
+    /// Block types in the palette (a uniform chunk has just its one)
+    pub fn palette(&self) -> &[BlockType] {
+        match self {
+            Self::Uniform(block) => std::slice::from_ref(block),
+            Self::Paletted { palette, .. } => palette,
+        }
+    }
+
+    /// Drop unused palette entries, narrowing the indices, and collapse to `Uniform` if possible
+    pub fn compact(&mut self) {
+        let Self::Paletted { palette, bits, words } = self else {
+            return;
+        };
+        let mut used = vec![false; palette.len()];
+        for index in 0..CHUNK_VOLUME {
+            used[read(words, *bits, index)] = true;
+        }
+
+        // Old entry -> new entry, keeping palette order
+        let mut remap = vec![0; palette.len()];
+        let mut kept = Vec::new();
+        for (entry, block) in palette.iter().enumerate() {
+            if used[entry] {
+                remap[entry] = kept.len();
+                kept.push(*block);
+            }
+        }
+        if kept.len() == 1 {
+            *self = Self::Uniform(kept[0]);
+            return;
+        }
+        let narrow = bits_for(kept.len());
+        if kept.len() < palette.len() || narrow < *bits {
+            *words = repack(words, *bits, narrow, |entry| remap[entry]);
+            *bits = narrow;
+            *palette = kept;
+        }
+    }
+
+    /// Run-length encoding as (palette entry, length) pairs
+    fn runs(&self) -> Vec<(u8, u16)> {
+        let mut runs: Vec<(u8, u16)> = Vec::new();
+        for index in 0..CHUNK_VOLUME {
+            let entry = match self {
+                Self::Uniform(_) => 0,
+                Self::Paletted { bits, words, .. } => read(words, *bits, index) as u8,
+            };
+            match runs.last_mut() {
+                Some((last, length)) if *last == entry => *length += 1,
+                _ => runs.push((entry, 1)),
+            }
+        }
+        runs
+    }
+}
+
+/// Index bits per block for a palette of `len` entries
+fn bits_for(len: usize) -> u32 {
+    match len {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    }
+}
+
+fn words_for(bits: u32) -> usize {
+    CHUNK_VOLUME * bits as usize / 64
+}
+
+fn read(words: &[u64], bits: u32, index: usize) -> usize {
+    let per_word = 64 / bits as usize;
+    let shift = (index % per_word) as u32 * bits;
+    ((words[index / per_word] >> shift) & ((1 << bits) - 1)) as usize
+}
+
+fn write(words: &mut [u64], bits: u32, index: usize, entry: usize) {
+    let per_word = 64 / bits as usize;
+    let shift = (index % per_word) as u32 * bits;
+    let mask = ((1u64 << bits) - 1) << shift;
+    let word = &mut words[index / per_word];
+    *word = (*word & !mask) | ((entry as u64) << shift);
+}
+
+fn repack(words: &[u64], from: u32, to: u32, map: impl Fn(usize) -> usize) -> Vec<u64> {
+    let mut packed = vec![0; words_for(to)];
+    for index in 0..CHUNK_VOLUME {
+        write(&mut packed, to, index, map(read(words, from, index)));
+    }
+    packed
+}
+
+/// Wire form of `ChunkBlocks`
+#[derive(Serialize, Deserialize)]
+struct Encoded {
+    palette: Vec<BlockType>,
+    runs: Vec<(u8, u16)>,
+}
+
+impl Serialize for ChunkBlocks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Compact a copy so unused palette entries never reach the wire
+        let mut compact = self.clone();
+        compact.compact();
+        Encoded {
+            palette: compact.palette().to_vec(),
+            runs: compact.runs(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChunkBlocks {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = Encoded::deserialize(deserializer)?;
+        if encoded.palette.is_empty() || encoded.palette.len() > 256 {
+            return Err(D::Error::custom(format!("chunk palette has {} entries", encoded.palette.len())));
+        }
+        let total: usize = encoded.runs.iter().map(|(_, length)| *length as usize).sum();
+        if total != CHUNK_VOLUME {
+            return Err(D::Error::custom(format!("chunk runs cover {} blocks, expected {}", total, CHUNK_VOLUME)));
+        }
+        if let Some((entry, _)) = encoded.runs.iter().find(|(entry, _)| *entry as usize >= encoded.palette.len()) {
+            return Err(D::Error::custom(format!("chunk run uses palette entry {} of {}", entry, encoded.palette.len())));
+        }
+
+        let bits = bits_for(encoded.palette.len());
+        let mut words = vec![0; words_for(bits)];
+        let mut index = 0;
+        for (entry, length) in encoded.runs {
+            for _ in 0..length {
+                write(&mut words, bits, index, entry as usize);
+                index += 1;
+            }
+        }
+        let mut blocks = Self::Paletted { palette: encoded.palette, bits, words };
+        blocks.compact();
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const TYPES: [BlockType; 11] = [
+        BlockType::Air,
+        BlockType::WallStone,
+        BlockType::WallWood,
+        BlockType::Water,
+        BlockType::Dirt,
+        BlockType::Grass,
+        BlockType::Wood,
+        BlockType::BurningWood,
+        BlockType::Stone,
+        BlockType::Iron,
+        BlockType::Gold,
+    ];
+
+    #[test]
+    fn test_random_chunks_round_trip() {
+        let mut rng = StdRng::seed_from_u64(43);
+        for types in [1, 2, 3, 5, 11] {
+            // Mostly long runs of a few types, with scattered single blocks
+            let mut expected = vec![BlockType::Air; CHUNK_VOLUME];
+            let mut blocks = ChunkBlocks::default();
+            let mut index = 0;
+            while index < CHUNK_VOLUME {
+                let block = TYPES[rng.gen_range(0..types)];
+                let length = if rng.gen_bool(0.3) { 1 } else { rng.gen_range(1..600) };
+                let end = (index + length).min(CHUNK_VOLUME);
+                expected[index..end].fill(block);
+                (index..end).for_each(|i| blocks.set(i, block));
+                index += length;
+            }
+
+            assert!((0..CHUNK_VOLUME).all(|i| blocks.get(i) == expected[i]));
+            let json: ChunkBlocks = serde_json::from_str(&serde_json::to_string(&blocks).unwrap()).unwrap();
+            let binary: ChunkBlocks = bincode::deserialize(&bincode::serialize(&blocks).unwrap()).unwrap();
+            for decoded in [json, binary] {
+                assert!((0..CHUNK_VOLUME).all(|i| decoded.get(i) == expected[i]), "{} types", types);
+                assert!(decoded.palette().len() <= types);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uniform_fast_path() {
+        let mut blocks = ChunkBlocks::Uniform(BlockType::Stone);
+        blocks.set(7, BlockType::Stone);
+        assert_eq!(blocks.uniform(), Some(BlockType::Stone));
+
+        blocks.set(7, BlockType::Iron);
+        assert_eq!(blocks.get(7), BlockType::Iron);
+        assert_eq!(blocks.get(8), BlockType::Stone);
+        assert_eq!(blocks.uniform(), None);
+
+        // Mining the ore back out leaves a single type again
+        blocks.set(7, BlockType::Stone);
+        blocks.compact();
+        assert_eq!(blocks, ChunkBlocks::Uniform(BlockType::Stone));
+        assert_eq!(bincode::serialize(&blocks).unwrap().len(), 8 + 4 + 8 + 3);
+
+        let bad = r#"{"palette": ["Air"], "runs": [[0, 100]]}"#;
+        assert!(serde_json::from_str::<ChunkBlocks>(bad).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::{BinaryHeap, HashMap};
 use std::cmp::Ordering;
 use std::sync::Arc;
-use world_sim_core::GridCoord;
+use world_sim_core::{BlockType, GridCoord};
 use crate::GridLayer;
 
 /// A* pathfinding node
@@ -39,7 +39,7 @@ pub fn find_path(grid: &GridLayer, start: GridCoord, goal: GridCoord, max_iterat
     open_set.push(Node {
         coord: start,
         g_cost: 0,
-        h_cost: start.manhattan_distance(&goal),
+        h_cost: heuristic(start, goal),
         parent: None,
     });
     
@@ -51,16 +51,24 @@ pub fn find_path(grid: &GridLayer, start: GridCoord, goal: GridCoord, max_iterat
             return None; // Timeout
         }
         
+        // Skip stale duplicates of nodes that were already expanded
+        if closed_set.contains_key(&current.coord) {
+            continue;
+        }
+        closed_set.insert(current.coord, current.parent.unwrap_or(start));
+        
         if current.coord == goal {
             // Reconstruct path
             let mut path = vec![current.coord];
             let mut current_coord = current.coord;
             
-            while let Some(parent) = closed_set.get(&current_coord) {
-                path.push(*parent);
-                current_coord = *parent;
-                if current_coord == start {
-                    break;
+            while current_coord != start {
+                match closed_set.get(&current_coord) {
+                    Some(parent) => {
+                        path.push(*parent);
+                        current_coord = *parent;
+                    }
+                    None => break,
                 }
             }
             
@@ -80,7 +88,7 @@ pub fn find_path(grid: &GridLayer, start: GridCoord, goal: GridCoord, max_iterat
             }
             
             let g_cost = current.g_cost + 10; // Cost to move to neighbor
-            let h_cost = neighbor.manhattan_distance(&goal);
+            let h_cost = heuristic(neighbor, goal);
             
             open_set.push(Node {
                 coord: neighbor,
@@ -89,13 +97,87 @@ pub fn find_path(grid: &GridLayer, start: GridCoord, goal: GridCoord, max_iterat
                 parent: Some(current.coord),
             });
         }
-        
-        closed_set.insert(current.coord, current.parent.unwrap_or(start));
     }
     
     None // No path found
 }
 
+/// A* over the surface of the grid, one column at a time (4-directional)
+///
+/// Steps go from the top block of one column to the top block of a neighbouring one where
+/// `can_cross(from, to)` allows, given each top block and its type. The search ends at the first
+/// column within `reach` columns (Manhattan) of `goal`, whose height is ignored. Returns the top
+/// blocks passed through, starting with the one under `start`.
+pub fn find_surface_path(
+    grid: &GridLayer,
+    start: GridCoord,
+    goal: GridCoord,
+    reach: i32,
+    max_iterations: usize,
+    can_cross: impl Fn((GridCoord, BlockType), (GridCoord, BlockType)) -> bool,
+) -> Option<Vec<GridCoord>> {
+    // Columns are looked up again and again; a surface lookup scans the column's chunks
+    let mut tops: HashMap<(i32, i32), Option<(GridCoord, BlockType)>> = HashMap::new();
+    let mut top = |x: i32, z: i32| {
+        *tops.entry((x, z)).or_insert_with(|| {
+            grid.surface_height(x, z).map(|y| {
+                let coord = GridCoord::new(x, y, z);
+                (coord, grid.get_block(coord))
+            })
+        })
+    };
+    let remaining = |coord: GridCoord| ((coord.x - goal.x).abs() + (coord.z - goal.z).abs() - reach).max(0) * 10;
+
+    let first = top(start.x, start.z)?;
+    let mut open_set = BinaryHeap::new();
+    let mut closed_set: HashMap<GridCoord, GridCoord> = HashMap::new();
+    open_set.push(Node { coord: first.0, g_cost: 0, h_cost: remaining(first.0), parent: None });
+
+    let mut iterations = 0;
+    while let Some(current) = open_set.pop() {
+        iterations += 1;
+        if iterations > max_iterations {
+            return None;
+        }
+        if closed_set.contains_key(&current.coord) {
+            continue;
+        }
+        closed_set.insert(current.coord, current.parent.unwrap_or(current.coord));
+
+        if remaining(current.coord) == 0 {
+            let mut path = vec![current.coord];
+            let mut at = current.coord;
+            while at != first.0 {
+                at = closed_set[&at];
+                path.push(at);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let from = (current.coord, grid.get_block(current.coord));
+        for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let Some(to) = top(current.coord.x + dx, current.coord.z + dz) else { continue };
+            if closed_set.contains_key(&to.0) || !can_cross(from, to) {
+                continue;
+            }
+            open_set.push(Node {
+                coord: to.0,
+                g_cost: current.g_cost + 10,
+                h_cost: remaining(to.0),
+                parent: Some(current.coord),
+            });
+        }
+    }
+
+    None
+}
+
+/// Manhattan distance scaled to the per-step movement cost
+fn heuristic(from: GridCoord, to: GridCoord) -> i32 {
+    from.manhattan_distance(&to) * 10
+}
+
 /// Get neighboring coordinates (6-directional for 3D)
 fn get_neighbors(coord: GridCoord) -> Vec<GridCoord> {
     vec![
@@ -139,7 +221,6 @@ impl HierarchicalPathfinding {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use world_sim_core::BlockType;
 
     #[test]
     fn test_simple_path() {
@@ -159,5 +240,45 @@ mod tests {
         let path = find_path(&grid, start, goal, 1000);
         assert!(path.is_some());
     }
-}
 
+    /// Every step moves to a face neighbour
+    fn assert_contiguous(path: &[GridCoord]) {
+        for step in path.windows(2) {
+            assert_eq!(step[0].manhattan_distance(&step[1]), 1, "{:?} -> {:?}", step[0], step[1]);
+        }
+    }
+
+    #[test]
+    fn test_path_runs_from_start_to_goal() {
+        let grid = GridLayer::new();
+        let start = GridCoord::new(0, 1, 0);
+        let goal = GridCoord::new(3, 1, 2);
+
+        let path = find_path(&grid, start, goal, 1000).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_contiguous(&path);
+        // Shortest: one node per step plus the start
+        assert_eq!(path.len(), 6);
+    }
+
+    #[test]
+    fn test_path_takes_shortest_detour() {
+        let grid = GridLayer::new();
+        // A 3x3 stone plate across the straight line; going around it costs 4 extra steps
+        for y in -1..=1 {
+            for z in -1..=1 {
+                grid.set_block(GridCoord::new(2, y, z), BlockType::Stone);
+            }
+        }
+        let start = GridCoord::new(0, 0, 0);
+        let goal = GridCoord::new(4, 0, 0);
+
+        let path = find_path(&grid, start, goal, 1000).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_contiguous(&path);
+        assert!(path.iter().all(|&coord| grid.is_walkable(coord)));
+        assert_eq!(path.len(), 9);
+    }
+}
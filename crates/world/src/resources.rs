@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
-use world_sim_core::{GridCoord, Position};
+use world_sim_core::{sim_rng, sim_uuid, Position, ResourceType, SpatialIndex};
 use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::sync::Arc;
+use uuid::Uuid;
 
 /// Types of harvestable resources in the world
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -10,6 +12,19 @@ pub enum ResourceNodeType {
     Rock,
     Farm,
     IronDeposit,
+    GoldDeposit,
+}
+
+impl ResourceNodeType {
+    /// What its miners dig out of the ground (`None` for nodes harvested without digging)
+    pub fn ore(&self) -> Option<ResourceType> {
+        match self {
+            ResourceNodeType::Rock => Some(ResourceType::Stone),
+            ResourceNodeType::IronDeposit => Some(ResourceType::Iron),
+            ResourceNodeType::GoldDeposit => Some(ResourceType::Gold),
+            ResourceNodeType::Tree | ResourceNodeType::Farm => None,
+        }
+    }
 }
 
 /// A resource node in the world
@@ -24,7 +39,7 @@ pub struct ResourceNode {
 impl ResourceNode {
     pub fn new(resource_type: ResourceNodeType, position: Position, quantity: u32) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: sim_uuid(),
             resource_type,
             position,
             quantity,
@@ -34,16 +49,89 @@ impl ResourceNode {
 
 /// Manages all resource nodes in the world
 pub struct ResourceManager {
-    nodes: Arc<RwLock<Vec<ResourceNode>>>,
+    nodes: Arc<RwLock<Nodes>>,
+    regen_rates: RegenerationRates,
+}
+
+/// Cell size of the node index - about the spacing of nodes in a generated world
+const NODE_CELL_SIZE: f32 = 16.0;
+
+/// Nodes plus indexes by id and by position (keyed by place in the list; nodes never move)
+struct Nodes {
+    list: Vec<ResourceNode>,
+    ids: HashMap<Uuid, usize>,
+    spatial: SpatialIndex<usize>,
+}
+
+impl Nodes {
+    fn new() -> Self {
+        Self {
+            list: Vec::new(),
+            ids: HashMap::new(),
+            spatial: SpatialIndex::new(NODE_CELL_SIZE),
+        }
+    }
+
+    fn push(&mut self, node: ResourceNode) {
+        let index = self.list.len();
+        self.ids.insert(node.id, index);
+        self.spatial.insert(index, node.position);
+        self.list.push(node);
+    }
+
+    fn clear(&mut self) {
+        self.list.clear();
+        self.ids.clear();
+        self.spatial.clear();
+    }
+
+    fn get_mut(&mut self, id: Uuid) -> Option<&mut ResourceNode> {
+        self.ids.get(&id).map(|&i| &mut self.list[i])
+    }
+}
+
+/// Units regenerated per node per regeneration pass
+///
+/// Mines don't regenerate: they hold only what is left in the ground.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegenerationRates {
+    pub tree: u32,
+    pub farm: u32,
+}
+
+impl Default for RegenerationRates {
+    fn default() -> Self {
+        Self {
+            tree: 5,  // Trees grow fast
+            farm: 10, // Farms produce quickly
+        }
+    }
+}
+
+impl RegenerationRates {
+    pub fn for_node(&self, node_type: ResourceNodeType) -> u32 {
+        match node_type {
+            ResourceNodeType::Tree => self.tree,
+            ResourceNodeType::Farm => self.farm,
+            ResourceNodeType::Rock | ResourceNodeType::IronDeposit | ResourceNodeType::GoldDeposit => 0,
+        }
+    }
 }
 
 impl ResourceManager {
     pub fn new() -> Self {
         Self {
-            nodes: Arc::new(RwLock::new(Vec::new())),
+            nodes: Arc::new(RwLock::new(Nodes::new())),
+            regen_rates: RegenerationRates::default(),
         }
     }
 
+    pub fn with_regen_rates(mut self, regen_rates: RegenerationRates) -> Self {
+        self.regen_rates = regen_rates;
+        self
+    }
+
     /// Add a resource node
     pub fn add_node(&self, node: ResourceNode) {
         self.nodes.write().push(node);
@@ -51,13 +139,20 @@ impl ResourceManager {
 
     /// Get all nodes
     pub fn get_nodes(&self) -> Vec<ResourceNode> {
-        self.nodes.read().clone()
+        self.nodes.read().list.clone()
+    }
+
+    /// Get all nodes with a spatial index keyed by place in the returned list
+    pub fn get_nodes_indexed(&self) -> (Vec<ResourceNode>, SpatialIndex<usize>) {
+        let nodes = self.nodes.read();
+        (nodes.list.clone(), nodes.spatial.clone())
     }
 
     /// Get nodes of a specific type
     pub fn get_nodes_by_type(&self, resource_type: ResourceNodeType) -> Vec<ResourceNode> {
         self.nodes
             .read()
+            .list
             .iter()
             .filter(|n| n.resource_type == resource_type)
             .cloned()
@@ -66,22 +161,22 @@ impl ResourceManager {
 
     /// Find nearest node of a type
     pub fn find_nearest(&self, pos: Position, resource_type: ResourceNodeType) -> Option<ResourceNode> {
+        self.find_nearest_matching(pos, |n| n.resource_type == resource_type && n.quantity > 0)
+    }
+
+    /// Find the nearest node accepted by `filter` (ties go to the earlier node)
+    pub fn find_nearest_matching(&self, pos: Position, filter: impl Fn(&ResourceNode) -> bool) -> Option<ResourceNode> {
         let nodes = self.nodes.read();
         nodes
-            .iter()
-            .filter(|n| n.resource_type == resource_type && n.quantity > 0)
-            .min_by(|a, b| {
-                let dist_a = pos.distance_to(&a.position);
-                let dist_b = pos.distance_to(&b.position);
-                dist_a.partial_cmp(&dist_b).unwrap()
-            })
-            .cloned()
+            .spatial
+            .nearest(&pos, |i| filter(&nodes.list[i]))
+            .map(|(i, _)| nodes.list[i].clone())
     }
 
     /// Harvest from a node
     pub fn harvest(&self, node_id: uuid::Uuid, amount: u32) -> Option<u32> {
         let mut nodes = self.nodes.write();
-        if let Some(node) = nodes.iter_mut().find(|n| n.id == node_id) {
+        if let Some(node) = nodes.get_mut(node_id) {
             let harvested = amount.min(node.quantity);
             node.quantity -= harvested;
             Some(harvested)
@@ -90,26 +185,33 @@ impl ResourceManager {
         }
     }
     
+    /// Set what is left at a node (returns false if there is no such node)
+    pub fn set_quantity(&self, node_id: uuid::Uuid, quantity: u32) -> bool {
+        match self.nodes.write().get_mut(node_id) {
+            Some(node) => {
+                node.quantity = quantity;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Regenerate resources (natural growth)
     pub fn regenerate(&self) {
         let mut nodes = self.nodes.write();
-        for node in nodes.iter_mut() {
+        for node in nodes.list.iter_mut() {
             // Regenerate based on node type
             let max_quantity = match node.resource_type {
                 ResourceNodeType::Tree => 200,
                 ResourceNodeType::Rock => 150,
                 ResourceNodeType::Farm => 300,
                 ResourceNodeType::IronDeposit => 100,
+                ResourceNodeType::GoldDeposit => 50,
             };
             
             // If node is depleted or low, regenerate quickly
             if node.quantity < max_quantity {
-                let regen_rate = match node.resource_type {
-                    ResourceNodeType::Tree => 5,      // Trees grow fast
-                    ResourceNodeType::Rock => 2,      // Stone regenerates slowly
-                    ResourceNodeType::Farm => 10,     // Farms produce quickly
-                    ResourceNodeType::IronDeposit => 3, // Iron moderate
-                };
+                let regen_rate = self.regen_rates.for_node(node.resource_type);
                 
                 node.quantity = (node.quantity + regen_rate).min(max_quantity);
             }
@@ -119,7 +221,7 @@ impl ResourceManager {
     /// Generate random resource nodes
     pub fn generate_random_nodes(&self, count: usize, world_size: f32) {
         use rand::Rng;
-        let mut rng = rand::thread_rng();
+        let mut rng = sim_rng();
         
         let mut nodes = self.nodes.write();
         
@@ -128,11 +230,12 @@ impl ResourceManager {
         
         // Generate new nodes
         for _ in 0..count {
-            let resource_type = match rng.gen_range(0..4) {
+            let resource_type = match rng.gen_range(0..5) {
                 0 => ResourceNodeType::Tree,
                 1 => ResourceNodeType::Rock,
                 2 => ResourceNodeType::Farm,
-                _ => ResourceNodeType::IronDeposit,
+                3 => ResourceNodeType::IronDeposit,
+                _ => ResourceNodeType::GoldDeposit,
             };
             
             let position = Position::new(
@@ -0,0 +1,581 @@
+//! Seeded procedural terrain: heightmap, biomes, lakes, ore veins and trees
+//!
+//! [`generate_terrain`] fills the spawn area with voxels and returns a [`Terrain`] summary that
+//! resource nodes are placed from; a [`TerrainGenerator`] generates the rest one chunk column at a
+//! time as activity comes near. The same seed and config always produce the same world.
+
+use ahash::AHashMap;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use world_sim_core::{stream_rng, BlockType, ChunkCoord, GridCoord, Position};
+
+use crate::grid::{Chunk, GridLayer, CHUNK_SIZE};
+use crate::resources::{ResourceManager, ResourceNode, ResourceNodeType};
+
+/// Columns per side of the patches trees and ore veins are scattered over
+const TREE_PATCH: i32 = 4;
+const VEIN_PATCH: i32 = 16;
+
+/// Most blocks an ore vein wanders from where it starts
+const VEIN_REACH: i32 = 13;
+
+/// Told apart from the seed for the random streams of vein and tree patches
+const VEIN_SALT: u64 = 0x7665_696e;
+const TREE_SALT: u64 = 0x7472_6565;
+
+/// Ore within this many blocks of the surface counts as a minable deposit
+const DEPOSIT_DEPTH: i32 = 4;
+
+/// Columns on each side of the origin covered by the flat test world
+pub const FLAT_HALF_EXTENT: i32 = 50;
+
+/// Which world generator to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerrainKind {
+    /// Noise heightmap with biomes, lakes, ore and trees
+    Procedural,
+    /// A flat grass plane with resource nodes scattered uniformly (the original test world)
+    Flat,
+}
+
+/// World generator settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerrainConfig {
+    pub generator: TerrainKind,
+    /// Map seed; drawn from the simulation seed when unset
+    pub seed: Option<u64>,
+    /// Columns on each side of the origin of the spawn area, generated up front and covered by
+    /// resource nodes (the rest of the world is generated as activity comes near)
+    pub half_extent: i32,
+    /// Water fills basins up to this height
+    pub sea_level: i32,
+    /// Surface height the noise varies around
+    pub base_height: f32,
+    /// How far the surface rises and falls around `base_height`
+    pub relief: f32,
+    /// Typical width of hills and valleys, in blocks
+    pub feature_size: f32,
+    /// Stone goes down to this many blocks below sea level
+    pub depth: i32,
+    /// Chance of a tree per 4x4 patch of forest, and of other grassland
+    pub forest_trees: f32,
+    pub plains_trees: f32,
+    /// Ore veins per 16x16 patch
+    pub veins: f32,
+    /// Resource nodes placed on the generated terrain
+    pub nodes: usize,
+}
+
+impl Default for TerrainConfig {
+    fn default() -> Self {
+        Self {
+            generator: TerrainKind::Procedural,
+            seed: None,
+            half_extent: 96,
+            sea_level: 0,
+            base_height: 6.0,
+            relief: 14.0,
+            feature_size: 48.0,
+            depth: 24,
+            forest_trees: 0.6,
+            plains_trees: 0.04,
+            veins: 1.5,
+            nodes: 50,
+        }
+    }
+}
+
+impl TerrainConfig {
+    /// Columns on each side of the origin of the spawn area the selected generator fills up front
+    pub fn extent(&self) -> i32 {
+        match self.generator {
+            TerrainKind::Procedural => self.half_extent,
+            TerrainKind::Flat => FLAT_HALF_EXTENT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Biome {
+    Lake,
+    Plains,
+    Forest,
+    Hills,
+    Mountains,
+}
+
+/// Summary of a generated world: per-column heights and biomes plus notable features
+#[derive(Debug, Clone)]
+pub struct Terrain {
+    pub half_extent: i32,
+    pub sea_level: i32,
+    heights: Vec<i32>,
+    biomes: Vec<Biome>,
+    /// Ground block under each tree trunk
+    pub trees: Vec<GridCoord>,
+    /// Iron and gold blocks within a few blocks of the surface
+    pub deposits: Vec<(GridCoord, BlockType)>,
+    /// Surface blocks that are bare stone (mountain tops and outcrops)
+    pub outcrops: Vec<GridCoord>,
+}
+
+impl Terrain {
+    fn column(&self, x: i32, z: i32) -> Option<usize> {
+        let e = self.half_extent;
+        if x < -e || x > e || z < -e || z > e {
+            return None;
+        }
+        let side = (2 * e + 1) as usize;
+        Some((z + e) as usize * side + (x + e) as usize)
+    }
+
+    /// Height of the solid surface (not counting trees or water) in a generated column
+    pub fn height(&self, x: i32, z: i32) -> Option<i32> {
+        self.column(x, z).map(|i| self.heights[i])
+    }
+
+    pub fn biome(&self, x: i32, z: i32) -> Option<Biome> {
+        self.column(x, z).map(|i| self.biomes[i])
+    }
+
+    /// Columns per biome
+    pub fn biome_counts(&self) -> BTreeMap<Biome, usize> {
+        let mut counts = BTreeMap::new();
+        for biome in &self.biomes {
+            *counts.entry(*biome).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Place `count` resource nodes on fitting terrain: trees on trunks, rocks on bare stone,
+    /// iron and gold deposits over shallow ore, farms on plains
+    ///
+    /// Node positions stay on the y = 1 plane agents move on; the voxel surface under a node is
+    /// `height(x, z)`.
+    pub fn place_nodes(&self, resources: &ResourceManager, count: usize, rng: &mut impl Rng) {
+        let site = |coord: &GridCoord| (coord.x, coord.z);
+        let deposits = |ore: BlockType| self.deposits.iter().filter(move |(_, block)| *block == ore).map(|(c, _)| site(c));
+        let mut sites: [Vec<(i32, i32)>; 5] = [
+            self.trees.iter().map(site).collect(),
+            self.outcrops.iter().map(site).collect(),
+            deposits(BlockType::Iron).collect(),
+            deposits(BlockType::Gold).collect(),
+            self.biomes
+                .iter()
+                .enumerate()
+                .filter(|(_, biome)| **biome == Biome::Plains)
+                .map(|(i, _)| {
+                    let side = 2 * self.half_extent + 1;
+                    (i as i32 % side - self.half_extent, i as i32 / side - self.half_extent)
+                })
+                .collect(),
+        ];
+        for list in sites.iter_mut() {
+            list.dedup();
+        }
+        let types = [
+            ResourceNodeType::Tree,
+            ResourceNodeType::Rock,
+            ResourceNodeType::IronDeposit,
+            ResourceNodeType::GoldDeposit,
+            ResourceNodeType::Farm,
+        ];
+
+        for _ in 0..count {
+            let kind = rng.gen_range(0..types.len());
+            if sites[kind].is_empty() {
+                continue;
+            }
+            let (x, z) = sites[kind].swap_remove(rng.gen_range(0..sites[kind].len()));
+            let position = Position::new(x as f32 + 0.5, 1.0, z as f32 + 0.5);
+            resources.add_node(ResourceNode::new(types[kind], position, rng.gen_range(50..200)));
+        }
+    }
+}
+
+/// Seeded 2D gradient noise
+struct Noise {
+    perm: [u8; 512],
+}
+
+impl Noise {
+    fn new(rng: &mut impl Rng) -> Self {
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle(rng);
+        let mut perm = [0; 512];
+        for (i, value) in perm.iter_mut().enumerate() {
+            *value = table[i & 255];
+        }
+        Self { perm }
+    }
+
+    fn gradient(&self, x: i32, z: i32, dx: f32, dz: f32) -> f32 {
+        let hash = self.perm[self.perm[(x & 255) as usize] as usize + (z & 255) as usize];
+        match hash & 7 {
+            0 => dx + dz,
+            1 => dx - dz,
+            2 => -dx + dz,
+            3 => -dx - dz,
+            4 => dx,
+            5 => -dx,
+            6 => dz,
+            _ => -dz,
+        }
+    }
+
+    /// Noise at a point, roughly in -1..1
+    fn at(&self, x: f32, z: f32) -> f32 {
+        let (x0, z0) = (x.floor(), z.floor());
+        let (dx, dz) = (x - x0, z - z0);
+        let (ix, iz) = (x0 as i32, z0 as i32);
+        let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let (u, v) = (fade(dx), fade(dz));
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+        let top = lerp(self.gradient(ix, iz, dx, dz), self.gradient(ix + 1, iz, dx - 1.0, dz), u);
+        let bottom = lerp(self.gradient(ix, iz + 1, dx, dz - 1.0), self.gradient(ix + 1, iz + 1, dx - 1.0, dz - 1.0), u);
+        lerp(top, bottom, v)
+    }
+
+    /// Several octaves summed, each at twice the frequency and half the weight, in about -1..1
+    fn fractal(&self, x: f32, z: f32, octaves: u32) -> f32 {
+        let (mut total, mut weight, mut frequency, mut norm) = (0.0, 1.0, 1.0, 0.0);
+        for _ in 0..octaves {
+            total += self.at(x * frequency, z * frequency) * weight;
+            norm += weight;
+            weight *= 0.5;
+            frequency *= 2.0;
+        }
+        total / norm
+    }
+}
+
+/// Chunks being filled before they go into the grid
+#[derive(Default)]
+struct Blocks {
+    chunks: AHashMap<ChunkCoord, Chunk>,
+}
+
+impl Blocks {
+    fn set(&mut self, coord: GridCoord, block: BlockType) {
+        let chunk_coord = coord.to_chunk_coord(CHUNK_SIZE);
+        self.chunks
+            .entry(chunk_coord)
+            .or_insert_with(|| Chunk::new(chunk_coord))
+            .set(coord.x.rem_euclid(CHUNK_SIZE), coord.y.rem_euclid(CHUNK_SIZE), coord.z.rem_euclid(CHUNK_SIZE), block);
+    }
+
+    fn get(&self, coord: GridCoord) -> BlockType {
+        self.chunks
+            .get(&coord.to_chunk_coord(CHUNK_SIZE))
+            .map(|chunk| chunk.get(coord.x.rem_euclid(CHUNK_SIZE), coord.y.rem_euclid(CHUNK_SIZE), coord.z.rem_euclid(CHUNK_SIZE)))
+            .unwrap_or(BlockType::Air)
+    }
+}
+
+/// Generates the world one chunk column at a time, so any part of it can be generated on demand
+///
+/// Heights and biomes come from noise and every patch of ore veins and trees has its own random
+/// stream, so a column comes out the same whether it is generated up front or long after.
+pub struct TerrainGenerator {
+    config: TerrainConfig,
+    seed: u64,
+    elevation: Noise,
+    moisture: Noise,
+}
+
+impl TerrainGenerator {
+    pub fn new(config: &TerrainConfig, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let elevation = Noise::new(&mut rng);
+        let moisture = Noise::new(&mut rng);
+        Self { config: config.clone(), seed, elevation, moisture }
+    }
+
+    /// Chunk columns covering the spawn area (`extent()` columns on each side of the origin),
+    /// the part of the world generated up front
+    pub fn spawn_columns(&self) -> Vec<(i32, i32)> {
+        let (low, high) = ((-self.config.extent()).div_euclid(CHUNK_SIZE), self.config.extent().div_euclid(CHUNK_SIZE));
+        (low..=high).flat_map(|z| (low..=high).map(move |x| (x, z))).collect()
+    }
+
+    /// The chunks of one chunk column
+    pub fn column(&self, x: i32, z: i32) -> Vec<Chunk> {
+        let mut blocks = Blocks::default();
+        self.fill_column(x, z, &mut blocks);
+        blocks.chunks.into_values().collect()
+    }
+
+    /// Lowest block of the ground
+    fn bottom(&self) -> i32 {
+        self.config.sea_level - self.config.depth
+    }
+
+    /// Height of the solid surface and the biome of a column; mountains rise more steeply than the noise alone
+    fn surface(&self, x: i32, z: i32) -> (i32, Biome) {
+        let config = &self.config;
+        let (nx, nz) = (x as f32 / config.feature_size, z as f32 / config.feature_size);
+        // Fractal noise mostly stays within ±0.3; stretch it to about ±0.75
+        let n = self.elevation.fractal(nx, nz, 4) * 2.5;
+        let peak = (n - 0.5).max(0.0) * 2.0;
+        let height = (config.base_height + config.relief * (n + peak)).round() as i32;
+        let height = height.clamp(self.bottom() + 1, self.bottom() + 4 * CHUNK_SIZE);
+
+        let biome = if height < config.sea_level {
+            Biome::Lake
+        } else if n > 0.5 {
+            Biome::Mountains
+        } else if n > 0.25 {
+            Biome::Hills
+        } else if self.moisture.fractal(nx * 0.7 + 31.7, nz * 0.7 - 11.3, 3) > 0.0 {
+            Biome::Forest
+        } else {
+            Biome::Plains
+        };
+        (height, biome)
+    }
+
+    /// Random stream of the patch at `x`, `z` (`salt` tells veins from trees)
+    fn patch_rng(&self, salt: u64, x: i32, z: i32) -> StdRng {
+        stream_rng(self.seed ^ salt, (x as u32 as u64) << 32 | z as u32 as u64)
+    }
+
+    /// Generate the chunk column at `x`, `z` into `blocks`, returning the ground block under each tree
+    fn fill_column(&self, x: i32, z: i32, blocks: &mut Blocks) -> Vec<GridCoord> {
+        let (x0, z0) = (x * CHUNK_SIZE, z * CHUNK_SIZE);
+        let columns = || (z0..z0 + CHUNK_SIZE).flat_map(move |z| (x0..x0 + CHUNK_SIZE).map(move |x| (x, z)));
+        if self.config.generator == TerrainKind::Flat {
+            // Grass at y = 0 with dirt below, as `GridLayer::generate_simple_terrain` lays it
+            for (x, z) in columns() {
+                blocks.set(GridCoord::new(x, 0, z), BlockType::Grass);
+                for y in -5..0 {
+                    blocks.set(GridCoord::new(x, y, z), BlockType::Dirt);
+                }
+            }
+            return Vec::new();
+        }
+
+        let (config, bottom) = (&self.config, self.bottom());
+        let mut surfaces = AHashMap::new();
+        for (x, z) in columns() {
+            let (height, biome) = self.surface(x, z);
+            surfaces.insert((x, z), (height, biome));
+
+            // Stone, a few layers of dirt, then the surface block; water fills basins
+            for y in bottom..=height {
+                let block = match (biome, height - y) {
+                    (Biome::Mountains, _) => BlockType::Stone,
+                    (Biome::Lake, 0..=3) => BlockType::Dirt,
+                    (_, 0) => BlockType::Grass,
+                    (_, 1..=3) => BlockType::Dirt,
+                    _ => BlockType::Stone,
+                };
+                blocks.set(GridCoord::new(x, y, z), block);
+            }
+            for y in height + 1..=config.sea_level {
+                blocks.set(GridCoord::new(x, y, z), BlockType::Water);
+            }
+        }
+
+        // Ore veins: random walks through the ground - stone pushing up through the soil near the
+        // surface, iron at middling depth and gold deep down. Walks cross column borders, so every
+        // patch within reach is walked in full (keeping its stream in step) and only this
+        // column's blocks are kept.
+        let patches = |start: i32| (start - VEIN_REACH).div_euclid(VEIN_PATCH)..=(start + CHUNK_SIZE + VEIN_REACH).div_euclid(VEIN_PATCH);
+        for patch_z in patches(z0) {
+            for patch_x in patches(x0) {
+                let (patch_x, patch_z) = (patch_x * VEIN_PATCH, patch_z * VEIN_PATCH);
+                let mut rng = self.patch_rng(VEIN_SALT, patch_x, patch_z);
+                let veins = config.veins.floor() as u32 + u32::from(rng.gen::<f32>() < config.veins.fract());
+                for _ in 0..veins {
+                    let x = patch_x + rng.gen_range(0..VEIN_PATCH);
+                    let z = patch_z + rng.gen_range(0..VEIN_PATCH);
+                    let (surface, _) = self.surface(x, z);
+                    let (ore, depth) = match rng.gen_range(0..10) {
+                        0..=2 => (BlockType::Stone, rng.gen_range(0..3)),
+                        3..=7 => (BlockType::Iron, rng.gen_range(2..12)),
+                        _ => (BlockType::Gold, rng.gen_range(8..20)),
+                    };
+                    let mut at = GridCoord::new(x, (surface - depth).max(bottom), z);
+                    for _ in 0..rng.gen_range(6..VEIN_REACH + 1) {
+                        // Only ground turns to ore, never air or water
+                        let ground = surfaces.get(&(at.x, at.z)).is_some_and(|(height, _)| (bottom..=*height).contains(&at.y));
+                        if ground {
+                            blocks.set(at, ore);
+                        }
+                        match rng.gen_range(0..6) {
+                            0 => at.x += 1,
+                            1 => at.x -= 1,
+                            2 => at.z += 1,
+                            3 => at.z -= 1,
+                            4 => at.y += 1,
+                            _ => at.y -= 1,
+                        }
+                    }
+                }
+            }
+        }
+
+        // Trees: a trunk of wood blocks on grass, at most one per patch
+        let mut trees = Vec::new();
+        for patch_z in (z0..z0 + CHUNK_SIZE).step_by(TREE_PATCH as usize) {
+            for patch_x in (x0..x0 + CHUNK_SIZE).step_by(TREE_PATCH as usize) {
+                let mut rng = self.patch_rng(TREE_SALT, patch_x, patch_z);
+                let x = patch_x + rng.gen_range(0..TREE_PATCH);
+                let z = patch_z + rng.gen_range(0..TREE_PATCH);
+                let (height, biome) = surfaces[&(x, z)];
+                let chance = match biome {
+                    Biome::Forest => config.forest_trees,
+                    Biome::Plains | Biome::Hills => config.plains_trees,
+                    _ => 0.0,
+                };
+                let trunk = rng.gen_range(3..=5);
+                if rng.gen::<f32>() >= chance {
+                    continue;
+                }
+                let ground = GridCoord::new(x, height, z);
+                if blocks.get(ground) != BlockType::Grass {
+                    continue;
+                }
+                for y in 1..=trunk {
+                    blocks.set(GridCoord::new(x, height + y, z), BlockType::Wood);
+                }
+                trees.push(ground);
+            }
+        }
+        trees
+    }
+}
+
+/// Generate the spawn area of the world described by `config` into `grid` and return its summary
+///
+/// The rest of the world is left to be generated on demand with a [`TerrainGenerator`] of the
+/// same config and seed.
+pub fn generate_terrain(grid: &GridLayer, config: &TerrainConfig, seed: u64) -> Terrain {
+    let generator = TerrainGenerator::new(config, seed);
+    let e = config.half_extent;
+    let mut terrain = Terrain {
+        half_extent: e,
+        sea_level: config.sea_level,
+        heights: Vec::new(),
+        biomes: Vec::new(),
+        trees: Vec::new(),
+        deposits: Vec::new(),
+        outcrops: Vec::new(),
+    };
+    let mut blocks = Blocks::default();
+    for (x, z) in generator.spawn_columns() {
+        terrain.trees.extend(generator.fill_column(x, z, &mut blocks));
+    }
+    terrain.trees.retain(|tree| tree.x.abs() <= e && tree.z.abs() <= e);
+    terrain.trees.sort_unstable_by_key(|tree| (tree.z, tree.x));
+
+    // Record heights and biomes, and what resource nodes can be placed on
+    let bottom = generator.bottom();
+    for z in -e..=e {
+        for x in -e..=e {
+            let (height, biome) = generator.surface(x, z);
+            terrain.heights.push(height);
+            terrain.biomes.push(biome);
+            if blocks.get(GridCoord::new(x, height, z)) == BlockType::Stone {
+                terrain.outcrops.push(GridCoord::new(x, height, z));
+            }
+            for y in (height - DEPOSIT_DEPTH).max(bottom)..=height {
+                let coord = GridCoord::new(x, y, z);
+                let block = blocks.get(coord);
+                if matches!(block, BlockType::Iron | BlockType::Gold) {
+                    terrain.deposits.push((coord, block));
+                }
+            }
+        }
+    }
+
+    for chunk in blocks.chunks.into_values() {
+        grid.insert_chunk(chunk);
+    }
+    terrain
+}
+
+/// Bury a lode of stone, iron or gold under each rock and ore node of the flat world, which has
+/// nothing but dirt under its grass
+pub fn lay_flat_lodes(grid: &GridLayer, resources: &ResourceManager) {
+    for node in resources.get_nodes() {
+        let ore = match node.resource_type {
+            ResourceNodeType::Rock => BlockType::Stone,
+            ResourceNodeType::IronDeposit => BlockType::Iron,
+            ResourceNodeType::GoldDeposit => BlockType::Gold,
+            ResourceNodeType::Tree | ResourceNodeType::Farm => continue,
+        };
+        let at = node.position.to_grid_coord();
+        grid.fill_box(GridCoord::new(at.x - 1, -3, at.z - 1), GridCoord::new(at.x + 1, -2, at.z + 1), ore);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::CHUNK_VOLUME;
+
+    #[test]
+    fn test_generation_is_seeded_and_consistent() {
+        let config = TerrainConfig { half_extent: 40, ..TerrainConfig::default() };
+        let (grid, other) = (GridLayer::new(), GridLayer::new());
+        let terrain = generate_terrain(&grid, &config, 3);
+        let again = generate_terrain(&other, &config, 3);
+        assert_eq!(terrain.heights, again.heights);
+        assert_eq!(terrain.trees, again.trees);
+
+        // Voxels agree with the summary: surfaces, lakes, trunks
+        for z in (-40..=40).step_by(7) {
+            for x in (-40..=40).step_by(7) {
+                let height = terrain.height(x, z).unwrap();
+                let surface = grid.get_block(GridCoord::new(x, height, z));
+                match terrain.biome(x, z).unwrap() {
+                    Biome::Lake => {
+                        assert_eq!(grid.get_block(GridCoord::new(x, config.sea_level, z)), BlockType::Water);
+                    }
+                    Biome::Mountains => assert!(matches!(surface, BlockType::Stone | BlockType::Iron | BlockType::Gold)),
+                    _ => assert!(surface.is_solid()),
+                }
+                assert_eq!(grid.get_block(GridCoord::new(x, config.sea_level - config.depth - 1, z)), BlockType::Air);
+            }
+        }
+        for tree in &terrain.trees {
+            assert_eq!(grid.get_block(GridCoord::new(tree.x, tree.y + 1, tree.z)), BlockType::Wood);
+            assert_eq!(grid.surface_height(tree.x, tree.z).map(|top| top > tree.y), Some(true));
+        }
+        assert!(terrain.deposits.iter().all(|(coord, block)| grid.get_block(*coord) == *block));
+
+        // A column comes out the same generated on its own as next to its neighbours
+        let generator = TerrainGenerator::new(&config, 3);
+        let column = generator.column(1, -1);
+        assert_eq!(column.len(), grid.get_loaded_chunks().iter().filter(|c| (c.x, c.z) == (1, -1)).count());
+        for chunk in column {
+            let loaded = grid.get_chunk(chunk.coord).unwrap();
+            assert!((0..CHUNK_VOLUME).all(|i| chunk.blocks.get(i) == loaded.blocks.get(i)));
+        }
+
+        let counts = terrain.biome_counts();
+        assert!(counts.len() >= 3, "expected a varied map, got {:?}", counts);
+
+        let resources = ResourceManager::new();
+        terrain.place_nodes(&resources, 40, &mut StdRng::seed_from_u64(1));
+        for node in resources.get_nodes() {
+            let (x, z) = (node.position.x.floor() as i32, node.position.z.floor() as i32);
+            match node.resource_type {
+                ResourceNodeType::Tree => assert!(terrain.trees.iter().any(|t| t.x == x && t.z == z)),
+                ResourceNodeType::Farm => assert_eq!(terrain.biome(x, z), Some(Biome::Plains)),
+                ResourceNodeType::GoldDeposit => assert!(terrain
+                    .deposits
+                    .iter()
+                    .any(|(coord, block)| *block == BlockType::Gold && coord.x == x && coord.z == z)),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Cellular water: flow levels, falling and spreading, rain and evaporation
+//!
+//! Every water block holds a level from 1 to [`MAX_WATER_LEVEL`] (full). The grid keeps the
+//! `Water` blocks themselves, so line of sight, walkability and pathfinding see floods like any
+//! other block; this module only tracks the levels. Water nobody has touched yet - lakes from
+//! the terrain generator, blocks placed by hand - counts as full.
+//!
+//! Each step water falls into the cell below if it has room, otherwise shares single levels with
+//! lower neighbours on the same layer, so it runs downhill, fills pits and settles flat. Only
+//! chunks where water moved last step, or which were edited since, are processed.
+//!
+//! Cells outside loaded chunks, or outside the generated world's extent, count as solid, so
+//! water never drains out of the world.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use world_sim_core::{BlockType, ChunkCoord, GridCoord};
+
+use crate::ecology::WeatherState;
+use crate::grid::{GridLayer, CHUNK_SIZE};
+
+/// Level of a full water block
+pub const MAX_WATER_LEVEL: u8 = 8;
+
+/// Horizontal neighbours, in the order water spreads to them
+const SIDES: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Water simulation settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaterConfig {
+    /// Steps between rain raising surface water by one level (storms rain twice as often)
+    pub rain_interval: u64,
+    /// Steps between droughts lowering surface water by one level; in clear weather only
+    /// partly filled surface water dries up, at the same pace
+    pub evaporation_interval: u64,
+    /// Building health lost per step while a building stands in water
+    pub flood_damage: f32,
+}
+
+impl Default for WaterConfig {
+    fn default() -> Self {
+        Self {
+            rain_interval: 60,
+            evaporation_interval: 120,
+            flood_damage: 0.5,
+        }
+    }
+}
+
+/// What one step did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WaterStep {
+    /// Cells whose level changed
+    pub changed: usize,
+    /// Chunks processed
+    pub active_chunks: usize,
+}
+
+/// Water levels and the chunks where water may move
+#[derive(Debug, Clone)]
+pub struct WaterSimulation {
+    config: WaterConfig,
+    /// Columns on each side of the origin water may occupy
+    extent: Option<i32>,
+    /// Levels of known water cells, by chunk
+    levels: BTreeMap<ChunkCoord, BTreeMap<GridCoord, u8>>,
+    /// Chunks whose water blocks have been read into `levels`
+    scanned: BTreeSet<ChunkCoord>,
+    /// Chunks to process next step
+    active: BTreeSet<ChunkCoord>,
+    /// Grid revision as of the last step (later edits are someone else's)
+    seen_revision: u64,
+    steps: u64,
+    /// Cells changed during the current step
+    changed: BTreeSet<GridCoord>,
+}
+
+impl WaterSimulation {
+    pub fn new(config: WaterConfig) -> Self {
+        Self {
+            config,
+            extent: None,
+            levels: BTreeMap::new(),
+            scanned: BTreeSet::new(),
+            active: BTreeSet::new(),
+            seen_revision: 0,
+            steps: 0,
+            changed: BTreeSet::new(),
+        }
+    }
+
+    /// Keep water within columns `-half_extent..=half_extent` (the generated world)
+    pub fn with_extent(mut self, half_extent: i32) -> Self {
+        self.extent = Some(half_extent);
+        self
+    }
+
+    pub fn config(&self) -> &WaterConfig {
+        &self.config
+    }
+
+    /// Water level of a cell (0 for anything that isn't water)
+    pub fn level(&self, grid: &GridLayer, coord: GridCoord) -> u8 {
+        match self.levels.get(&chunk_of(coord)).and_then(|cells| cells.get(&coord)) {
+            Some(level) => *level,
+            None if grid.get_block(coord) == BlockType::Water => MAX_WATER_LEVEL,
+            None => 0,
+        }
+    }
+
+    /// Chunks that will be processed next step
+    pub fn active_chunks(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Advance the water one step under the given weather
+    pub fn step(&mut self, grid: &GridLayer, weather: WeatherState) -> WaterStep {
+        self.steps += 1;
+
+        // Edits since the last step may have opened paths (or dropped water) anywhere nearby
+        for chunk in grid.changed_since(self.seen_revision) {
+            self.scanned.remove(&chunk);
+            self.activate_around(chunk);
+        }
+
+        let interval = match weather {
+            WeatherState::Rain => self.config.rain_interval,
+            WeatherState::Storm => (self.config.rain_interval / 2).max(1),
+            WeatherState::Drought | WeatherState::Clear => self.config.evaporation_interval,
+        };
+        if self.steps.is_multiple_of(interval.max(1)) {
+            self.apply_weather(grid, weather);
+        }
+
+        let active: Vec<ChunkCoord> = std::mem::take(&mut self.active).into_iter().collect();
+        for chunk in &active {
+            self.scan(grid, *chunk);
+        }
+        let cells: Vec<GridCoord> = active
+            .iter()
+            .filter_map(|chunk| self.levels.get(chunk))
+            .flat_map(|cells| cells.keys().copied())
+            .collect();
+        for coord in cells {
+            self.flow(grid, coord);
+        }
+
+        // Whatever changed may let water move next step, here and next door
+        let changed = std::mem::take(&mut self.changed);
+        for coord in &changed {
+            self.active.insert(chunk_of(*coord));
+            for (dx, dy, dz) in [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)] {
+                self.active.insert(chunk_of(GridCoord::new(coord.x + dx, coord.y + dy, coord.z + dz)));
+            }
+        }
+        self.seen_revision = grid.revision();
+
+        WaterStep {
+            changed: changed.len(),
+            active_chunks: active.len(),
+        }
+    }
+
+    /// Rain raises and drought lowers every surface water cell by a level
+    fn apply_weather(&mut self, grid: &GridLayer, weather: WeatherState) {
+        for chunk in grid.chunks_containing(BlockType::Water) {
+            self.scan(grid, chunk);
+        }
+        let cells: Vec<(GridCoord, u8)> = self
+            .levels
+            .values()
+            .flat_map(|cells| cells.iter().map(|(coord, level)| (*coord, *level)))
+            .collect();
+
+        for (coord, level) in cells {
+            let above = GridCoord::new(coord.x, coord.y + 1, coord.z);
+            if grid.get_block(above) != BlockType::Air {
+                continue;
+            }
+            match weather {
+                WeatherState::Rain | WeatherState::Storm if level < MAX_WATER_LEVEL => self.set_level(grid, coord, level + 1),
+                WeatherState::Rain | WeatherState::Storm if self.holds_water(grid, above) => self.set_level(grid, above, 1),
+                WeatherState::Drought => self.set_level(grid, coord, level - 1),
+                WeatherState::Clear if level < MAX_WATER_LEVEL => self.set_level(grid, coord, level - 1),
+                _ => {}
+            }
+        }
+    }
+
+    /// Move water out of one cell: down if there is room, else one level to each lower side or drop
+    fn flow(&mut self, grid: &GridLayer, coord: GridCoord) {
+        let mut level = self.tracked(coord);
+        if level == 0 {
+            return;
+        }
+
+        let below = GridCoord::new(coord.x, coord.y - 1, coord.z);
+        if let Some(under) = self.room(grid, below) {
+            if under < MAX_WATER_LEVEL {
+                let moved = level.min(MAX_WATER_LEVEL - under);
+                self.set_level(grid, coord, level - moved);
+                self.set_level(grid, below, under + moved);
+                return;
+            }
+        }
+
+        for (dx, dz) in SIDES {
+            if level == 0 {
+                break;
+            }
+            let side = GridCoord::new(coord.x + dx, coord.y, coord.z + dz);
+            if let Some(beside) = self.room(grid, side) {
+                // Even the last level runs off towards a drop
+                let below_side = GridCoord::new(side.x, side.y - 1, side.z);
+                let drop = beside == 0 && self.room(grid, below_side).is_some_and(|under| under < MAX_WATER_LEVEL);
+                if beside + 1 < level || drop {
+                    level -= 1;
+                    self.set_level(grid, coord, level);
+                    self.set_level(grid, side, beside + 1);
+                }
+            }
+        }
+    }
+
+    /// Current level of a cell water can occupy, or `None` for solid blocks and unloaded chunks
+    fn room(&mut self, grid: &GridLayer, coord: GridCoord) -> Option<u8> {
+        if !self.holds_water(grid, coord) {
+            return None;
+        }
+        self.scan(grid, chunk_of(coord));
+        Some(self.tracked(coord))
+    }
+
+    fn holds_water(&self, grid: &GridLayer, coord: GridCoord) -> bool {
+        let inside = self.extent.is_none_or(|e| coord.x.abs() <= e && coord.z.abs() <= e);
+        inside
+            && grid.has_chunk(chunk_of(coord)) && matches!(grid.get_block(coord), BlockType::Air | BlockType::Water)
+    }
+
+    fn tracked(&self, coord: GridCoord) -> u8 {
+        self.levels.get(&chunk_of(coord)).and_then(|cells| cells.get(&coord)).copied().unwrap_or(0)
+    }
+
+    /// Set a cell's level, turning it into water or air as it fills or empties
+    fn set_level(&mut self, grid: &GridLayer, coord: GridCoord, level: u8) {
+        let cells = self.levels.entry(chunk_of(coord)).or_default();
+        let previous = cells.get(&coord).copied().unwrap_or(0);
+        if previous == level {
+            return;
+        }
+        if level == 0 {
+            cells.remove(&coord);
+            grid.set_block(coord, BlockType::Air);
+        } else {
+            cells.insert(coord, level);
+            if previous == 0 {
+                grid.set_block(coord, BlockType::Water);
+            }
+        }
+        self.changed.insert(coord);
+    }
+
+    /// Read a chunk's water blocks into `levels` (once, until the chunk is edited by someone else)
+    fn scan(&mut self, grid: &GridLayer, chunk_coord: ChunkCoord) {
+        if !self.scanned.insert(chunk_coord) {
+            return;
+        }
+        let Some(chunk) = grid.get_chunk(chunk_coord) else {
+            self.levels.remove(&chunk_coord);
+            return;
+        };
+
+        let cells = self.levels.entry(chunk_coord).or_default();
+        // Water that was built over or dug out is gone; new water blocks start full
+        cells.retain(|coord, _| {
+            chunk.get(coord.x.rem_euclid(CHUNK_SIZE), coord.y.rem_euclid(CHUNK_SIZE), coord.z.rem_euclid(CHUNK_SIZE)) == BlockType::Water
+        });
+        if chunk.blocks.palette().contains(&BlockType::Water) {
+            for y in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        if chunk.get(x, y, z) == BlockType::Water {
+                            let coord = GridCoord::new(
+                                chunk_coord.x * CHUNK_SIZE + x,
+                                chunk_coord.y * CHUNK_SIZE + y,
+                                chunk_coord.z * CHUNK_SIZE + z,
+                            );
+                            cells.entry(coord).or_insert(MAX_WATER_LEVEL);
+                        }
+                    }
+                }
+            }
+        }
+        if cells.is_empty() {
+            self.levels.remove(&chunk_coord);
+        }
+    }
+
+    fn activate_around(&mut self, chunk: ChunkCoord) {
+        for (dx, dy, dz) in [(0, 0, 0), (1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)] {
+            self.active.insert(ChunkCoord::new(chunk.x + dx, chunk.y + dy, chunk.z + dz));
+        }
+    }
+}
+
+fn chunk_of(coord: GridCoord) -> ChunkCoord {
+    coord.to_chunk_coord(CHUNK_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 12x12 stone floor at y = 0 with a low rim and a pit in one corner
+    fn basin() -> GridLayer {
+        let grid = GridLayer::new();
+        grid.fill_box(GridCoord::new(0, -3, 0), GridCoord::new(11, 1, 11), BlockType::Stone);
+        grid.fill_box(GridCoord::new(1, 1, 1), GridCoord::new(10, 1, 10), BlockType::Air);
+        grid.fill_box(GridCoord::new(9, -2, 9), GridCoord::new(10, 0, 10), BlockType::Air);
+        grid
+    }
+
+    fn total(water: &WaterSimulation, grid: &GridLayer) -> u32 {
+        (-3..=3)
+            .flat_map(|y| (0..12).flat_map(move |z| (0..12).map(move |x| GridCoord::new(x, y, z))))
+            .map(|coord| water.level(grid, coord) as u32)
+            .sum()
+    }
+
+    #[test]
+    fn test_water_spreads_fills_pits_and_settles() {
+        let grid = basin();
+        grid.fill_box(GridCoord::new(8, 1, 9), GridCoord::new(8, 3, 9), BlockType::Water);
+        let mut water = WaterSimulation::new(WaterConfig { rain_interval: 1000, evaporation_interval: 1000, ..WaterConfig::default() });
+
+        let mut steps = 0;
+        while steps < 200 {
+            steps += 1;
+            if water.step(&grid, WeatherState::Clear).changed == 0 {
+                break;
+            }
+        }
+        assert!(steps < 200, "water never settled");
+        assert_eq!(water.active_chunks(), 0);
+        assert_eq!(total(&water, &grid), 3 * MAX_WATER_LEVEL as u32);
+
+        // The column beside the pit mostly ran into it and settled level across the pit's bottom;
+        // the rest lies on the floor as a film one level deep
+        let bottom: Vec<u8> = [(9, 9), (10, 9), (9, 10), (10, 10)]
+            .iter()
+            .map(|(x, z)| water.level(&grid, GridCoord::new(*x, -2, *z)))
+            .collect();
+        assert!(bottom.iter().map(|l| *l as u32).sum::<u32>() >= 2 * MAX_WATER_LEVEL as u32, "{:?}", bottom);
+        assert!(bottom.iter().max().unwrap() - bottom.iter().min().unwrap() <= 1);
+        assert!((1..11).all(|z| (1..11).all(|x| water.level(&grid, GridCoord::new(x, 1, z)) <= 1)));
+
+        // Edits away from settled water don't set it moving again
+        grid.set_block(GridCoord::new(5, 5, 5), BlockType::Stone);
+        assert_eq!(water.step(&grid, WeatherState::Clear).changed, 0);
+    }
+
+    #[test]
+    fn test_weather_raises_and_lowers_water() {
+        let grid = basin();
+        grid.fill_box(GridCoord::new(9, -2, 9), GridCoord::new(10, 0, 10), BlockType::Water);
+        let config = WaterConfig { rain_interval: 2, evaporation_interval: 2, ..WaterConfig::default() };
+        let mut water = WaterSimulation::new(config);
+        let pool = total(&water, &grid);
+
+        for _ in 0..4 {
+            water.step(&grid, WeatherState::Storm);
+        }
+        assert!(total(&water, &grid) > pool);
+        assert_eq!(grid.get_block(GridCoord::new(9, 1, 9)), BlockType::Water);
+
+        for _ in 0..200 {
+            water.step(&grid, WeatherState::Drought);
+        }
+        assert_eq!(total(&water, &grid), 0);
+        assert_eq!(grid.get_block(GridCoord::new(9, -2, 9)), BlockType::Air);
+    }
+}
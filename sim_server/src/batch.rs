@@ -0,0 +1,295 @@
+//! Headless batch mode - run a seeded world for N ticks as fast as possible and report
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+use world_sim_event_bus::{EventBus, EventEnvelope, EventSubscriber};
+
+use crate::config::SimConfig;
+use crate::simulation::{Simulation, WorldStats};
+
+/// Resources that get a price column in the time series
+const PRICE_COLUMNS: [&str; 4] = ["Wood", "Stone", "Iron", "Food"];
+
+#[derive(Debug, Clone, Args)]
+pub struct BatchArgs {
+    /// Number of fast ticks to simulate (slow ticks run at the configured ratio)
+    #[arg(long)]
+    pub ticks: u64,
+
+    /// RNG seed (random if omitted; the seed used is recorded in the report)
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Write the JSON report to this file instead of stdout
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+
+    /// Also write a CSV time series to this file
+    #[arg(long)]
+    pub timeseries: Option<PathBuf>,
+
+    /// Fast ticks between time series samples
+    #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+    pub sample_every: u64,
+}
+
+/// Machine-readable result of a batch run
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport {
+    pub seed: u64,
+    pub ticks: u64,
+    pub wall_clock_seconds: f64,
+    pub ticks_per_second: f64,
+    pub births: u64,
+    pub deaths: u64,
+    pub deaths_by_cause: BTreeMap<String, u64>,
+    pub wars_declared: u64,
+    /// Invariant violations per `system: Invariant` (audited runs only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit: Option<BTreeMap<String, u64>>,
+    #[serde(rename = "final")]
+    pub final_stats: WorldStats,
+    pub config: SimConfig,
+}
+
+impl BatchReport {
+    /// Flat outcome metrics (`population`, `population.Peasant`, `price.Food`, `buildings.House`, `gold.gini`, ...)
+    pub fn outcomes(&self) -> BTreeMap<String, f64> {
+        let stats = &self.final_stats;
+        let mut outcomes = BTreeMap::from([
+            ("population".to_string(), stats.population as f64),
+            ("births".to_string(), self.births as f64),
+            ("deaths".to_string(), self.deaths as f64),
+            ("wars_declared".to_string(), self.wars_declared as f64),
+            ("money_supply".to_string(), stats.money_supply),
+            ("agent_gold".to_string(), stats.agent_gold),
+            ("inflation_rate".to_string(), stats.inflation_rate),
+            ("buildings".to_string(), stats.buildings as f64),
+            ("buildings_complete".to_string(), stats.buildings_complete as f64),
+            ("factions".to_string(), stats.factions as f64),
+        ]);
+        for (class, count) in &stats.population_by_class {
+            outcomes.insert(format!("population.{}", class), *count as f64);
+        }
+        for (cause, count) in &self.deaths_by_cause {
+            outcomes.insert(format!("deaths.{}", cause), *count as f64);
+        }
+        for (resource, price) in &stats.prices {
+            outcomes.insert(format!("price.{}", resource), *price);
+        }
+        for (building_type, count) in &stats.buildings_by_type {
+            outcomes.insert(format!("buildings.{}", building_type), *count as f64);
+        }
+        let gold = &stats.gold_distribution;
+        outcomes.extend([
+            ("gold.p10".to_string(), gold.p10),
+            ("gold.median".to_string(), gold.median),
+            ("gold.p90".to_string(), gold.p90),
+            ("gold.gini".to_string(), gold.gini),
+        ]);
+        outcomes
+    }
+}
+
+/// One row of the time series
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub tick: u64,
+    pub stats: WorldStats,
+    pub births: u64,
+    pub deaths: u64,
+    pub wars_declared: u64,
+}
+
+/// Counts lifecycle and war events as they are published
+#[derive(Default)]
+struct EventTally {
+    counts: Mutex<Tally>,
+}
+
+#[derive(Default, Clone)]
+struct Tally {
+    births: u64,
+    deaths_by_cause: BTreeMap<String, u64>,
+    wars_declared: u64,
+}
+
+impl Tally {
+    fn deaths(&self) -> u64 {
+        self.deaths_by_cause.values().sum()
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for EventTally {
+    async fn on_event(&self, event: &EventEnvelope) {
+        let mut counts = self.counts.lock();
+        match event.event_type.as_str() {
+            "AgentBorn" => counts.births += 1,
+            "AgentDied" => {
+                let cause = event.payload["cause"].as_str().unwrap_or("Unknown").to_string();
+                *counts.deaths_by_cause.entry(cause).or_insert(0) += 1;
+            }
+            "WarDeclared" => counts.wars_declared += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Run a world without pacing or API, sampling the time series every `sample_every` ticks
+///
+/// The simulation RNG is per-thread, so call this from a current-thread runtime for
+/// a run that is reproducible from its seed.
+pub async fn run(
+    config: SimConfig,
+    ticks: u64,
+    seed: u64,
+    sample_every: Option<u64>,
+) -> Result<(BatchReport, Vec<Sample>)> {
+    world_sim_core::seed_rng(seed);
+
+    let event_bus = Arc::new(EventBus::new());
+    let tally = Arc::new(EventTally::default());
+    event_bus.subscribe("AgentBorn", tally.clone());
+    event_bus.subscribe("AgentDied", tally.clone());
+    event_bus.subscribe("WarDeclared", tally.clone());
+
+    let intervals = config.ticks.clone();
+    let slow_every = (intervals.slow_ms / intervals.fast_ms).max(1);
+    let very_slow_every = (intervals.very_slow_ms / intervals.fast_ms).max(1);
+    let mut simulation = Simulation::new(config.clone(), event_bus)?;
+
+    info!("🧪 Batch run: {} ticks, seed {}", ticks, seed);
+    let started = Instant::now();
+    let mut samples = Vec::new();
+
+    for tick in 0..ticks {
+        simulation.tick_fast(intervals.fast().as_secs_f64()).await?;
+        if tick % slow_every == 0 {
+            simulation.tick_slow(intervals.slow().as_secs_f64()).await?;
+        }
+        if tick % very_slow_every == 0 {
+            simulation.tick_very_slow(intervals.very_slow().as_secs_f64()).await?;
+        }
+
+        if sample_every.is_some_and(|every| (tick + 1) % every == 0) {
+            let counts = tally.counts.lock().clone();
+            samples.push(Sample {
+                tick: tick + 1,
+                stats: simulation.stats(),
+                births: counts.births,
+                deaths: counts.deaths(),
+                wars_declared: counts.wars_declared,
+            });
+        }
+    }
+
+    let wall_clock_seconds = started.elapsed().as_secs_f64();
+    let counts = tally.counts.lock().clone();
+    let report = BatchReport {
+        seed,
+        ticks,
+        wall_clock_seconds,
+        ticks_per_second: ticks as f64 / wall_clock_seconds.max(f64::EPSILON),
+        births: counts.births,
+        deaths: counts.deaths(),
+        deaths_by_cause: counts.deaths_by_cause,
+        wars_declared: counts.wars_declared,
+        audit: simulation.audit_summary(),
+        final_stats: simulation.stats(),
+        config,
+    };
+    info!("✅ Batch run finished in {:.1}s ({:.0} ticks/s)", wall_clock_seconds, report.ticks_per_second);
+
+    Ok((report, samples))
+}
+
+/// Run a batch and write its outputs as requested on the command line
+pub async fn run_and_write(config: SimConfig, args: BatchArgs) -> Result<()> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let sample_every = args.timeseries.as_ref().map(|_| args.sample_every);
+    let (report, samples) = run(config, args.ticks, seed, sample_every).await?;
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match &args.report {
+        Some(path) => std::fs::write(path, json)
+            .with_context(|| format!("Failed to write report to {}", path.display()))?,
+        None => println!("{}", json),
+    }
+
+    if let Some(path) = &args.timeseries {
+        write_timeseries(path, &samples)
+            .with_context(|| format!("Failed to write time series to {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+fn write_timeseries(path: &Path, samples: &[Sample]) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+
+    let mut header = vec![
+        "tick".to_string(),
+        "sim_time".to_string(),
+        "population".to_string(),
+        "money_supply".to_string(),
+        "agent_gold".to_string(),
+        "buildings".to_string(),
+        "buildings_complete".to_string(),
+        "factions".to_string(),
+        "births".to_string(),
+        "deaths".to_string(),
+        "wars_declared".to_string(),
+    ];
+    header.extend(PRICE_COLUMNS.iter().map(|resource| format!("price_{}", resource.to_lowercase())));
+    writer.write_record(&header)?;
+
+    for sample in samples {
+        let stats = &sample.stats;
+        let mut row = vec![
+            sample.tick.to_string(),
+            format!("{:.1}", stats.sim_time),
+            stats.population.to_string(),
+            format!("{:.2}", stats.money_supply),
+            format!("{:.2}", stats.agent_gold),
+            stats.buildings.to_string(),
+            stats.buildings_complete.to_string(),
+            stats.factions.to_string(),
+            sample.births.to_string(),
+            sample.deaths.to_string(),
+            sample.wars_declared.to_string(),
+        ];
+        row.extend(
+            PRICE_COLUMNS
+                .iter()
+                .map(|resource| stats.prices.get(*resource).map(|p| format!("{:.2}", p)).unwrap_or_default()),
+        );
+        writer.write_record(&row)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_seeded_runs_repeat() {
+        let (first, samples) = run(SimConfig::default(), 50, 42, Some(10)).await.unwrap();
+        let (second, _) = run(SimConfig::default(), 50, 42, None).await.unwrap();
+
+        assert_eq!(samples.len(), 5);
+        assert_eq!(first.final_stats, second.final_stats);
+        assert_eq!(first.final_stats.population, 100 + first.births as usize - first.deaths as usize);
+    }
+}
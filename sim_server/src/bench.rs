@@ -0,0 +1,128 @@
+//! Tick benchmark - time the real scheduler ticks of a large seeded world
+
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+use world_sim_event_bus::EventBus;
+
+use crate::config::SimConfig;
+use crate::simulation::Simulation;
+
+#[derive(Debug, Clone, Args)]
+pub struct BenchArgs {
+    /// Initial population (the configured classes, with peasants making up the rest)
+    #[arg(long, default_value_t = 10_000)]
+    pub agents: u32,
+
+    /// Number of fast ticks to time (slow ticks run at the configured ratio and are timed apart)
+    #[arg(long, default_value_t = 200)]
+    pub ticks: u64,
+
+    /// RNG seed
+    #[arg(long, default_value_t = 1)]
+    pub seed: u64,
+}
+
+/// Tick times of a bench run, in milliseconds
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub agents: usize,
+    pub fast_ticks: usize,
+    /// What a fast tick may take to keep pace (`ticks.fast_ms`)
+    pub fast_budget_ms: u64,
+    pub fast: TickTimes,
+    pub slow_ticks: usize,
+    pub slow_budget_ms: u64,
+    pub slow: TickTimes,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TickTimes {
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl TickTimes {
+    fn of(mut times: Vec<f64>) -> Self {
+        if times.is_empty() {
+            return Self::default();
+        }
+        times.sort_by(f64::total_cmp);
+        let at = |q: f64| times[((times.len() - 1) as f64 * q).round() as usize];
+        Self {
+            mean_ms: times.iter().sum::<f64>() / times.len() as f64,
+            p50_ms: at(0.5),
+            p99_ms: at(0.99),
+            max_ms: times[times.len() - 1],
+        }
+    }
+}
+
+/// Build a world of `args.agents` agents and time its fast and slow ticks as `batch` runs them
+///
+/// The very slow tick (once a minute of sim time) runs untimed.
+pub async fn run(mut config: SimConfig, args: &BenchArgs) -> Result<BenchReport> {
+    world_sim_core::seed_rng(args.seed);
+    let counts = &mut config.population.class_counts;
+    counts.peasant = args.agents.saturating_sub(counts.total() - counts.peasant);
+
+    let intervals = config.ticks.clone();
+    let slow_every = (intervals.slow_ms / intervals.fast_ms).max(1);
+    let very_slow_every = (intervals.very_slow_ms / intervals.fast_ms).max(1);
+    let mut simulation = Simulation::new(config, Arc::new(EventBus::new()))?;
+    let agents = simulation.stats().population;
+    info!("⏱️ Bench: {} agents, {} fast ticks", agents, args.ticks);
+
+    let millis = |started: Instant| started.elapsed().as_secs_f64() * 1000.0;
+    let (mut fast, mut slow) = (Vec::new(), Vec::new());
+    for tick in 0..args.ticks {
+        let started = Instant::now();
+        simulation.tick_fast(intervals.fast().as_secs_f64()).await?;
+        fast.push(millis(started));
+        if tick % slow_every == 0 {
+            let started = Instant::now();
+            simulation.tick_slow(intervals.slow().as_secs_f64()).await?;
+            slow.push(millis(started));
+        }
+        if tick % very_slow_every == 0 {
+            simulation.tick_very_slow(intervals.very_slow().as_secs_f64()).await?;
+        }
+    }
+
+    Ok(BenchReport {
+        agents,
+        fast_ticks: fast.len(),
+        fast_budget_ms: intervals.fast_ms,
+        fast: TickTimes::of(fast),
+        slow_ticks: slow.len(),
+        slow_budget_ms: intervals.slow_ms,
+        slow: TickTimes::of(slow),
+    })
+}
+
+/// Run a bench and print its report as JSON
+pub async fn run_and_write(config: SimConfig, args: BenchArgs) -> Result<()> {
+    let report = run(config, &args).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bench_times_every_tick() {
+        let args = BenchArgs { agents: 300, ticks: 12, seed: 3 };
+        let report = run(SimConfig::default(), &args).await.unwrap();
+
+        assert_eq!(report.agents, 300);
+        assert_eq!((report.fast_ticks, report.slow_ticks), (12, 2));
+        assert!(report.fast.p50_ms <= report.fast.p99_ms && report.fast.p99_ms <= report.fast.max_ms);
+    }
+}
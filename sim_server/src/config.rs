@@ -0,0 +1,598 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+use world_sim_agents::{SocialClass, StartingWallets};
+use world_sim_core::ResourceType;
+use world_sim_world::{BuildingType, FireConfig, RegenerationRates, TerrainConfig, WaterConfig};
+
+/// Prefix for environment overrides: `WORLDSIM__ECONOMY__TAX_RATE=0.1` sets `economy.tax_rate`
+pub const ENV_PREFIX: &str = "WORLDSIM__";
+
+/// All tunable simulation parameters (loaded from TOML; every key is optional)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimConfig {
+    pub server: ServerConfig,
+    pub ticks: TickConfig,
+    pub population: PopulationConfig,
+    pub economy: EconomyConfig,
+    pub combat: CombatConfig,
+    pub terrain: TerrainConfig,
+    pub water: WaterConfig,
+    pub fire: FireConfig,
+    pub resources: ResourcesConfig,
+    pub buildings: BuildingsConfig,
+    pub vehicles: VehiclesConfig,
+    pub systems: SystemsConfig,
+    pub lod: LodConfig,
+    pub streaming: StreamingConfig,
+    pub audit: AuditConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Admin API bind address
+    pub bind: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:8080".to_string(),
+        }
+    }
+}
+
+/// Tick intervals in milliseconds (each tick advances simulated time by its interval)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TickConfig {
+    /// Real-time systems (movement, combat)
+    pub fast_ms: u64,
+    /// Economy and utility AI
+    pub slow_ms: u64,
+    /// Ecology and demographics
+    pub very_slow_ms: u64,
+}
+
+impl Default for TickConfig {
+    fn default() -> Self {
+        Self {
+            fast_ms: 100,         // 10 Hz
+            slow_ms: 1_000,       // 1 Hz
+            very_slow_ms: 60_000, // 1/min
+        }
+    }
+}
+
+impl TickConfig {
+    pub fn fast(&self) -> Duration {
+        Duration::from_millis(self.fast_ms)
+    }
+
+    pub fn slow(&self) -> Duration {
+        Duration::from_millis(self.slow_ms)
+    }
+
+    pub fn very_slow(&self) -> Duration {
+        Duration::from_millis(self.very_slow_ms)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PopulationConfig {
+    /// Initial agents per social class
+    pub class_counts: ClassCounts,
+    /// Radius of the innermost spawn ring around the world center
+    pub spawn_radius: f32,
+    /// Birth chance per living agent per demographics tick
+    pub birth_rate: f64,
+    /// Natural death chance per agent per demographics tick
+    pub death_rate: f64,
+    pub starting_wallets: StartingWallets,
+}
+
+impl Default for PopulationConfig {
+    fn default() -> Self {
+        Self {
+            class_counts: ClassCounts::default(),
+            spawn_radius: 20.0,
+            birth_rate: 0.01,
+            death_rate: 0.005,
+            starting_wallets: StartingWallets::default(),
+        }
+    }
+}
+
+/// Initial population per social class (spawned in this order)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClassCounts {
+    pub king: u32,
+    pub noble: u32,
+    pub knight: u32,
+    pub soldier: u32,
+    pub merchant: u32,
+    pub burgher: u32,
+    pub cleric: u32,
+    pub peasant: u32,
+}
+
+impl Default for ClassCounts {
+    fn default() -> Self {
+        Self {
+            king: 2, // 2 potential leaders
+            noble: 4,
+            knight: 8,
+            soldier: 14,
+            merchant: 12,
+            burgher: 10,
+            cleric: 4,
+            peasant: 46,
+        }
+    }
+}
+
+impl ClassCounts {
+    pub fn iter(&self) -> impl Iterator<Item = (SocialClass, u32)> {
+        [
+            (SocialClass::King, self.king),
+            (SocialClass::Noble, self.noble),
+            (SocialClass::Knight, self.knight),
+            (SocialClass::Soldier, self.soldier),
+            (SocialClass::Merchant, self.merchant),
+            (SocialClass::Burgher, self.burgher),
+            (SocialClass::Cleric, self.cleric),
+            (SocialClass::Peasant, self.peasant),
+        ]
+        .into_iter()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.iter().map(|(_, count)| count).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EconomyConfig {
+    /// Share of wealth collected from taxable classes each tax cycle
+    pub tax_rate: f64,
+    /// Starting money supply tracked by the currency system
+    pub initial_money_supply: f64,
+    /// Rise in the inflation rate (over its lowest point since the last spike) that is reported
+    /// as an `InflationSpike` event
+    pub inflation_spike: f64,
+}
+
+impl Default for EconomyConfig {
+    fn default() -> Self {
+        Self {
+            tax_rate: 0.05,
+            initial_money_supply: 20_000.0,
+            inflation_spike: 0.02,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CombatConfig {
+    /// Chance per fast tick that one of two enemies in melee range dies
+    pub death_chance: f64,
+}
+
+impl Default for CombatConfig {
+    fn default() -> Self {
+        Self { death_chance: 0.15 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResourcesConfig {
+    pub regen: RegenerationRates,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildingsConfig {
+    /// Construction cost overrides, e.g. `Warehouse = { Wood = 100, Stone = 50 }`
+    pub costs: HashMap<BuildingType, HashMap<ResourceType, u32>>,
+}
+
+/// Carts and boats the world starts with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VehiclesConfig {
+    /// Public carts parked at each market for builders to haul materials with
+    pub carts_per_market: usize,
+    /// Public boats at each dock that water connects to another dock
+    pub boats_per_dock: usize,
+    /// A market within this many blocks of open water gets a dock
+    pub dock_reach: i32,
+}
+
+impl Default for VehiclesConfig {
+    fn default() -> Self {
+        Self {
+            carts_per_market: 2,
+            boats_per_dock: 1,
+            dock_reach: 12,
+        }
+    }
+}
+
+/// Which simulation systems run (all of them by default)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SystemsConfig {
+    /// Systems to skip, e.g. `["combat", "war"]`
+    pub disabled: Vec<String>,
+    /// Alternative implementations by system name, e.g. `combat = "nonlethal"`
+    pub replace: HashMap<String, String>,
+}
+
+/// Agent level of detail (off by default)
+///
+/// Commoners far from every focus point are planned less often, and beyond `reduced_radius`
+/// they stop moving and their work and meals are settled statistically. Focus points are the
+/// `focus` list, observers set through the Admin API and (optionally) every market.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LodConfig {
+    pub enabled: bool,
+    /// Agents within this ground distance of a focus point get full detail
+    pub full_radius: f32,
+    /// Agents within this distance (and beyond `full_radius`) are planned every `reduced_every` ticks
+    pub reduced_radius: f32,
+    pub reduced_every: u32,
+    /// Fixed `[x, z]` focus points
+    pub focus: Vec<[f32; 2]>,
+    /// Treat every market as a focus point
+    pub focus_markets: bool,
+    /// Share of a working harvester's yield an aggregated harvester produces per slow tick
+    pub yield_factor: f64,
+    /// Chance per slow tick that an aggregated agent buys and eats one unit of food
+    pub food_rate: f64,
+}
+
+impl Default for LodConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            full_radius: 60.0,
+            reduced_radius: 150.0,
+            reduced_every: 5,
+            focus: Vec::new(),
+            focus_markets: true,
+            yield_factor: 0.5, // walking to nodes and markets isn't simulated
+            food_rate: 0.05,
+        }
+    }
+}
+
+/// Chunk streaming (off by default)
+///
+/// Only chunks near activity - living agents, buildings, markets, vehicles and observers - stay
+/// in the grid. The rest are paged out to a chunk store (edited ones written, unchanged ones
+/// dropped) and paged back in when something comes near them again. Columns nothing has come
+/// near before are generated from the terrain config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StreamingConfig {
+    pub enabled: bool,
+    /// Chunk columns kept loaded on every side of one with activity in it
+    pub load_radius: i32,
+    /// Seconds a chunk column stays loaded after the last activity near it
+    pub unload_after: f64,
+    /// Directory paged-out chunks are written to (cleared at startup); empty for a scratch
+    /// directory under the system temp directory, removed on exit
+    pub chunk_dir: String,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            load_radius: 2,
+            unload_after: 120.0,
+            chunk_dir: String::new(),
+        }
+    }
+}
+
+/// Invariant checks after every system (off by default; tests turn them on)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    /// Stop the simulation at the first violation instead of logging it
+    pub fail_on_violation: bool,
+}
+
+/// A config value that parsed but is out of range
+#[derive(Debug, thiserror::Error)]
+#[error("invalid config value for `{key}`: {reason}")]
+pub struct ConfigError {
+    pub key: String,
+    pub reason: String,
+}
+
+impl SimConfig {
+    /// Load defaults, then the TOML file, then `WORLDSIM__*` env vars, then `key=value` overrides
+    pub fn load(path: Option<&Path>, overrides: &[String]) -> Result<Self> {
+        let mut value = match path {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read config file {}", path.display()))?;
+                toml::from_str::<toml::Table>(&text)
+                    .with_context(|| format!("Failed to parse config file {}", path.display()))?
+            }
+            None => toml::Table::new(),
+        };
+
+        for (name, raw) in std::env::vars() {
+            if let Some(key) = name.strip_prefix(ENV_PREFIX) {
+                let key = key.to_lowercase().replace("__", ".");
+                set_key(&mut value, &key, &raw).with_context(|| format!("Invalid override from {}", name))?;
+            }
+        }
+
+        Self::from_table(value, overrides)
+    }
+
+    /// A copy of this config with `key=value` overrides applied (and validated)
+    pub fn with_overrides(&self, overrides: &[String]) -> Result<Self> {
+        let value = toml::Table::try_from(self).context("Failed to convert config to TOML")?;
+        Self::from_table(value, overrides)
+    }
+
+    fn from_table(mut value: toml::Table, overrides: &[String]) -> Result<Self> {
+        for assignment in overrides {
+            let (key, raw) = assignment
+                .split_once('=')
+                .with_context(|| format!("Override `{}` must look like key=value", assignment))?;
+            set_key(&mut value, key.trim(), raw.trim())?;
+        }
+
+        let config: SimConfig = toml::Value::Table(value).try_into().context("Invalid config")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check ranges that the types alone can't express
+    pub fn validate(&self) -> std::result::Result<(), ConfigError> {
+        if self.server.bind.parse::<SocketAddr>().is_err() {
+            return Err(invalid("server.bind", "expected an address like 127.0.0.1:8080"));
+        }
+
+        for (key, ms) in [
+            ("ticks.fast_ms", self.ticks.fast_ms),
+            ("ticks.slow_ms", self.ticks.slow_ms),
+            ("ticks.very_slow_ms", self.ticks.very_slow_ms),
+        ] {
+            if ms == 0 {
+                return Err(invalid(key, "must be greater than 0"));
+            }
+        }
+
+        if self.population.class_counts.total() == 0 {
+            return Err(invalid("population.class_counts", "at least one agent must be spawned"));
+        }
+        if !self.population.spawn_radius.is_finite() || self.population.spawn_radius < 0.0 {
+            return Err(invalid("population.spawn_radius", "must be a non-negative number"));
+        }
+
+        for (key, chance) in [
+            ("population.birth_rate", self.population.birth_rate),
+            ("population.death_rate", self.population.death_rate),
+            ("combat.death_chance", self.combat.death_chance),
+            ("economy.tax_rate", self.economy.tax_rate),
+            ("lod.yield_factor", self.lod.yield_factor),
+            ("lod.food_rate", self.lod.food_rate),
+        ] {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(invalid(key, "must be between 0 and 1"));
+            }
+        }
+
+        let wallets = &self.population.starting_wallets;
+        for (key, amount) in [
+            ("population.starting_wallets.king", wallets.king),
+            ("population.starting_wallets.noble", wallets.noble),
+            ("population.starting_wallets.knight", wallets.knight),
+            ("population.starting_wallets.soldier", wallets.soldier),
+            ("population.starting_wallets.merchant", wallets.merchant),
+            ("population.starting_wallets.burgher", wallets.burgher),
+            ("population.starting_wallets.cleric", wallets.cleric),
+            ("population.starting_wallets.peasant", wallets.peasant),
+            ("economy.initial_money_supply", self.economy.initial_money_supply),
+        ] {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(invalid(key, "must be a non-negative number"));
+            }
+        }
+
+        if !self.economy.inflation_spike.is_finite() || self.economy.inflation_spike <= 0.0 {
+            return Err(invalid("economy.inflation_spike", "must be a positive number"));
+        }
+
+        let terrain = &self.terrain;
+        if terrain.half_extent <= 0 {
+            return Err(invalid("terrain.half_extent", "must be greater than 0"));
+        }
+        if terrain.depth <= 0 {
+            return Err(invalid("terrain.depth", "must be greater than 0"));
+        }
+        if !terrain.feature_size.is_finite() || terrain.feature_size <= 0.0 {
+            return Err(invalid("terrain.feature_size", "must be a positive number"));
+        }
+        if !terrain.base_height.is_finite() {
+            return Err(invalid("terrain.base_height", "must be a finite number"));
+        }
+        if !terrain.relief.is_finite() || terrain.relief < 0.0 {
+            return Err(invalid("terrain.relief", "must be a non-negative number"));
+        }
+        if !terrain.veins.is_finite() || terrain.veins < 0.0 {
+            return Err(invalid("terrain.veins", "must be a non-negative number"));
+        }
+        for (key, chance) in [("terrain.forest_trees", terrain.forest_trees), ("terrain.plains_trees", terrain.plains_trees)] {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(invalid(key, "must be between 0 and 1"));
+            }
+        }
+
+        for (key, interval) in [("water.rain_interval", self.water.rain_interval), ("water.evaporation_interval", self.water.evaporation_interval)] {
+            if interval == 0 {
+                return Err(invalid(key, "must be greater than 0"));
+            }
+        }
+        if !self.water.flood_damage.is_finite() || self.water.flood_damage < 0.0 {
+            return Err(invalid("water.flood_damage", "must be a non-negative number"));
+        }
+
+        let fire = &self.fire;
+        for (key, chance) in [
+            ("fire.spread_chance", fire.spread_chance),
+            ("fire.lightning_chance", fire.lightning_chance),
+            ("fire.rain_douse_chance", fire.rain_douse_chance),
+            ("fire.arson_chance", fire.arson_chance),
+        ] {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(invalid(key, "must be between 0 and 1"));
+            }
+        }
+        if fire.burn_steps == 0 {
+            return Err(invalid("fire.burn_steps", "must be greater than 0"));
+        }
+        if !fire.building_damage.is_finite() || fire.building_damage < 0.0 {
+            return Err(invalid("fire.building_damage", "must be a non-negative number"));
+        }
+        if !fire.alarm_radius.is_finite() || fire.alarm_radius < 0.0 {
+            return Err(invalid("fire.alarm_radius", "must be a non-negative number"));
+        }
+
+        if self.vehicles.dock_reach < 0 {
+            return Err(invalid("vehicles.dock_reach", "must not be negative"));
+        }
+
+        let lod = &self.lod;
+        if !lod.full_radius.is_finite() || lod.full_radius < 0.0 {
+            return Err(invalid("lod.full_radius", "must be a non-negative number"));
+        }
+        if !lod.reduced_radius.is_finite() || lod.reduced_radius < lod.full_radius {
+            return Err(invalid("lod.reduced_radius", "must be at least lod.full_radius"));
+        }
+        if lod.reduced_every == 0 {
+            return Err(invalid("lod.reduced_every", "must be greater than 0"));
+        }
+
+        if self.streaming.load_radius < 0 {
+            return Err(invalid("streaming.load_radius", "must not be negative"));
+        }
+        if !self.streaming.unload_after.is_finite() || self.streaming.unload_after < 0.0 {
+            return Err(invalid("streaming.unload_after", "must be a non-negative number"));
+        }
+
+        Ok(())
+    }
+
+    /// The config as TOML (as recorded in snapshots and printed by `--print-config`)
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+fn invalid(key: &str, reason: &str) -> ConfigError {
+    ConfigError {
+        key: key.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Set a dotted key, parsing the value as TOML (`0.1`, `true`, `"x"`) or falling back to a string
+fn set_key(table: &mut toml::Table, key: &str, raw: &str) -> Result<()> {
+    let value = toml::from_str::<toml::Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut parsed| parsed.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()));
+
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().filter(|k| !k.is_empty()).context("Empty config key")?;
+    let mut current = table;
+    for part in parts {
+        current = current
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("Config key `{}` is not a table", part))?;
+    }
+    current.insert(last.to_string(), value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_error(overrides: &[&str]) -> String {
+        let overrides: Vec<String> = overrides.iter().map(|o| o.to_string()).collect();
+        format!("{:#}", SimConfig::load(None, &overrides).unwrap_err())
+    }
+
+    #[test]
+    fn test_overrides_apply() {
+        let config = SimConfig::load(
+            None,
+            &[
+                "economy.tax_rate=0.1".to_string(),
+                "server.bind=0.0.0.0:9000".to_string(),
+                "buildings.costs.Walls.Stone=10".to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.economy.tax_rate, 0.1);
+        assert_eq!(config.server.bind, "0.0.0.0:9000");
+        assert_eq!(config.buildings.costs[&BuildingType::Walls][&ResourceType::Stone], 10);
+        assert_eq!(config.population.class_counts.total(), 100);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let config = SimConfig::load(None, &["economy.tax_rate=0.1".to_string()]).unwrap();
+        assert_eq!(toml::from_str::<SimConfig>(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_out_of_range_values_name_their_key() {
+        let error = load_error(&["combat.death_chance=1.5"]);
+        assert!(error.contains("combat.death_chance"), "{}", error);
+
+        let error = load_error(&["lod.reduced_radius=10"]);
+        assert!(error.contains("lod.reduced_radius"), "{}", error);
+    }
+
+    #[test]
+    fn test_terrain_validation() {
+        assert!(SimConfig::load(None, &["terrain.generator=\"flat\"".to_string()]).is_ok());
+
+        let error = load_error(&["terrain.base_height=nan"]);
+        assert!(error.contains("terrain.base_height"), "{}", error);
+        assert!(!error.contains("terrain.relief"), "{}", error);
+
+        let error = load_error(&["terrain.relief=-1.0"]);
+        assert!(error.contains("terrain.relief"), "{}", error);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let error = load_error(&["economy.tax_rte=0.1"]);
+        assert!(error.contains("tax_rte"), "{}", error);
+    }
+}
@@ -0,0 +1,231 @@
+//! Golden runs - seeded scenarios whose outcome fingerprints are checked in
+//!
+//! Each `<name>.json` in the golden directory describes a scenario (seed, ticks and config
+//! overrides on top of the defaults) and the outcome metrics it produced when it was last blessed.
+//! `sim_server golden` reruns the scenarios and reports every metric outside its tolerance;
+//! `sim_server golden --bless` records this build's results as the new goldens.
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::batch;
+use crate::config::SimConfig;
+
+/// Relative tolerance for metrics without their own entry (seeded runs repeat exactly;
+/// this only absorbs float formatting)
+const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Args)]
+pub struct GoldenArgs {
+    /// Scenarios to run, by file name without `.json` (all of them if none are given)
+    pub scenarios: Vec<String>,
+
+    /// Directory holding the golden files
+    #[arg(long, default_value = "sim_server/golden")]
+    pub dir: PathBuf,
+
+    /// Record this build's results as the new goldens instead of comparing
+    #[arg(long)]
+    pub bless: bool,
+}
+
+/// A scenario and its blessed fingerprint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Golden {
+    pub seed: u64,
+    pub ticks: u64,
+    /// `key=value` config overrides applied to the defaults (never to a `--config` file)
+    #[serde(default)]
+    pub overrides: Vec<String>,
+    /// Relative tolerance by metric, or by metric family with a trailing dot (`"price."`)
+    #[serde(default)]
+    pub tolerances: BTreeMap<String, f64>,
+    /// Outcome metrics as of the last bless (see `BatchReport::outcomes`)
+    #[serde(default)]
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// A metric that differs from its golden value, appeared, or went missing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub metric: String,
+    pub expected: Option<f64>,
+    pub actual: Option<f64>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.expected, self.actual) {
+            (Some(expected), Some(actual)) => {
+                write!(f, "{}: expected {}, got {}", self.metric, expected, actual)?;
+                if expected != 0.0 {
+                    write!(f, " ({:+.2}%)", (actual - expected) / expected.abs() * 100.0)?;
+                }
+                Ok(())
+            }
+            (Some(expected), None) => write!(f, "{}: expected {}, now missing", self.metric, expected),
+            (None, Some(actual)) => write!(f, "{}: new metric, got {}", self.metric, actual),
+            (None, None) => write!(f, "{}: missing", self.metric),
+        }
+    }
+}
+
+impl Golden {
+    /// Tolerance for a metric: its own entry, else the longest matching family, else the default
+    pub fn tolerance(&self, metric: &str) -> f64 {
+        if let Some(tolerance) = self.tolerances.get(metric) {
+            return *tolerance;
+        }
+        self.tolerances
+            .iter()
+            .filter(|(family, _)| family.ends_with('.') && metric.starts_with(family.as_str()))
+            .max_by_key(|(family, _)| family.len())
+            .map(|(_, tolerance)| *tolerance)
+            .unwrap_or(DEFAULT_TOLERANCE)
+    }
+
+    /// Compare a fresh run's metrics against the golden ones
+    ///
+    /// A tolerance is relative to the golden value, but never tighter than the same fraction
+    /// of 1 - so `0.1` lets a count of 3 move by 0.3 and a count of 0 by 0.1.
+    pub fn compare(&self, actual: &BTreeMap<String, f64>) -> Vec<Mismatch> {
+        let names: BTreeSet<&String> = self.metrics.keys().chain(actual.keys()).collect();
+        names
+            .into_iter()
+            .filter_map(|metric| {
+                let expected = self.metrics.get(metric).copied();
+                let value = actual.get(metric).copied();
+                let within = match (expected, value) {
+                    (Some(e), Some(a)) => (a - e).abs() <= self.tolerance(metric) * e.abs().max(1.0),
+                    _ => false,
+                };
+                (!within).then(|| Mismatch { metric: metric.clone(), expected, actual: value })
+            })
+            .collect()
+    }
+
+    /// Run the scenario and return its outcome metrics
+    ///
+    /// Call from a current-thread runtime, like `batch::run`.
+    pub async fn run(&self) -> Result<BTreeMap<String, f64>> {
+        let config = SimConfig::default().with_overrides(&self.overrides)?;
+        let (report, _) = batch::run(config, self.ticks, self.seed, None).await?;
+        Ok(report.outcomes())
+    }
+}
+
+/// Load the named goldens from a directory (all `*.json` files if `names` is empty), sorted by name
+pub fn load(dir: &Path, names: &[String]) -> Result<Vec<(String, PathBuf, Golden)>> {
+    let mut paths: Vec<(String, PathBuf)> = if names.is_empty() {
+        std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to list golden files in {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Some((path.file_stem()?.to_str()?.to_string(), path)))
+            .collect()
+    } else {
+        names.iter().map(|name| (name.clone(), dir.join(format!("{}.json", name)))).collect()
+    };
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|(name, path)| {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read golden file {}", path.display()))?;
+            let golden = serde_json::from_str(&text)
+                .with_context(|| format!("Invalid golden file {}", path.display()))?;
+            Ok((name, path, golden))
+        })
+        .collect()
+}
+
+/// Check (or bless) the goldens selected on the command line
+pub async fn run_and_write(args: GoldenArgs) -> Result<()> {
+    let goldens = load(&args.dir, &args.scenarios)?;
+    if goldens.is_empty() {
+        bail!("No golden files in {}", args.dir.display());
+    }
+
+    let mut failed = Vec::new();
+    for (name, path, mut golden) in goldens {
+        let metrics = golden.run().await.with_context(|| format!("Golden run `{}` failed", name))?;
+        let mismatches = golden.compare(&metrics);
+        let scenario = format!("{} (seed {}, {} ticks)", name, golden.seed, golden.ticks);
+
+        if args.bless {
+            golden.metrics = metrics;
+            std::fs::write(&path, serde_json::to_string_pretty(&golden)? + "\n")
+                .with_context(|| format!("Failed to write golden file {}", path.display()))?;
+            println!("📝 {}: blessed {} metrics ({} changed)", scenario, golden.metrics.len(), mismatches.len());
+        } else if mismatches.is_empty() {
+            println!("✅ {}: {} metrics match", scenario, metrics.len());
+        } else {
+            println!("❌ {}: {} metrics differ", scenario, mismatches.len());
+            failed.push(name);
+        }
+        for mismatch in &mismatches {
+            println!("    {}", mismatch);
+        }
+    }
+
+    if !failed.is_empty() {
+        bail!(
+            "Golden runs differ: {} (rerun with --bless if the change is intended)",
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_goldens_match() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("golden");
+        let goldens = load(&dir, &[]).unwrap();
+        assert!(!goldens.is_empty());
+
+        for (name, _, golden) in goldens {
+            let mismatches = golden.compare(&golden.run().await.unwrap());
+            let report: Vec<String> = mismatches.iter().map(|m| m.to_string()).collect();
+            assert!(
+                mismatches.is_empty(),
+                "golden run `{}` changed (bless with `cargo run --release --bin sim_server -- golden --bless {}` if intended):\n{}",
+                name,
+                name,
+                report.join("\n")
+            );
+        }
+    }
+
+    #[test]
+    fn test_tolerances() {
+        let golden = Golden {
+            seed: 1,
+            ticks: 1,
+            overrides: Vec::new(),
+            tolerances: BTreeMap::from([("price.".to_string(), 0.1), ("price.Iron".to_string(), 0.0)]),
+            metrics: BTreeMap::from([
+                ("price.Food".to_string(), 10.0),
+                ("price.Iron".to_string(), 15.0),
+                ("deaths".to_string(), 0.0),
+            ]),
+        };
+        let actual = BTreeMap::from([
+            ("price.Food".to_string(), 10.9),
+            ("price.Iron".to_string(), 15.1),
+            ("births".to_string(), 2.0),
+        ]);
+
+        let metrics: Vec<String> = golden.compare(&actual).into_iter().map(|m| m.metric).collect();
+        assert_eq!(metrics, vec!["births", "deaths", "price.Iron"]);
+    }
+}
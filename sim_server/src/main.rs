@@ -1,13 +1,114 @@
 use anyhow::Result;
-use std::time::Duration;
-use tokio::time::interval;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use tokio::time::{interval, MissedTickBehavior};
 use tracing::{info, warn};
+use world_sim_event_bus::get_event_bus;
 
+mod batch;
+mod bench;
+mod config;
+mod golden;
 mod simulation;
+mod sweep;
+mod systems;
+use batch::BatchArgs;
+use bench::BenchArgs;
+use config::SimConfig;
+use golden::GoldenArgs;
 use simulation::Simulation;
+use sweep::SweepArgs;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+#[derive(Parser)]
+#[command(name = "sim_server", about = "World Simulation Server")]
+struct Cli {
+    /// TOML config file (all keys optional; see config.example.toml)
+    #[arg(long, env = "WORLDSIM_CONFIG", global = true)]
+    config: Option<PathBuf>,
+
+    /// Admin API bind address (overrides `server.bind`)
+    #[arg(long)]
+    bind: Option<String>,
+
+    /// Override a config key, e.g. `--set economy.tax_rate=0.1` (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    overrides: Vec<String>,
+
+    /// Print the effective config as TOML and exit
+    #[arg(long, global = true)]
+    print_config: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a seeded world headless for N ticks (no pacing, no API) and write a report
+    Batch(BatchArgs),
+    /// Run a parameter grid over several seeds in parallel and aggregate the outcomes
+    Sweep(SweepArgs),
+    /// Rerun the golden scenarios and compare their outcomes with the checked-in ones
+    /// (scenarios carry their own config; `--config`/`--set` do not apply)
+    Golden(GoldenArgs),
+    /// Time the fast and slow ticks of a large seeded world (10k agents by default)
+    Bench(BenchArgs),
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut overrides = cli.overrides;
+    if let Some(bind) = cli.bind {
+        overrides.push(format!("server.bind=\"{}\"", bind));
+    }
+    let config = SimConfig::load(cli.config.as_deref(), &overrides)?;
+    if cli.print_config {
+        print!("{}", config.to_toml());
+        return Ok(());
+    }
+
+    match cli.command {
+        Some(Command::Batch(args)) => {
+            init_headless_logging();
+
+            // Single thread: the simulation RNG is per-thread, so this keeps seeded runs reproducible
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?
+                .block_on(batch::run_and_write(config, args))
+        }
+        Some(Command::Sweep(args)) => {
+            init_headless_logging();
+            sweep::run_and_write(config, args)
+        }
+        Some(Command::Golden(args)) => {
+            init_headless_logging();
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?
+                .block_on(golden::run_and_write(args))
+        }
+        Some(Command::Bench(args)) => {
+            init_headless_logging();
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?
+                .block_on(bench::run_and_write(config, args))
+        }
+        None => tokio::runtime::Runtime::new()?.block_on(serve(config)),
+    }
+}
+
+/// Warnings only (and sweep progress), on stderr, so a report on stdout stays parseable
+fn init_headless_logging() {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::new("warn,sim_server::sweep=info"))
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Run the paced simulation with the Admin API
+async fn serve(config: SimConfig) -> Result<()> {
     // Initialize logging
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
@@ -16,42 +117,55 @@ async fn main() -> Result<()> {
     info!("🌍 Starting World Simulation Server");
 
     // Create simulation
-    let mut simulation = Simulation::new().await?;
+    let bind = config.server.bind.clone();
+    let ticks = config.ticks.clone();
+    let mut simulation = Simulation::new(config, get_event_bus())?;
+    simulation.connect_external_services().await?;
 
     info!("✅ Simulation initialized");
 
     // Spawn API server task
+    let (command_sender, mut commands) = world_sim_admin_api::command_channel();
     let _api_handle = {
-        let admin_api = simulation.get_admin_api_server();
+        let admin_api = simulation.get_admin_api_server().with_commands(command_sender);
+        let bind = bind.clone();
         tokio::spawn(async move {
-            if let Err(e) = admin_api.serve("127.0.0.1:8080").await {
+            if let Err(e) = admin_api.serve(&bind).await {
                 warn!("Admin API server error: {}", e);
             }
         })
     };
 
-    info!("🌐 Admin API listening on http://127.0.0.1:8080");
+    info!("🌐 Admin API listening on http://{}", bind);
 
     // Main simulation loop
-    let mut tick_interval = interval(Duration::from_millis(100)); // 10 Hz
-    let mut slow_tick_interval = interval(Duration::from_secs(1)); // 1 Hz
-    let mut very_slow_tick_interval = interval(Duration::from_secs(60)); // 1/min
+    let mut tick_interval = interval(ticks.fast());
+    let mut slow_tick_interval = interval(ticks.slow());
+    let mut very_slow_tick_interval = interval(ticks.very_slow());
+    // Paused ticks are missed, not owed: resume on a fresh schedule instead of bursting
+    for ticker in [&mut tick_interval, &mut slow_tick_interval, &mut very_slow_tick_interval] {
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    }
 
     info!("🚀 Simulation running");
 
     loop {
         tokio::select! {
-            _ = tick_interval.tick() => {
+            _ = tick_interval.tick(), if !simulation.is_paused() => {
                 // Fast tick (real-time systems)
-                simulation.tick_fast(0.1).await?;
+                simulation.tick_fast(ticks.fast().as_secs_f64()).await?;
             }
-            _ = slow_tick_interval.tick() => {
+            _ = slow_tick_interval.tick(), if !simulation.is_paused() => {
                 // Slow tick (economy, utility AI)
-                simulation.tick_slow(1.0).await?;
+                simulation.tick_slow(ticks.slow().as_secs_f64()).await?;
             }
-            _ = very_slow_tick_interval.tick() => {
+            _ = very_slow_tick_interval.tick(), if !simulation.is_paused() => {
                 // Very slow tick (ecology, demographics)
-                simulation.tick_very_slow(60.0).await?;
+                simulation.tick_very_slow(ticks.very_slow().as_secs_f64()).await?;
+            }
+            Some(command) = commands.recv() => {
+                // Admin API commands (pause/resume, snapshots)
+                simulation.handle_command(command).await;
             }
             _ = tokio::signal::ctrl_c() => {
                 info!("Received shutdown signal");
@@ -61,7 +175,7 @@ async fn main() -> Result<()> {
     }
 
     info!("💾 Saving world state...");
-    simulation.save_snapshot().await?;
+    simulation.save_snapshot("AutoSave").await?;
 
     info!("👋 Simulation shutdown complete");
     Ok(())
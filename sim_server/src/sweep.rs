@@ -0,0 +1,332 @@
+//! Parameter sweeps - run every combination of a parameter grid for several seeds in parallel
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use tracing::info;
+
+use crate::batch;
+use crate::config::SimConfig;
+
+#[derive(Debug, Clone, Args)]
+pub struct SweepArgs {
+    /// Fast ticks per run
+    #[arg(long)]
+    pub ticks: u64,
+
+    /// Config key to vary, as `key=v1,v2,...` (repeatable; every combination is run)
+    #[arg(long = "param", value_name = "KEY=V1,V2,...", required = true)]
+    pub params: Vec<String>,
+
+    /// Seeds to run for every combination, e.g. `1,2,3` or `1-10`
+    #[arg(long, default_value = "1-5")]
+    pub seeds: String,
+
+    /// Runs in parallel (defaults to one per CPU)
+    #[arg(long)]
+    pub jobs: Option<usize>,
+
+    /// Write one summary row per combination as CSV
+    #[arg(long)]
+    pub csv: Option<PathBuf>,
+
+    /// Write the full results, including every run, as JSON (stdout if no output is given)
+    #[arg(long)]
+    pub json: Option<PathBuf>,
+}
+
+/// A config key and the values it takes in the sweep
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub key: String,
+    pub values: Vec<String>,
+}
+
+/// Mean and sample variance of one outcome over a combination's runs
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Summary {
+    pub mean: f64,
+    pub variance: f64,
+}
+
+impl Summary {
+    pub fn of(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self { mean: f64::NAN, variance: f64::NAN };
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = if values.len() > 1 {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        Self { mean, variance }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunResult {
+    pub seed: u64,
+    pub outcomes: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CombinationResult {
+    pub params: BTreeMap<String, serde_json::Value>,
+    pub metrics: BTreeMap<String, Summary>,
+    pub runs: Vec<RunResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepReport {
+    pub ticks: u64,
+    pub seeds: Vec<u64>,
+    pub wall_clock_seconds: f64,
+    pub results: Vec<CombinationResult>,
+}
+
+/// Parse `key=v1,v2,...`
+pub fn parse_param(raw: &str) -> Result<Param> {
+    let (key, values) = raw
+        .split_once('=')
+        .with_context(|| format!("Parameter `{}` must look like key=v1,v2,...", raw))?;
+    let key = key.trim().to_string();
+    let values: Vec<String> = values.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect();
+    if key.is_empty() || values.is_empty() {
+        bail!("Parameter `{}` needs a key and at least one value", raw);
+    }
+    Ok(Param { key, values })
+}
+
+/// Parse a seed list such as `1,2,3`, `1-10` or `1-3,7`
+pub fn parse_seeds(raw: &str) -> Result<Vec<u64>> {
+    let mut seeds = Vec::new();
+    for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start: u64 = start.trim().parse().with_context(|| format!("Invalid seed range `{}`", part))?;
+                let end: u64 = end.trim().parse().with_context(|| format!("Invalid seed range `{}`", part))?;
+                if start > end {
+                    bail!("Invalid seed range `{}`", part);
+                }
+                seeds.extend(start..=end);
+            }
+            None => seeds.push(part.parse().with_context(|| format!("Invalid seed `{}`", part))?),
+        }
+    }
+    if seeds.is_empty() {
+        bail!("At least one seed is required");
+    }
+    Ok(seeds)
+}
+
+/// Every combination of parameter values (the first parameter varies slowest)
+pub fn combinations(params: &[Param]) -> Vec<Vec<(String, String)>> {
+    params.iter().fold(vec![Vec::new()], |combinations, param| {
+        combinations
+            .iter()
+            .flat_map(|combination| {
+                param.values.iter().map(move |value| {
+                    let mut next = combination.clone();
+                    next.push((param.key.clone(), value.clone()));
+                    next
+                })
+            })
+            .collect()
+    })
+}
+
+/// Run every combination for every seed and aggregate the outcomes
+pub fn run(base: &SimConfig, ticks: u64, params: &[Param], seeds: &[u64], jobs: Option<usize>) -> Result<SweepReport> {
+    // Build (and validate) every config before starting any runs
+    let combinations = combinations(params);
+    let configs = combinations
+        .iter()
+        .map(|combination| {
+            let overrides: Vec<String> = combination.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+            base.with_overrides(&overrides)
+                .with_context(|| format!("Invalid combination {}", overrides.join(" ")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let runs: Vec<(usize, u64)> = (0..configs.len())
+        .flat_map(|index| seeds.iter().map(move |seed| (index, *seed)))
+        .collect();
+
+    let jobs = jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .clamp(1, runs.len().max(1));
+
+    // Runs go on plain threads rather than a rayon pool: a rayon worker blocked on a run's
+    // parallel agent phase may steal another run and reseed its thread's RNG mid-tick
+    let started = Instant::now();
+    let next_run = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let mut reports: Vec<(usize, Result<RunResult>)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let order = next_run.fetch_add(1, Ordering::Relaxed);
+                        let Some(&(index, seed)) = runs.get(order) else { break };
+                        let result = run_one(&configs[index], ticks, seed);
+                        let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                        info!("🧪 [{}/{}] seed {} {}", done, runs.len(), seed, describe(&combinations[index]));
+                        results.push((order, result));
+                    }
+                    results
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("sweep worker panicked"))
+            .collect()
+    });
+    reports.sort_by_key(|(order, _)| *order);
+    let reports = reports.into_iter().map(|(_, report)| report).collect::<Result<Vec<_>>>()?;
+
+    // Counts missing from a run (e.g. no combat deaths) are zero; missing prices are skipped
+    let metric_names: BTreeSet<String> = reports.iter().flat_map(|r| r.outcomes.keys().cloned()).collect();
+    let mut reports = reports.into_iter();
+    let results = combinations
+        .into_iter()
+        .map(|combination| {
+            let runs: Vec<RunResult> = reports.by_ref().take(seeds.len()).collect();
+            let metrics = metric_names
+                .iter()
+                .map(|name| {
+                    let values: Vec<f64> = runs
+                        .iter()
+                        .filter_map(|run| match run.outcomes.get(name) {
+                            Some(value) => Some(*value),
+                            None if name.starts_with("price.") => None,
+                            None => Some(0.0),
+                        })
+                        .collect();
+                    (name.clone(), Summary::of(&values))
+                })
+                .collect();
+            let params = combination
+                .into_iter()
+                .map(|(key, value)| {
+                    let json = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+                    (key, json)
+                })
+                .collect();
+            CombinationResult { params, metrics, runs }
+        })
+        .collect();
+
+    Ok(SweepReport {
+        ticks,
+        seeds: seeds.to_vec(),
+        wall_clock_seconds: started.elapsed().as_secs_f64(),
+        results,
+    })
+}
+
+/// One seeded run on its own single-threaded runtime, so the per-thread RNG stays seeded
+fn run_one(config: &SimConfig, ticks: u64, seed: u64) -> Result<RunResult> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let (report, _) = runtime.block_on(batch::run(config.clone(), ticks, seed, None))?;
+    Ok(RunResult { seed, outcomes: report.outcomes() })
+}
+
+/// Run a sweep and write its outputs as requested on the command line
+pub fn run_and_write(base: SimConfig, args: SweepArgs) -> Result<()> {
+    let params = args.params.iter().map(|p| parse_param(p)).collect::<Result<Vec<_>>>()?;
+    let seeds = parse_seeds(&args.seeds)?;
+    let report = run(&base, args.ticks, &params, &seeds, args.jobs)?;
+
+    if let Some(path) = &args.csv {
+        write_csv(path, &params, &report).with_context(|| format!("Failed to write CSV to {}", path.display()))?;
+    }
+    let json = serde_json::to_string_pretty(&report)?;
+    match &args.json {
+        Some(path) => std::fs::write(path, json).with_context(|| format!("Failed to write JSON to {}", path.display()))?,
+        None if args.csv.is_none() => println!("{}", json),
+        None => {}
+    }
+
+    Ok(())
+}
+
+fn describe(combination: &[(String, String)]) -> String {
+    combination.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(" ")
+}
+
+fn write_csv(path: &Path, params: &[Param], report: &SweepReport) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    let metric_names: Vec<&String> = report.results.first().map(|r| r.metrics.keys().collect()).unwrap_or_default();
+
+    let mut header: Vec<String> = params.iter().map(|p| p.key.clone()).collect();
+    header.push("runs".to_string());
+    for name in &metric_names {
+        header.push(format!("{}_mean", name));
+        header.push(format!("{}_variance", name));
+    }
+    writer.write_record(&header)?;
+
+    for result in &report.results {
+        let mut row: Vec<String> = params
+            .iter()
+            .map(|param| match &result.params[&param.key] {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        row.push(result.runs.len().to_string());
+        for name in &metric_names {
+            let summary = result.metrics[*name];
+            row.push(summary.mean.to_string());
+            row.push(summary.variance.to_string());
+        }
+        writer.write_record(&row)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_and_aggregation() {
+        assert_eq!(parse_seeds("1-3,7").unwrap(), vec![1, 2, 3, 7]);
+        assert!(parse_seeds("5-1").is_err());
+        assert!(parse_param("buildings.costs.Walls.Stone=1,2").is_ok());
+
+        let params = vec![
+            parse_param("economy.tax_rate=0.05,0.2").unwrap(),
+            parse_param("population.birth_rate=0.01, 0.02, 0.03").unwrap(),
+        ];
+        let grid = combinations(&params);
+        assert_eq!(grid.len(), 6);
+        assert_eq!(grid[1], vec![
+            ("economy.tax_rate".to_string(), "0.05".to_string()),
+            ("population.birth_rate".to_string(), "0.02".to_string()),
+        ]);
+
+        let summary = Summary::of(&[2.0, 4.0, 6.0]);
+        assert_eq!(summary, Summary { mean: 4.0, variance: 4.0 });
+
+        // Parallel runs reproduce each other for the same seed
+        let params = vec![parse_param("economy.tax_rate=0.05,0.5").unwrap()];
+        let report = run(&SimConfig::default(), 20, &params, &[1, 1], Some(2)).unwrap();
+        assert_eq!(report.results.len(), 2);
+        for result in &report.results {
+            assert_eq!(result.runs[0].outcomes, result.runs[1].outcomes);
+            assert_eq!(result.metrics["population"].variance, 0.0);
+        }
+    }
+}
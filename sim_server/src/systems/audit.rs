@@ -0,0 +1,187 @@
+//! Runtime invariant checks between systems
+//!
+//! With `[audit] enabled = true` the scheduler hands the world to an [`Auditor`] after every
+//! system, so a broken invariant is blamed on the system that broke it:
+//!
+//! - gold conservation: wallets of agents in the live store plus construction funds change
+//!   only by what the system minted or burned through `CurrencySystem`
+//! - wallets and construction funds are finite and non-negative
+//! - agent loads and building storage stay within capacity
+//! - dead agents hold no construction assignment and no open market orders
+//!
+//! Gold moved from outside the scheduler (Admin API funding, snapshot restores) is not an
+//! error; the auditor re-reads its baseline at the start of every tick.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+use super::World;
+
+/// Float rounding allowed in the gold balance: absolute, plus relative to the gold held
+const GOLD_TOLERANCE: (f64, f64) = (1e-6, 1e-9);
+
+/// What was broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Invariant {
+    GoldConservation,
+    NegativeBalance,
+    OverCapacity,
+    DeadAgentActive,
+}
+
+/// An invariant found broken right after a system ran
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub system: &'static str,
+    pub sim_time: f64,
+    pub invariant: Invariant,
+    pub detail: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} after `{}` at {:.1}s: {}", self.invariant, self.system, self.sim_time, self.detail)
+    }
+}
+
+/// Checks invariants after each system and remembers what it has reported
+#[derive(Debug, Default)]
+pub struct Auditor {
+    /// Turn violations into errors
+    fail_on_violation: bool,
+    /// Gold held minus gold issued, as of the last check
+    balance: Option<f64>,
+    /// Standing problems already reported (a violation is blamed once, on the system that caused it)
+    open: HashSet<(Invariant, Uuid)>,
+    /// Violations per system and invariant since the start
+    counts: BTreeMap<(&'static str, Invariant), u64>,
+}
+
+impl Auditor {
+    pub fn new(fail_on_violation: bool) -> Self {
+        Self {
+            fail_on_violation,
+            ..Self::default()
+        }
+    }
+
+    pub fn fails_on_violation(&self) -> bool {
+        self.fail_on_violation
+    }
+
+    /// Take the current world as correct (called before a tick's systems run)
+    pub fn rebaseline(&mut self, world: &World) {
+        self.balance = Some(gold_balance(world).0);
+    }
+
+    /// Check every invariant after `system` ran, returning newly broken ones
+    pub fn check(&mut self, system: &'static str, world: &World, sim_time: f64) -> Vec<Violation> {
+        let mut found: Vec<(Invariant, Uuid, String)> = Vec::new();
+
+        let (balance, held) = gold_balance(world);
+        if let Some(before) = self.balance {
+            let drift = balance - before;
+            if drift.abs() > GOLD_TOLERANCE.0 + GOLD_TOLERANCE.1 * held.abs() {
+                found.push((Invariant::GoldConservation, Uuid::nil(), format!("{:+.4} gold not minted or burned", drift)));
+            }
+        }
+        self.balance = Some(balance);
+
+        let mut dead = HashSet::new();
+        for agent in world.lifecycle.agents().iter() {
+            if !agent.wallet.is_finite() || agent.wallet < 0.0 {
+                found.push((Invariant::NegativeBalance, agent.id.0, format!("{} has {} gold", agent.name, agent.wallet)));
+            }
+            let load = agent.current_inventory_weight();
+            if load > agent.max_carrying_capacity() {
+                found.push((
+                    Invariant::OverCapacity,
+                    agent.id.0,
+                    format!("{} carries {} (capacity {})", agent.name, load, agent.max_carrying_capacity()),
+                ));
+            }
+            if !agent.is_alive() {
+                dead.insert(agent.id);
+                if agent.carrying_resources.is_some() {
+                    found.push((Invariant::DeadAgentActive, agent.id.0, format!("dead {} is assigned to a construction site", agent.name)));
+                }
+            }
+        }
+
+        for market in world.markets.read().get_all_markets() {
+            for order in market.buy_orders.iter().chain(&market.sell_orders) {
+                if dead.contains(&order.agent_id) {
+                    found.push((
+                        Invariant::DeadAgentActive,
+                        order.agent_id.0,
+                        format!("dead agent has an open {:?} order at {}", order.order_type, market.name),
+                    ));
+                }
+            }
+        }
+
+        for building in world.buildings.read().get_all_buildings() {
+            if !building.construction_fund.is_finite() || building.construction_fund < 0.0 {
+                found.push((
+                    Invariant::NegativeBalance,
+                    building.id,
+                    format!("{} has a construction fund of {}", building.name, building.construction_fund),
+                ));
+            }
+            let usage = building.storage.current_usage();
+            if usage > building.storage.capacity {
+                found.push((
+                    Invariant::OverCapacity,
+                    building.id,
+                    format!("{} stores {} (capacity {})", building.name, usage, building.storage.capacity),
+                ));
+            }
+        }
+
+        // Report each standing problem once; forget the ones that were resolved
+        let current: HashSet<(Invariant, Uuid)> = found.iter().map(|(invariant, id, _)| (*invariant, *id)).collect();
+        let mut violations = Vec::new();
+        for (invariant, id, detail) in found {
+            if invariant == Invariant::GoldConservation || !self.open.contains(&(invariant, id)) {
+                *self.counts.entry((system, invariant)).or_insert(0) += 1;
+                violations.push(Violation { system, sim_time, invariant, detail });
+            }
+        }
+        self.open = current;
+        violations
+    }
+
+    /// Violations so far, keyed `system: Invariant`
+    pub fn summary(&self) -> BTreeMap<String, u64> {
+        self.counts
+            .iter()
+            .map(|((system, invariant), count)| (format!("{}: {:?}", system, invariant), *count))
+            .collect()
+    }
+}
+
+/// Gold held (by agents in the live store and in construction funds) minus gold issued, and gold held
+fn gold_balance(world: &World) -> (f64, f64) {
+    let wallets: f64 = world.lifecycle.agents().iter().map(|a| a.wallet).sum();
+    let funds: f64 = world.buildings.read().get_all_buildings().iter().map(|b| b.construction_fund).sum();
+    let held = wallets + funds;
+    (held - world.currency.read().net_issued(), held)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch;
+    use crate::config::SimConfig;
+
+    #[tokio::test]
+    async fn test_seeded_run_keeps_invariants() {
+        let mut config = SimConfig::default();
+        config.audit.enabled = true;
+        config.audit.fail_on_violation = true;
+
+        // Long enough for a very slow tick (taxes, demographics, building orders)
+        let (report, _) = batch::run(config, 620, 7, None).await.unwrap();
+        assert_eq!(report.audit, Some(Default::default()));
+    }
+}
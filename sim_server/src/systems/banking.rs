@@ -0,0 +1,118 @@
+//! Burgher banking and market facilitation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+pub struct BankingSystem;
+
+#[async_trait]
+impl System for BankingSystem {
+    fn name(&self) -> &'static str {
+        "banking"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Markets], &[Resource::Agents])
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        process_burgher_activities(world, tick);
+        
+        Ok(())
+    }
+}
+
+/// Burgher banking and market facilitation system
+fn process_burgher_activities(world: &World, tick: &Tick) {
+    let mut agents = world.lifecycle.get_agents_mut();
+    
+    // Find Burghers/Merchants with sufficient capital
+    let mut wealthy_burghers: Vec<(world_sim_core::AgentId, String, f64)> = agents.iter()
+        .filter(|a| matches!(a.social_class, world_sim_agents::SocialClass::Burgher | world_sim_agents::SocialClass::Merchant) && a.wallet >= 500.0)
+        .map(|a| (a.id, a.name.clone(), a.wallet))
+        .collect();
+    
+    if wealthy_burghers.is_empty() {
+        drop(agents);
+        return;
+    }
+    
+    // LOAN SYSTEM: Burghers offer construction loans to peasants
+    let wood_price = world.market_price(world_sim_core::ResourceType::Wood);
+    let stone_price = world.market_price(world_sim_core::ResourceType::Stone);
+    let house_cost = (30.0 * wood_price + 10.0 * stone_price) * 3.0;
+    
+    // Collect loan requests (avoid double borrow)
+    let mut loan_requests: Vec<(world_sim_core::AgentId, String, f64)> = Vec::new(); // (borrower_id, name, amount_needed)
+    
+    for agent in agents.iter() {
+        if !matches!(agent.social_class, world_sim_agents::SocialClass::Peasant) {
+            continue;
+        }
+        
+        // Peasant wants to build but doesn't have full amount
+        if agent.wallet < house_cost && agent.wallet >= house_cost * 0.3 && agent.loans_owed.is_empty() {
+            // Has 30%+ down payment AND no existing loans - eligible!
+            let loan_amount = house_cost - agent.wallet;
+            loan_requests.push((agent.id, agent.name.clone(), loan_amount));
+        }
+    }
+    
+    // Process loans (now we can safely mutate)
+    let mut loans_issued = 0;
+    for (borrower_id, borrower_name, loan_amount) in loan_requests {
+        // Find a wealthy burgher
+        if let Some(idx) = wealthy_burghers.iter().position(|(_, _, wallet)| *wallet >= loan_amount) {
+            let (lender_id, lender_name, _) = wealthy_burghers[idx].clone();
+            
+            let loan = world_sim_agents::Loan {
+                lender_id,
+                borrower_id,
+                principal: loan_amount,
+                remaining: loan_amount * 1.05, // 5% interest
+                interest_rate: 0.05,
+                issued_time: tick.sim_time,
+            };
+            
+            // Update borrower
+            if let Some(borrower) = agents.get_mut(borrower_id) {
+                borrower.wallet += loan_amount;
+                borrower.loans_owed.push(loan.clone());
+            }
+            
+            // Update lender
+            if let Some(lender) = agents.get_mut(lender_id) {
+                lender.wallet -= loan_amount;
+                lender.loans_given.push(loan);
+            }
+            
+            info!("🏦 Burgher {} lent {:.1} gold to {} for construction (5% interest)", 
+                  lender_name, loan_amount, borrower_name);
+            
+            loans_issued += 1;
+            
+            // Update cached wallet and remove if depleted
+            wealthy_burghers[idx].2 -= loan_amount;
+            if wealthy_burghers[idx].2 < 500.0 {
+                wealthy_burghers.remove(idx);
+            }
+            
+            if loans_issued >= 3 {
+                break; // Max 3 loans per second to avoid spam
+            }
+        }
+    }
+    
+    if loans_issued > 0 {
+        info!("🏦 Issued {} construction loans this cycle", loans_issued);
+    }
+    
+    drop(agents);
+}
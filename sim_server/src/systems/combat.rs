@@ -0,0 +1,249 @@
+//! Melee combat between rival factions and organic war declarations
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use tracing::info;
+use world_sim_agents::{AgentState, SimAgent};
+use world_sim_core::{sim_rng, AgentId, FactionId, Position};
+use world_sim_world::{BuildingOwner, BuildingType, ResourceNodeType};
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// Enemies in range fight; the `nonlethal` variant never kills (or raids)
+pub struct CombatSystem {
+    lethal: bool,
+}
+
+impl CombatSystem {
+    pub fn lethal() -> Self {
+        Self { lethal: true }
+    }
+
+    pub fn nonlethal() -> Self {
+        Self { lethal: false }
+    }
+}
+
+#[async_trait]
+impl System for CombatSystem {
+    fn name(&self) -> &'static str {
+        "combat"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Fast
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Politics], &[Resource::Agents, Resource::Nodes, Resource::Buildings])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        // Phase 3: Combat detection
+        let mut combat_pairs = Vec::new();
+        {
+            let agents = world.lifecycle.agents();
+            for agent_a in agents.living() {
+                // Only agents with a faction can fight
+                let Some(agent_a) = Combatant::of(agent_a) else { continue };
+                
+                // Neighbours come in store order; taking only those after agent_a visits each
+                // pair once, in the same order as a full pairwise scan
+                let nearby = agents.within_radius(&agent_a.position, 15.0);
+                for agent_b in nearby.into_iter().skip_while(|b| b.id != agent_a.id).skip(1) {
+                    if !agent_b.is_alive() {
+                        continue;
+                    }
+                    let Some(agent_b) = Combatant::of(agent_b) else { continue };
+                    
+                    if agent_a.faction != agent_b.faction {
+                        // Different factions = enemies!
+                        let dist = agent_a.position.distance_to(&agent_b.position);
+                        
+                        if dist < 15.0 {
+                            // Close enough to fight! (increased from 5.0 for more frequent combat)
+                            combat_pairs.push((agent_a, agent_b, dist));
+                        }
+                    }
+                }
+            }
+        }
+        
+        // Process combat and resource raiding
+        let mut rng = sim_rng();
+        for (agent_a, agent_b, dist) in combat_pairs {
+            let (id_a, id_b) = (agent_a.id, agent_b.id);
+            // Set to fighting state whenever enemies are in range
+            world.lifecycle.update_agent_state(id_a, AgentState::Fighting { target: id_b });
+            world.lifecycle.update_agent_state(id_b, AgentState::Fighting { target: id_a });
+            
+            // Chance per tick that someone dies when very close (combat.death_chance)
+            if self.lethal && dist < 5.0 && rng.gen::<f64>() < world.config.combat.death_chance {
+                let (loser, winner) = if rng.gen::<bool>() { (agent_a, agent_b) } else { (agent_b, agent_a) };
+                
+                world.lifecycle.kill_agent(loser.id, "Combat".to_string()).await;
+                
+                // Resource raiding: Winner steals resources near the loser's position
+                // 30% chance to raid nearby resources after winning combat
+                if rng.gen::<f32>() < 0.3 {
+                    // Find nearest resource within 10 units
+                    let resource = world.resources
+                        .find_nearest_matching(loser.position, |r| r.quantity > 0)
+                        .filter(|r| r.position.distance_to(&loser.position) < 10.0);
+                    
+                    if let Some(resource) = resource {
+                        // Raid 10-30% of the resource
+                        let raid_percent = rng.gen_range(0.1..0.3);
+                        let raid_amount = (resource.quantity as f32 * raid_percent) as u32;
+                        let raid_amount = raid_amount.max(1).min(resource.quantity);
+                        
+                        if world.resources.harvest(resource.id, raid_amount).is_some() {
+                            info!("⚔️ Resource raided! Winner took {} units from {:?}", raid_amount, resource.resource_type);
+                            
+                            // Winner's faction gains resources (stored in nearest faction warehouse if available)
+                            let warehouse_id = world.buildings.read()
+                                .find_nearest_building_matching(&winner.position, |b| {
+                                    matches!(b.building_type, BuildingType::Warehouse)
+                                        && matches!(&b.owner, BuildingOwner::Faction(f) if *f == winner.faction)
+                                        && b.is_complete()
+                                })
+                                .map(|w| w.id);
+                            
+                            // Store raided resources in warehouse
+                            if let Some(warehouse_id) = warehouse_id {
+                                let mut buildings_mut = world.buildings.write();
+                                if let Some(wh) = buildings_mut.get_building_mut(warehouse_id) {
+                                    // Convert resource node type to resource type (simplified mapping)
+                                    let resource_type = match resource.resource_type {
+                                        ResourceNodeType::Tree => world_sim_core::ResourceType::Wood,
+                                        ResourceNodeType::Rock => world_sim_core::ResourceType::Stone,
+                                        ResourceNodeType::IronDeposit => world_sim_core::ResourceType::Iron,
+                                        ResourceNodeType::GoldDeposit => world_sim_core::ResourceType::Gold,
+                                        ResourceNodeType::Farm => world_sim_core::ResourceType::Food,
+                                    };
+                                    
+                                    wh.storage.store(resource_type, raid_amount);
+                                    info!("📦 Raided resources stored in {}", wh.name);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// What combat needs to know about a faction member
+#[derive(Clone, Copy)]
+struct Combatant {
+    id: AgentId,
+    position: Position,
+    faction: FactionId,
+}
+
+impl Combatant {
+    fn of(agent: &SimAgent) -> Option<Self> {
+        agent.personality.beliefs.faction_loyalty.map(|faction| Self {
+            id: agent.id,
+            position: agent.position,
+            faction,
+        })
+    }
+}
+
+/// Declares wars when factions run short of resources
+pub struct WarSystem;
+
+#[async_trait]
+impl System for WarSystem {
+    fn name(&self) -> &'static str {
+        "war"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Agents, Resource::Nodes], &[Resource::Politics])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["demographics"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        check_resource_scarcity_and_trigger_wars(world).await;
+        
+        Ok(())
+    }
+}
+
+/// Check for resource scarcity and trigger wars organically
+async fn check_resource_scarcity_and_trigger_wars(world: &World) {
+    let resource_nodes = world.resources.get_nodes();
+    
+    // Calculate total resources available
+    let total_food: u32 = resource_nodes
+        .iter()
+        .filter(|r| matches!(r.resource_type, ResourceNodeType::Farm))
+        .map(|r| r.quantity)
+        .sum();
+    
+    let total_materials: u32 = resource_nodes
+        .iter()
+        .filter(|r| matches!(
+            r.resource_type,
+            ResourceNodeType::Tree | ResourceNodeType::Rock | ResourceNodeType::IronDeposit
+        ))
+        .map(|r| r.quantity)
+        .sum();
+    
+    let agent_count = world.lifecycle.count_living();
+    
+    // Check if resources are scarce (per capita)
+    let food_per_capita = if agent_count > 0 {
+        total_food as f32 / agent_count as f32
+    } else {
+        100.0
+    };
+    
+    let materials_per_capita = if agent_count > 0 {
+        total_materials as f32 / agent_count as f32
+    } else {
+        100.0
+    };
+    
+    // Trigger war if resources are scarce (< 15 per person) and no active war
+    if food_per_capita < 15.0 || materials_per_capita < 20.0 {
+        // Check if already at war
+        let factions = world.politics.get_all_factions();
+        
+        if factions.len() >= 2 {
+            let faction_a = factions[0].id;
+            let faction_b = factions[1].id;
+            
+            // Check if already at war (simplified - check if agents are hostile)
+            let has_combat = world.lifecycle.agents().iter().any(|a| matches!(a.state, AgentState::Fighting { .. }));
+            
+            // Only declare war if not already fighting and resources are critically low
+            if !has_combat && (food_per_capita < 10.0 || materials_per_capita < 15.0) {
+                let reason = if food_per_capita < materials_per_capita {
+                    format!("Food scarcity crisis! ({:.1} food per person)", food_per_capita)
+                } else {
+                    format!("Material shortage! ({:.1} materials per person)", materials_per_capita)
+                };
+                
+                world.politics.declare_war(faction_a, faction_b, reason).await;
+                
+                info!("⚔️ WAR DECLARED due to resource scarcity!");
+                info!("  Food per capita: {:.1}", food_per_capita);
+                info!("  Materials per capita: {:.1}", materials_per_capita);
+            }
+        }
+    }
+}
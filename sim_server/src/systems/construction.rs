@@ -0,0 +1,844 @@
+//! Builder assignment, construction progress, building blocks and construction funding
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+use uuid::Uuid;
+use world_sim_agents::{AgentState, BuildingResources, Job};
+use world_sim_core::{AgentId, GridCoord, Position, ResourceType};
+use world_sim_societal::MarketSystem;
+use world_sim_world::{BuildingManager, BuildingType, ObjectKind};
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// Snapshot of an incomplete building used for builder assignment
+/// (id, name, type, remaining resources, construction progress)
+type IncompleteBuilding = (Uuid, String, BuildingType, std::collections::HashMap<world_sim_core::ResourceType, u32>, f32);
+
+pub struct BuilderAssignmentSystem;
+
+#[async_trait]
+impl System for BuilderAssignmentSystem {
+    fn name(&self) -> &'static str {
+        "builder_assignment"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Buildings], &[Resource::Agents])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["needs"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        assign_builders_to_buildings(world);
+        
+        Ok(())
+    }
+}
+
+/// Builders at a market buy their site's materials, and builders at their site deliver
+/// materials and spend them to advance construction
+pub struct ConstructionSystem;
+
+/// Builders this close to a market can buy there
+const MARKET_BUYING_DISTANCE: f32 = 6.5;
+
+/// Materials builders buy and carry (the ones `BuildingResources` holds)
+const MATERIALS: [ResourceType; 3] = [ResourceType::Wood, ResourceType::Stone, ResourceType::Iron];
+
+/// A builder's part of the construction pass (written back to the agent afterwards)
+struct BuilderWork {
+    id: AgentId,
+    position: Position,
+    carrying: Option<BuildingResources>,
+    state: AgentState,
+    /// The cart the builder drives and what is loaded on it
+    cart: Option<(Uuid, BTreeMap<ResourceType, u32>)>,
+}
+
+/// An empty-handed builder at a market
+struct Buyer {
+    id: AgentId,
+    site: Uuid,
+    market: Uuid,
+    /// Units the builder can still carry
+    room: u32,
+    /// The cart the builder drives and the units it can still take
+    cart: Option<(Uuid, u32)>,
+}
+
+#[async_trait]
+impl System for ConstructionSystem {
+    fn name(&self) -> &'static str {
+        "construction"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(
+            &[],
+            &[Resource::Agents, Resource::Buildings, Resource::Markets, Resource::Currency, Resource::Objects],
+        )
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["builder_assignment", "haulage"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        return_surplus(world);
+        buy_materials(world);
+
+        // Update building construction progress
+        // Collect buildings that need construction first (avoid borrow conflicts)
+        let incomplete_buildings: Vec<(Uuid, Position, f32)> = {
+            let buildings = world.buildings.read();
+            buildings.get_all_buildings()
+                .iter()
+                .filter(|b| !b.is_complete())
+                .map(|b| (b.id, b.position, (b.building_type.approach() as f32 + 2.0).max(5.0)))
+                .collect()
+        }; // Drop read lock
+        
+        // RESOURCE-BASED CONSTRUCTION: Builders deliver and consume resources
+        if !incomplete_buildings.is_empty() {
+            // Work on copies of the builders so the agents lock isn't held with the buildings lock
+            let carts: HashMap<AgentId, (Uuid, BTreeMap<ResourceType, u32>)> = world.objects.read()
+                .get_all()
+                .iter()
+                .filter(|o| o.kind == ObjectKind::Cart)
+                .filter_map(|o| o.crew.first().map(|driver| (*driver, (o.id, o.cargo.clone()))))
+                .collect();
+            let mut builders: Vec<BuilderWork> = world.lifecycle.agents()
+                .iter()
+                .filter(|agent| matches!(agent.job, Job::Builder))
+                .map(|agent| BuilderWork {
+                    id: agent.id,
+                    position: agent.position,
+                    carrying: agent.carrying_resources.clone(),
+                    state: agent.state.clone(),
+                    cart: carts.get(&agent.id).cloned(),
+                })
+                .collect();
+            
+            // Carts emptied at a site
+            let mut unloaded: Vec<(Uuid, BTreeMap<ResourceType, u32>)> = Vec::new();
+            let mut buildings_write = world.buildings.write();
+            for (building_id, building_pos, reach) in incomplete_buildings {
+                if let Some(building) = buildings_write.get_building_mut(building_id) {
+                    // Process builders at this building (carts pull up beside it, so only the ground distance counts)
+                    for builder in builders.iter_mut() {
+                        let dist_to_building = builder.position.horizontal_distance_to(&building_pos);
+                        
+                        // Carrying resources for this building - deliver them (and whatever is on their cart)
+                        if let Some(carrying) = &builder.carrying {
+                            if carrying.target_building_id == building_id && dist_to_building < reach {
+                                if carrying.wood > 0 {
+                                    building.add_resources(world_sim_core::ResourceType::Wood, carrying.wood);
+                                }
+                                if carrying.stone > 0 {
+                                    building.add_resources(world_sim_core::ResourceType::Stone, carrying.stone);
+                                }
+                                if carrying.iron > 0 {
+                                    building.add_resources(world_sim_core::ResourceType::Iron, carrying.iron);
+                                }
+                                
+                                info!("🚚 Builder delivered {} wood, {} stone, {} iron to {}", 
+                                      carrying.wood, carrying.stone, carrying.iron, building.name);
+                                
+                                if let Some((cart, cargo)) = builder.cart.take().filter(|(_, cargo)| !cargo.is_empty()) {
+                                    for (resource, quantity) in &cargo {
+                                        building.add_resources(*resource, *quantity);
+                                    }
+                                    info!("🛒 Cart unloaded {:?} at {}", cargo, building.name);
+                                    unloaded.push((cart, cargo));
+                                }
+                                
+                                builder.carrying = None;
+                            }
+                        }
+                        
+                        // Work on construction if at site (with resource consumption)
+                        if dist_to_building < reach && builder.carrying.is_none() {
+                            let progress_per_builder = 0.02; // 2% per builder per second
+                            
+                            if building.construct_with_resources(progress_per_builder) {
+                                builder.state = AgentState::Building { 
+                                    building_type: format!("{:?}", building.building_type)
+                                };
+                                
+                                if building.is_complete() {
+                                    info!("🏗️ Building completed: {}", building.name);
+                                }
+                            } else {
+                                // Can't construct - need more resources
+                                builder.state = AgentState::Idle;
+                            }
+                        }
+                    }
+                }
+            }
+            drop(buildings_write);
+            
+            if !unloaded.is_empty() {
+                let mut objects = world.objects.write();
+                for (cart, cargo) in unloaded {
+                    if let Some(cart) = objects.get_mut(cart) {
+                        for (resource, quantity) in cargo {
+                            cart.unload_cargo(resource, quantity);
+                        }
+                    }
+                }
+            }
+            
+            world.lifecycle.apply_updates(
+                builders.into_iter().map(|b| (b.id, (b.carrying, b.state))).collect(),
+                |agent, (carrying, state)| {
+                    agent.carrying_resources = carrying;
+                    agent.state = state;
+                },
+            );
+        }
+        
+        Ok(())
+    }
+}
+
+/// Materials whose site is gone or finished go into storage: what builders (and their carts)
+/// carry for a demolished or completed site, and what a finished building was left holding
+/// (e.g. when the Admin API completes it). Builders with nothing left are released.
+fn return_surplus(world: &World) {
+    // Builders carrying for a site that is no longer being built, with what is on their cart
+    let (orphans, leftovers) = {
+        let buildings = world.buildings.read();
+        let building = |id: Uuid| buildings.get_building(id).filter(|b| !b.is_complete());
+        let carts: HashMap<AgentId, (Uuid, BTreeMap<ResourceType, u32>)> = world.objects.read()
+            .get_all()
+            .iter()
+            .filter(|o| o.kind == ObjectKind::Cart)
+            .filter_map(|o| o.crew.first().map(|driver| (*driver, (o.id, o.cargo.clone()))))
+            .collect();
+        let orphans: Vec<(BuilderWork, BuildingResources)> = world.lifecycle.agents()
+            .living()
+            .filter(|a| matches!(a.job, Job::Builder))
+            .filter_map(|a| {
+                let carrying = a.carrying_resources.clone().filter(|c| building(c.target_building_id).is_none())?;
+                Some((
+                    BuilderWork { id: a.id, position: a.position, carrying: None, state: AgentState::Idle, cart: carts.get(&a.id).cloned() },
+                    carrying,
+                ))
+            })
+            .collect();
+        let leftovers: Vec<(Uuid, Vec<ResourceType>)> = buildings.get_all_buildings()
+            .iter()
+            .filter(|b| b.is_complete() && b.current_resources.values().any(|q| *q > 0))
+            .map(|b| (b.id, b.current_resources.keys().copied().collect()))
+            .collect();
+        (orphans, leftovers)
+    };
+    if orphans.is_empty() && leftovers.is_empty() {
+        return;
+    }
+
+    // Priced before the markets are locked, for a market that doesn't stock a resource yet
+    let prices: BTreeMap<ResourceType, f64> = MATERIALS
+        .into_iter()
+        .chain(orphans.iter().flat_map(|(b, _)| b.cart.iter().flat_map(|(_, cargo)| cargo.keys().copied())))
+        .chain(leftovers.iter().flat_map(|(_, resources)| resources.iter().copied()))
+        .map(|resource| (resource, world.market_price(resource)))
+        .collect();
+    let price = |resource: ResourceType| prices[&resource];
+    let mut released: Vec<(AgentId, (Option<BuildingResources>, AgentState))> = Vec::new();
+    let mut unloaded: Vec<(Uuid, BTreeMap<ResourceType, u32>)> = Vec::new();
+    {
+        let mut buildings = world.buildings.write();
+        let mut markets = world.markets.write();
+        for (builder, mut carrying) in orphans {
+            for (resource, quantity) in [(ResourceType::Wood, &mut carrying.wood), (ResourceType::Stone, &mut carrying.stone), (ResourceType::Iron, &mut carrying.iron)] {
+                *quantity = stow(&mut buildings, &mut markets, &builder.position, resource, *quantity, price(resource));
+            }
+            if let Some((cart, cargo)) = builder.cart {
+                let stowed: BTreeMap<ResourceType, u32> = cargo
+                    .into_iter()
+                    .map(|(resource, quantity)| {
+                        (resource, quantity - stow(&mut buildings, &mut markets, &builder.position, resource, quantity, price(resource)))
+                    })
+                    .collect();
+                unloaded.push((cart, stowed));
+            }
+            // With nowhere to put it, the rest stays in hand until there is room
+            let kept = (carrying.wood + carrying.stone + carrying.iron > 0).then_some(carrying);
+            info!("🔨 Builder released from a finished or demolished site (still carrying {:?})", kept);
+            released.push((builder.id, (kept, builder.state)));
+        }
+        for (id, _) in leftovers {
+            let Some(building) = buildings.get_building_mut(id) else { continue };
+            let (position, name) = (building.position, building.name.clone());
+            let mut left = std::mem::take(&mut building.current_resources);
+            for (resource, quantity) in left.iter_mut() {
+                *quantity = stow(&mut buildings, &mut markets, &position, *resource, *quantity, price(*resource));
+            }
+            left.retain(|_, quantity| *quantity > 0);
+            info!("📦 Leftover materials of {} put into storage", name);
+            if let Some(building) = buildings.get_building_mut(id) {
+                building.current_resources = left;
+            }
+        }
+    }
+
+    if !unloaded.is_empty() {
+        let mut objects = world.objects.write();
+        for (cart, stowed) in unloaded {
+            if let Some(cart) = objects.get_mut(cart) {
+                for (resource, quantity) in stowed {
+                    cart.unload_cargo(resource, quantity);
+                }
+            }
+        }
+    }
+    world.lifecycle.apply_updates(released, |agent, (carrying, state)| {
+        agent.carrying_resources = carrying;
+        agent.state = state;
+    });
+}
+
+/// Puts a lot of materials into the nearest finished warehouses with room, and the rest on the
+/// nearest market; returns what found no place
+fn stow(
+    buildings: &mut BuildingManager,
+    markets: &mut MarketSystem,
+    at: &Position,
+    resource: ResourceType,
+    mut quantity: u32,
+    base_price: f64,
+) -> u32 {
+    while quantity > 0 {
+        let Some(id) = buildings
+            .find_nearest_building_matching(at, |b| {
+                b.building_type == BuildingType::Warehouse && b.is_complete() && b.storage.available_space() > 0
+            })
+            .map(|b| b.id)
+        else {
+            break;
+        };
+        let Some(warehouse) = buildings.get_building_mut(id) else { break };
+        let stored = quantity.min(warehouse.storage.available_space());
+        warehouse.storage.store(resource, stored);
+        quantity -= stored;
+    }
+    if quantity > 0 {
+        if let Some(market) = markets.find_nearest_market(at, None).map(|m| m.id).and_then(|id| markets.get_market_mut(id)) {
+            market.add_inventory(resource, quantity, base_price);
+            quantity = 0;
+        }
+    }
+    quantity
+}
+
+/// Builders at a market with nothing in hand buy what their site still lacks (less what is already
+/// on its way) out of the site's construction fund, as much as they and the cart they drive can carry
+fn buy_materials(world: &World) {
+    let carts: HashMap<AgentId, (Uuid, u32, BTreeMap<ResourceType, u32>)> = world.objects.read()
+        .get_all()
+        .iter()
+        .filter(|o| o.kind == ObjectKind::Cart)
+        .filter_map(|o| o.crew.first().map(|driver| (*driver, (o.id, o.free_capacity(), o.cargo.clone()))))
+        .collect();
+
+    let mut in_transit: HashMap<(Uuid, ResourceType), u32> = HashMap::new();
+    let mut buyers: Vec<Buyer> = Vec::new();
+    {
+        let agents = world.lifecycle.agents();
+        let markets = world.markets.read();
+        for agent in agents.iter().filter(|a| a.is_alive() && matches!(a.job, Job::Builder)) {
+            let Some(carrying) = &agent.carrying_resources else { continue };
+            let site = carrying.target_building_id;
+            let cart = carts.get(&agent.id);
+            let mut load = 0;
+            for (resource, quantity) in [(ResourceType::Wood, carrying.wood), (ResourceType::Stone, carrying.stone), (ResourceType::Iron, carrying.iron)]
+                .into_iter()
+                .chain(cart.iter().flat_map(|(_, _, cargo)| cargo.iter().map(|(r, q)| (*r, *q))))
+            {
+                *in_transit.entry((site, resource)).or_insert(0) += quantity;
+                load += quantity;
+            }
+            if load > 0 {
+                continue;
+            }
+            let Some(market) = markets
+                .find_nearest_market(&agent.position, None)
+                .filter(|m| m.position.horizontal_distance_to(&agent.position) <= MARKET_BUYING_DISTANCE)
+            else {
+                continue;
+            };
+            buyers.push(Buyer {
+                id: agent.id,
+                site,
+                market: market.id,
+                room: agent.max_carrying_capacity().saturating_sub(agent.current_inventory_weight()),
+                cart: cart.map(|(id, free, _)| (*id, *free)),
+            });
+        }
+    }
+    if buyers.is_empty() {
+        return;
+    }
+
+    // What each buyer carries and what goes on their cart
+    let mut purchases: Vec<(AgentId, BTreeMap<ResourceType, u32>)> = Vec::new();
+    let mut cart_loads: Vec<(Uuid, BTreeMap<ResourceType, u32>)> = Vec::new();
+    let mut payments: Vec<f64> = Vec::new();
+    {
+        let mut buildings = world.buildings.write();
+        let mut markets = world.markets.write();
+        for buyer in buyers {
+            let (Some(building), Some(market)) = (buildings.get_building_mut(buyer.site), markets.get_market_mut(buyer.market)) else {
+                continue;
+            };
+            let remaining = building.remaining_resources();
+            let (mut room, mut cart_room) = (buyer.room, buyer.cart.map_or(0, |(_, free)| free));
+            let mut spent = 0.0;
+            let (mut carried, mut loaded) = (BTreeMap::new(), BTreeMap::new());
+            for resource in MATERIALS {
+                let transit = in_transit.entry((buyer.site, resource)).or_insert(0);
+                let needed = remaining.get(&resource).copied().unwrap_or(0).saturating_sub(*transit);
+                let Some(good) = market.inventory.get(&resource).filter(|g| g.current_price > 0.0) else { continue };
+                let price = good.current_price;
+                let affordable = ((building.construction_fund - spent) / price).floor().max(0.0) as u32;
+                let quantity = needed.min(good.quantity).min(affordable).min(room + cart_room);
+                if quantity == 0 || !market.remove_inventory(resource, quantity) {
+                    continue;
+                }
+                spent += quantity as f64 * price;
+                *transit += quantity;
+                let by_hand = quantity.min(room);
+                room -= by_hand;
+                cart_room -= quantity - by_hand;
+                if by_hand > 0 {
+                    carried.insert(resource, by_hand);
+                }
+                if quantity > by_hand {
+                    loaded.insert(resource, quantity - by_hand);
+                }
+            }
+            if spent == 0.0 {
+                continue;
+            }
+
+            building.construction_fund = (building.construction_fund - spent).max(0.0);
+            info!("🧱 Builder bought {:?} (and {:?} by cart) at {} for {} ({:.1} gold)", carried, loaded, market.name, building.name, spent);
+            if let Some((cart, _)) = buyer.cart.filter(|_| !loaded.is_empty()) {
+                cart_loads.push((cart, loaded));
+            }
+            purchases.push((buyer.id, carried));
+            payments.push(spent);
+        }
+    }
+
+    // The market keeps no gold, so what the fund paid leaves circulation
+    {
+        let mut currency = world.currency.write();
+        for spent in payments {
+            currency.record_transaction(spent);
+            currency.burn_currency(spent);
+        }
+    }
+    {
+        let mut objects = world.objects.write();
+        for (cart, loaded) in cart_loads {
+            if let Some(cart) = objects.get_mut(cart) {
+                for (resource, quantity) in loaded {
+                    cart.load_cargo(resource, quantity);
+                }
+            }
+        }
+    }
+    world.lifecycle.apply_updates(purchases, |agent, carried| {
+        if let Some(carrying) = agent.carrying_resources.as_mut() {
+            carrying.wood += carried.get(&ResourceType::Wood).copied().unwrap_or(0);
+            carrying.stone += carried.get(&ResourceType::Stone).copied().unwrap_or(0);
+            carrying.iron += carried.get(&ResourceType::Iron).copied().unwrap_or(0);
+        }
+    });
+}
+
+/// Keeps each building's blocks in the grid in step with its construction progress and health,
+/// and clears away the blocks of buildings that are gone
+#[derive(Default)]
+pub struct StructureSystem {
+    /// Per building: its type, the lowest corner of its footprint and the blueprint blocks standing
+    standing: BTreeMap<Uuid, (BuildingType, GridCoord, usize)>,
+}
+
+#[async_trait]
+impl System for StructureSystem {
+    fn name(&self) -> &'static str {
+        "structures"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Buildings], &[Resource::Grid])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["construction", "water", "fire"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        let buildings: BTreeMap<Uuid, (BuildingType, GridCoord, usize)> = world
+            .buildings
+            .read()
+            .get_all_buildings()
+            .iter()
+            .map(|b| (b.id, (b.building_type, b.footprint().0, b.standing_blocks())))
+            .collect();
+
+        // Demolished, burned down or washed away (kept until their site is loaded to clear)
+        self.standing.retain(|id, (building_type, origin, standing)| {
+            buildings.contains_key(id) || building_type.materialize(&world.grid, *origin, *standing, 0).is_none()
+        });
+
+        // A site reaching into an unloaded chunk waits for it to be loaded again
+        for (id, (building_type, origin, target)) in buildings {
+            let standing = self.standing.entry(id).or_insert((building_type, origin, 0));
+            if standing.2 != target && building_type.materialize(&world.grid, origin, standing.2, target).is_some() {
+                standing.2 = target;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ConstructionFundingSystem;
+
+#[async_trait]
+impl System for ConstructionFundingSystem {
+    fn name(&self) -> &'static str {
+        "construction_funding"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Markets], &[Resource::Buildings, Resource::Kingdoms])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["taxes"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        replenish_construction_funds(world);
+        
+        Ok(())
+    }
+}
+
+/// Assign idle builders to incomplete buildings (priority queue: oldest first)
+fn assign_builders_to_buildings(world: &World) {
+    let buildings_lock = world.buildings.read();
+    let all_buildings = buildings_lock.get_all_buildings();
+    
+    // Collect building info we need (clone to avoid borrow issues)
+    let mut incomplete_buildings: Vec<IncompleteBuilding> = all_buildings.iter()
+        .filter(|b| b.construction_progress < 1.0)
+        .map(|b| (b.id, b.name.clone(), b.building_type, b.remaining_resources(), b.construction_progress))
+        .collect();
+    
+    // Sort by progress (least complete first to focus efforts)
+    incomplete_buildings.sort_by(|a, b| a.4.partial_cmp(&b.4).unwrap());
+    
+    drop(buildings_lock); // Release read lock before acquiring agents lock
+    
+    if incomplete_buildings.is_empty() {
+        return; // No work to do
+    }
+    
+    let mut agents = world.lifecycle.get_agents_mut();
+    
+    // Count all builders and their states for diagnostics
+    let all_builders: Vec<_> = agents.iter()
+        .filter(|a| matches!(a.job, Job::Builder))
+        .collect();
+    
+    let total_builders = all_builders.len();
+    let idle_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Idle)).count();
+    let carrying_count = all_builders.iter().filter(|a| a.carrying_resources.is_some()).count();
+    let building_state_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Building { .. })).count();
+    let eating_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Eating)).count();
+    let sleeping_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Sleeping)).count();
+    let working_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Working { .. })).count();
+    let trading_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Trading { .. })).count();
+    let moving_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Moving { .. })).count();
+    let fighting_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Fighting { .. })).count();
+    let talking_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Talking { .. })).count();
+    let patrolling_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Patrolling { .. })).count();
+    let following_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Following { .. })).count();
+    let dead_count = all_builders.iter().filter(|a| matches!(a.state, AgentState::Dead)).count();
+    
+    // Find all idle builders (with no resources)
+    let idle_builders: Vec<_> = agents.iter()
+        .filter(|a| matches!(a.job, Job::Builder) && matches!(a.state, AgentState::Idle) && a.carrying_resources.is_none())
+        .map(|a| a.id)
+        .collect();
+    
+    // ALWAYS log builder diagnostics when there are incomplete buildings
+    info!("🔨 Builder assignment check: {} incomplete buildings | Builders: {} total | States: Idle:{} Eating:{} Sleeping:{} Working:{} Trading:{} Building:{} Moving:{} Fighting:{} Talking:{} Patrol:{} Follow:{} Dead:{} | Carrying:{}", 
+          incomplete_buildings.len(), total_builders, idle_count, eating_count, sleeping_count, working_count, trading_count, building_state_count, moving_count, fighting_count, talking_count, patrolling_count, following_count, dead_count, carrying_count);
+    
+    if idle_builders.is_empty() {
+        if total_builders == 0 {
+            info!("⚠️ No builders exist! Need to assign more agents to Builder job.");
+        } else {
+            info!("⚠️ No idle builders available (all {} builders are busy or carrying resources)", total_builders);
+        }
+        return; // No idle builders
+    }
+    
+    info!("🔨 Builder assignment: {} idle builders, {} incomplete buildings", 
+          idle_builders.len(), incomplete_buildings.len());
+    
+    // Assign builders to buildings (round-robin to distribute workload)
+    for (idx, builder_id) in idle_builders.iter().enumerate() {
+        let building_idx = idx % incomplete_buildings.len();
+        let (building_id, building_name, building_type, remaining, _progress) = &incomplete_buildings[building_idx];
+        
+        if let Some(agent) = agents.get_mut(*builder_id) {
+            if remaining.values().sum::<u32>() == 0 {
+                // Building has all resources, can construct!
+                agent.state = AgentState::Building { building_type: format!("{:?}", building_type) };
+                // Set target building so builder knows where to go
+                agent.carrying_resources = Some(world_sim_agents::BuildingResources {
+                    wood: 0,
+                    stone: 0,
+                    iron: 0,
+                    target_building_id: *building_id,
+                });
+                info!("🔨 Builder {} assigned to {} (ID: {:?}, has all resources, can construct!)", 
+                      agent.name, building_name, building_id);
+            } else {
+                // Need to get resources from market - set target but with zero resources
+                agent.state = AgentState::Moving { destination: world_sim_core::GridCoord { x: 0, y: 0, z: 0 } }; // Will move to market
+                // Set target building ID, but keep resources at ZERO
+                agent.carrying_resources = Some(world_sim_agents::BuildingResources {
+                    wood: 0,
+                    stone: 0,
+                    iron: 0,
+                    target_building_id: *building_id,
+                });
+                info!("🔨 Builder {} assigned to {} (ID: {:?}, needs: {:?}) - heading to market to buy resources", 
+                      agent.name, building_name, building_id, remaining);
+            }
+        }
+    }
+}
+
+/// Replenish construction funds if prices rose (runs every 60s)
+fn replenish_construction_funds(world: &World) {
+    // Step 1: Identify buildings that need more funding
+    let buildings_lock = world.buildings.read();
+    let all_buildings = buildings_lock.get_all_buildings();
+    
+    let mut funding_requests: Vec<(uuid::Uuid, String, world_sim_world::BuildingOwner, f64, f64)> = Vec::new();
+    
+    for building in all_buildings {
+        if building.construction_progress < 1.0 {
+            // Calculate remaining resource cost at CURRENT market prices
+            let remaining = building.remaining_resources();
+            let estimated_cost: f64 = remaining.iter()
+                .map(|(resource_type, qty)| {
+                    world.market_price(*resource_type) * (*qty as f64)
+                })
+                .sum();
+            
+            // If construction fund is less than 50% of estimated remaining cost, request funding
+            if building.construction_fund < estimated_cost * 0.5 {
+                let additional_needed = estimated_cost * 2.0; // 200% buffer
+                funding_requests.push((
+                    building.id,
+                    building.name.clone(),
+                    building.owner.clone(),
+                    building.construction_fund,
+                    additional_needed,
+                ));
+            }
+        }
+    }
+    
+    drop(buildings_lock); // CRITICAL: Drop read lock before getting agents lock
+    
+    if funding_requests.is_empty() {
+        return;
+    }
+    
+    // Step 2: Collect funding from owners (agents lock)
+    let mut agents = world.lifecycle.get_agents_mut();
+    let mut successful_funding: Vec<(uuid::Uuid, f64)> = Vec::new();
+    
+    for (building_id, building_name, owner, _current_fund, additional_needed) in funding_requests {
+        let mut funded_amount = 0.0;
+        
+        match owner {
+            world_sim_world::BuildingOwner::Agent(owner_id) => {
+                // Get from agent who owns the building
+                if let Some(owner_agent) = agents.get_mut(owner_id) {
+                    if owner_agent.wallet >= additional_needed {
+                        owner_agent.wallet -= additional_needed;
+                        funded_amount = additional_needed;
+                        info!("💰 {} provides {:.1} gold to replenish {} fund (prices rose)", 
+                              owner_agent.name, additional_needed, building_name);
+                    }
+                }
+            },
+            world_sim_world::BuildingOwner::Public => {
+                // Get from nobles/kings (public works)
+                let noble_ids: Vec<_> = agents.iter()
+                    .filter(|a| matches!(a.social_class, 
+                        world_sim_agents::SocialClass::King | 
+                        world_sim_agents::SocialClass::Noble))
+                    .map(|a| a.id)
+                    .collect();
+                
+                if !noble_ids.is_empty() {
+                    let per_noble = additional_needed / noble_ids.len() as f64;
+                    let mut contributed = 0;
+                    
+                    for noble_id in noble_ids {
+                        if let Some(noble) = agents.get_mut(noble_id) {
+                            if noble.wallet >= per_noble {
+                                noble.wallet -= per_noble;
+                                funded_amount += per_noble;
+                                contributed += 1;
+                            }
+                        }
+                    }
+                    
+                    if contributed > 0 {
+                        info!("💰 {} nobles provide {:.1} gold to replenish {} fund (public building)", 
+                              contributed, funded_amount, building_name);
+                    }
+                }
+            },
+            _ => {}
+        }
+        
+        if funded_amount > 0.0 {
+            successful_funding.push((building_id, funded_amount));
+        }
+    }
+    
+    drop(agents); // CRITICAL: Drop agents lock before getting buildings lock
+    
+    // Step 3: Apply funding to buildings (buildings lock)
+    if !successful_funding.is_empty() {
+        let mut buildings_write = world.buildings.write();
+        for (building_id, funded_amount) in successful_funding {
+            if let Some(building) = buildings_write.get_building_mut(building_id) {
+                let old_fund = building.construction_fund;
+                building.construction_fund += funded_amount;
+                info!("✅ {} fund replenished: {:.1} → {:.1} gold", 
+                      building.name, old_fund, building.construction_fund);
+            }
+        }
+        drop(buildings_write);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SimConfig;
+    use crate::simulation::Simulation;
+    use std::sync::Arc;
+    use world_sim_event_bus::EventBus;
+    use world_sim_world::{BuildingOwner, DynamicObject};
+
+    /// Every unit of each material, wherever it is held
+    fn materials(world: &World) -> BTreeMap<ResourceType, u32> {
+        let mut total = BTreeMap::new();
+        let mut count = |resource: ResourceType, quantity: u32| *total.entry(resource).or_insert(0) += quantity;
+        for agent in world.lifecycle.agents().iter() {
+            agent.inventory.iter().for_each(|(r, q)| count(*r, *q));
+            if let Some(c) = &agent.carrying_resources {
+                count(ResourceType::Wood, c.wood);
+                count(ResourceType::Stone, c.stone);
+                count(ResourceType::Iron, c.iron);
+            }
+        }
+        for building in world.buildings.read().get_all_buildings() {
+            building.current_resources.iter().chain(&building.storage.inventory).for_each(|(r, q)| count(*r, *q));
+        }
+        for market in world.markets.read().get_all_markets() {
+            market.inventory.values().for_each(|good| count(good.resource_type, good.quantity));
+        }
+        for object in world.objects.read().get_all() {
+            object.cargo.iter().for_each(|(r, q)| count(*r, *q));
+        }
+        total.retain(|resource, _| MATERIALS.contains(resource));
+        total
+    }
+
+    #[tokio::test]
+    async fn test_released_builders_and_finished_sites_keep_their_materials() {
+        let mut config = SimConfig::default();
+        config.terrain.generator = world_sim_world::TerrainKind::Flat;
+        let sim = Simulation::new(config, Arc::new(EventBus::new())).unwrap();
+        let world = sim.world();
+
+        // A builder carrying for a site that is then demolished, with more on their cart, and a
+        // building completed (as by the Admin API) with materials still delivered to it
+        let (site, finished) = {
+            let mut buildings = world.buildings.write();
+            let site = buildings.new_building(BuildingType::Farm, Position::new(10.0, 1.0, 10.0), "Site".into(), BuildingOwner::Public);
+            let mut finished = buildings.new_building(BuildingType::Workshop, Position::new(-10.0, 1.0, 10.0), "Finished".into(), BuildingOwner::Public);
+            finished.add_resources(ResourceType::Stone, 9);
+            finished.complete_construction();
+            (buildings.add_building(site), buildings.add_building(finished))
+        };
+        let builder = {
+            let mut agents = world.lifecycle.get_agents_mut();
+            let agent = agents.iter_mut().find(|a| a.is_alive()).unwrap();
+            agent.job = Job::Builder;
+            agent.carrying_resources = Some(BuildingResources { wood: 5, stone: 3, iron: 1, target_building_id: site });
+            agent.id
+        };
+        let cart = {
+            let mut cart = DynamicObject::new(ObjectKind::Cart, Position::new(10.0, 1.0, 10.0), BuildingOwner::Public);
+            cart.crew.push(builder);
+            cart.load_cargo(ResourceType::Wood, 12);
+            world.objects.write().add(cart)
+        };
+        let before = materials(world);
+        world.buildings.write().remove_building(site);
+
+        return_surplus(world);
+
+        assert_eq!(materials(world), before);
+        let agents = world.lifecycle.agents();
+        let agent = agents.iter().find(|a| a.id == builder).unwrap();
+        assert!(agent.carrying_resources.is_none() && matches!(agent.state, AgentState::Idle));
+        assert!(world.objects.read().get(cart).unwrap().cargo.is_empty());
+        let buildings = world.buildings.read();
+        assert!(buildings.get_building(finished).unwrap().current_resources.is_empty());
+        let warehouse = buildings.find_nearest_building(&Position::new(0.0, 1.0, 0.0), Some(BuildingType::Warehouse), true).unwrap();
+        assert_eq!(warehouse.storage.inventory.get(&ResourceType::Wood), Some(&17));
+    }
+}
@@ -0,0 +1,582 @@
+//! Prices, market trading, wages and taxes
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+use world_sim_agents::{AgentState, Job, SocialClass};
+use world_sim_core::{sim_uuid, AgentId, Position, ResourceType};
+use world_sim_event_bus::InflationSpikeEvent;
+use world_sim_societal::MarketSystem;
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// Recalculates prices and reports inflation spikes
+#[derive(Default)]
+pub struct PriceSystem {
+    inflation: InflationWatch,
+}
+
+#[async_trait]
+impl System for PriceSystem {
+    fn name(&self) -> &'static str {
+        "prices"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Currency], &[Resource::Economy])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        world.economy.recalculate_prices().await;
+
+        let (inflation_rate, total_supply) = {
+            let currency = world.currency.read();
+            (currency.inflation_rate, currency.total_supply)
+        };
+        if let Some(previous_rate) = self.inflation.observe(inflation_rate, world.config.economy.inflation_spike) {
+            info!("📈 Inflation spiked from {:.3} to {:.3}", previous_rate, inflation_rate);
+            world
+                .event_bus
+                .publish(&InflationSpikeEvent {
+                    previous_rate,
+                    inflation_rate,
+                    total_supply,
+                })
+                .await;
+        }
+        
+        Ok(())
+    }
+}
+
+/// Tracks the lowest inflation rate since the last spike
+#[derive(Debug, Default)]
+struct InflationWatch {
+    floor: Option<f64>,
+}
+
+impl InflationWatch {
+    /// Record the current rate; returns the floor it rose from if it climbed at least `threshold` above it
+    fn observe(&mut self, rate: f64, threshold: f64) -> Option<f64> {
+        let floor = self.floor.map_or(rate, |floor| floor.min(rate));
+        if rate - floor >= threshold {
+            self.floor = Some(rate);
+            Some(floor)
+        } else {
+            self.floor = Some(floor);
+            None
+        }
+    }
+}
+
+/// Market order placement, matching and inter-market balancing
+pub struct TradingSystem;
+
+/// What order placement needs of an agent at a market
+struct Trader {
+    id: AgentId,
+    position: Position,
+    wallet: f64,
+    social_class: SocialClass,
+    needs: HashMap<ResourceType, u32>,
+    inventory: HashMap<ResourceType, u32>,
+}
+
+#[async_trait]
+impl System for TradingSystem {
+    fn name(&self) -> &'static str {
+        "trading"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::Agents, Resource::Markets, Resource::Currency])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["harvesting"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        // ECONOMIC SYSTEM: Agent trading behavior at markets (non-harvesters)
+        // Only agents at a market (in Trading state) trade; harvesters sell as they harvest
+        let traders: Vec<Trader> = world.lifecycle.agents()
+            .iter()
+            .filter(|a| matches!(a.state, AgentState::Trading { .. }))
+            .filter(|a| !matches!(a.job, Job::Woodcutter | Job::Miner | Job::Farmer))
+            .map(|a| Trader {
+                id: a.id,
+                position: a.position,
+                wallet: a.wallet,
+                social_class: a.social_class,
+                needs: a.needs.clone(),
+                inventory: a.inventory.clone(),
+            })
+            .collect();
+        let mut markets_lock = world.markets.write();
+        
+        for agent in &traders {
+            // NON-HARVESTERS: Normal trading with orders
+            // Find nearest market
+            if let Some(market) = markets_lock.find_nearest_market(&agent.position, None) {
+                let dist = agent.position.distance_to(&market.position);
+                
+                // Only trade if close enough (< 6.0 units)
+                if dist < 6.0 {
+                    let market_id = market.id;
+                    
+                    // Find the market to place orders
+                    if let Some(market) = markets_lock.get_market_mut(market_id) {
+                        // BUY what they need
+                        for (resource_type, needed_amount) in &agent.needs {
+                            let current_amount = agent.inventory.get(resource_type).copied().unwrap_or(0);
+                            
+                            if current_amount < *needed_amount {
+                                let deficit = needed_amount - current_amount;
+                                
+                                // Calculate max price willing to pay (higher for essentials)
+                                let base_price = match resource_type {
+                                    world_sim_core::ResourceType::Food => 10.0,
+                                    world_sim_core::ResourceType::Wood => 5.0,
+                                    world_sim_core::ResourceType::Stone => 3.0,
+                                    world_sim_core::ResourceType::Iron => 15.0,
+                                    _ => 5.0,
+                                };
+                                
+                                // Pay more if desperate (low inventory)
+                                let desperation = if current_amount == 0 { 2.0 } else { 1.5 };
+                                let max_price = base_price * desperation;
+                                
+                                // Only buy if can afford
+                                if agent.wallet >= max_price * deficit as f64 {
+                                    use world_sim_societal::{TradeOrder, OrderType};
+                                    
+                                    market.place_buy_order(TradeOrder {
+                                        id: sim_uuid(),
+                                        agent_id: agent.id,
+                                        resource: *resource_type,
+                                        quantity: deficit,
+                                        price_per_unit: max_price,
+                                        order_type: OrderType::Buy,
+                                    });
+                                }
+                            }
+                        }
+                        
+                        // SELL excess inventory
+                        for (resource_type, quantity) in &agent.inventory {
+                            let needed = agent.needs.get(resource_type).copied().unwrap_or(0);
+                            
+                            // Keep 2x what needed, sell the rest
+                            if *quantity > needed * 2 {
+                                let excess = quantity - (needed * 2);
+                                
+                                // Calculate asking price
+                                let base_price = match resource_type {
+                                    world_sim_core::ResourceType::Food => 10.0,
+                                    world_sim_core::ResourceType::Wood => 5.0,
+                                    world_sim_core::ResourceType::Stone => 3.0,
+                                    world_sim_core::ResourceType::Iron => 15.0,
+                                    _ => 5.0,
+                                };
+                                
+                                // Merchants sell for profit
+                                let profit_margin = if matches!(agent.social_class, world_sim_agents::SocialClass::Merchant | world_sim_agents::SocialClass::Burgher) {
+                                    1.2 // 20% markup
+                                } else {
+                                    1.0 // At base price
+                                };
+                                
+                                let asking_price = base_price * profit_margin;
+                                
+                                use world_sim_societal::{TradeOrder, OrderType};
+                                
+                                market.place_sell_order(TradeOrder {
+                                    id: sim_uuid(),
+                                    agent_id: agent.id,
+                                    resource: *resource_type,
+                                    quantity: excess,
+                                    price_per_unit: asking_price,
+                                    order_type: OrderType::Sell,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        
+        // ECONOMIC SYSTEM: Match orders at all markets (settled below, after releasing the markets lock)
+        let mut trades = Vec::new();
+        for market in markets_lock.get_all_markets_mut() {
+            // Match buy and sell orders
+            trades.extend(market.match_orders());
+            
+            // Update market prices based on supply/demand
+            market.update_prices();
+        }
+        
+        // INTER-MARKET TRADE: Balance inventories across markets
+        // This prevents iron hoarding in one market
+        balance_market_inventories(&mut markets_lock);
+        drop(markets_lock);
+        
+        // ECONOMIC SYSTEM: Execute each trade between buyer and seller
+        let mut settled = Vec::new();
+        let mut agents_mut = world.lifecycle.get_agents_mut();
+        for trade in trades {
+            // A seller archived since placing the order can't be paid - skip rather than charge the buyer
+            if !agents_mut.contains(trade.seller_id) {
+                continue;
+            }
+            
+            // Find buyer and seller
+            if let Some(buyer) = agents_mut.get_mut(trade.buyer_id) {
+                let total_cost = trade.price_per_unit * trade.quantity as f64;
+                
+                // Check if buyer can still afford (might have spent money already this tick)
+                if buyer.wallet >= total_cost {
+                    // Deduct money from buyer
+                    buyer.wallet -= total_cost;
+                    
+                    // Add resources to buyer inventory
+                    *buyer.inventory.entry(trade.resource).or_insert(0) += trade.quantity;
+                    
+                    // Now find seller and complete trade
+                    if let Some(seller) = agents_mut.get_mut(trade.seller_id) {
+                        // Add money to seller
+                        seller.wallet += total_cost;
+                        
+                        // Remove resources from seller inventory
+                        if let Some(seller_amount) = seller.inventory.get_mut(&trade.resource) {
+                            *seller_amount = seller_amount.saturating_sub(trade.quantity);
+                        }
+                        
+                        settled.push(total_cost);
+                        
+                        info!("💰 Trade executed: {} {} for {:.2} ({:.2}/unit)", 
+                              trade.quantity, format!("{:?}", trade.resource), total_cost, trade.price_per_unit);
+                    }
+                }
+            }
+        }
+        drop(agents_mut);
+        
+        // Record transactions in currency system
+        let mut currency_lock = world.currency.write();
+        for total_cost in settled {
+            currency_lock.record_transaction(total_cost);
+        }
+        
+        Ok(())
+    }
+}
+
+/// Pays wages and class stipends every simulated hour
+#[derive(Default)]
+pub struct WageSystem {
+    seconds_since_payday: u64,
+}
+
+#[async_trait]
+impl System for WageSystem {
+    fn name(&self) -> &'static str {
+        "wages"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::Agents, Resource::Currency])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["trading"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        // ECONOMIC SYSTEM: Wage system (pay workers every simulated hour)
+        let elapsed = self.seconds_since_payday;
+        self.seconds_since_payday += 1;
+        
+        if elapsed >= 60 { // Pay wages every 60 seconds (1 simulated hour)
+            self.seconds_since_payday = 0;
+            
+            let mut agents_mut = world.lifecycle.get_agents_mut();
+            let mut currency_mut = world.currency.write();
+            
+            for agent in agents_mut.iter_mut() {
+                // Calculate wage based on job
+                let wage = match agent.job {
+                    Job::Farmer => 5.0,
+                    Job::Woodcutter => 6.0,
+                    Job::Miner => 7.0,
+                    Job::Builder => 8.0,
+                    Job::Unemployed => {
+                        // Social classes get stipends
+                        match agent.social_class {
+                            world_sim_agents::SocialClass::King => 50.0,
+                            world_sim_agents::SocialClass::Noble => 20.0,
+                            world_sim_agents::SocialClass::Knight => 15.0,
+                            world_sim_agents::SocialClass::Soldier => 10.0,
+                            world_sim_agents::SocialClass::Merchant => 12.0,
+                            world_sim_agents::SocialClass::Cleric => 8.0,
+                            _ => 0.0,
+                        }
+                    }
+                };
+                
+                if wage > 0.0 {
+                    agent.wallet += wage;
+                    currency_mut.mint_currency(wage); // Creates new money (inflation)
+                }
+            }
+            
+            info!("💵 Wages paid to {} workers", agents_mut.len());
+        }
+        
+        Ok(())
+    }
+}
+
+pub struct TaxSystem;
+
+#[async_trait]
+impl System for TaxSystem {
+    fn name(&self) -> &'static str {
+        "taxes"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::Agents, Resource::Kingdoms, Resource::Currency])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["labor"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        collect_taxes(world);
+        
+        Ok(())
+    }
+}
+
+/// Balance inventories across markets (merchants redistribute goods)
+fn balance_market_inventories(markets: &mut parking_lot::RwLockWriteGuard<MarketSystem>) {
+    // For each resource type, find imbalances and redistribute
+    let resource_types = vec![
+        world_sim_core::ResourceType::Wood,
+        world_sim_core::ResourceType::Stone,
+        world_sim_core::ResourceType::Iron,
+        world_sim_core::ResourceType::Food,
+    ];
+    
+    for resource_type in resource_types {
+        // Calculate average inventory across all markets
+        let all_markets = markets.get_all_markets();
+        let mut total_inventory = 0u32;
+        let mut market_count = 0;
+        
+        for market in all_markets {
+            if let Some(good) = market.inventory.get(&resource_type) {
+                total_inventory += good.quantity;
+                market_count += 1;
+            }
+        }
+        
+        if market_count == 0 {
+            continue;
+        }
+        
+        let average_inventory = total_inventory / market_count as u32;
+        
+        // Find markets that are WAY above or below average
+        let mut surplus_markets: Vec<(uuid::Uuid, u32)> = Vec::new(); // (id, surplus amount)
+        let mut deficit_markets: Vec<(uuid::Uuid, u32)> = Vec::new(); // (id, deficit amount)
+        
+        for market in markets.get_all_markets() {
+            if let Some(good) = market.inventory.get(&resource_type) {
+                if good.quantity > average_inventory + 50 {
+                    // Has surplus
+                    let surplus = good.quantity - average_inventory;
+                    surplus_markets.push((market.id, surplus));
+                } else if good.quantity + 50 < average_inventory {
+                    // Has deficit
+                    let deficit = average_inventory - good.quantity;
+                    deficit_markets.push((market.id, deficit));
+                }
+            }
+        }
+        
+        // Transfer goods from surplus to deficit markets
+        // Merchants do this automatically (simulated)
+        if !surplus_markets.is_empty() && !deficit_markets.is_empty() {
+            // Collect transfer operations to avoid double mutable borrow
+            let mut transfers: Vec<(uuid::Uuid, uuid::Uuid, u32)> = Vec::new(); // (from, to, amount)
+            let mut remaining_surplus = surplus_markets.clone();
+            
+            for (deficit_market_id, deficit_amount) in &deficit_markets {
+                if remaining_surplus.is_empty() {
+                    break;
+                }
+                
+                let (surplus_market_id, surplus_amount) = remaining_surplus[0];
+                let transfer_amount = (deficit_amount / 2).min(surplus_amount / 2).min(30); // Transfer up to 30 units
+                
+                if transfer_amount > 0 {
+                    transfers.push((surplus_market_id, *deficit_market_id, transfer_amount));
+                    
+                    // Update remaining surplus
+                    remaining_surplus[0].1 -= transfer_amount;
+                    if remaining_surplus[0].1 == 0 {
+                        remaining_surplus.remove(0);
+                    }
+                }
+            }
+            
+            // Execute transfers (now we can do sequential mutable borrows)
+            for (from_id, to_id, amount) in transfers {
+                // Get names first (for logging)
+                let from_name = markets.get_all_markets().iter()
+                    .find(|m| m.id == from_id)
+                    .map(|m| m.name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string());
+                let to_name = markets.get_all_markets().iter()
+                    .find(|m| m.id == to_id)
+                    .map(|m| m.name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string());
+                
+                // Remove from source
+                let mut transfer_successful = false;
+                if let Some(from_market) = markets.get_market_mut(from_id) {
+                    if let Some(good) = from_market.inventory.get_mut(&resource_type) {
+                        if good.quantity >= amount {
+                            good.quantity -= amount;
+                            transfer_successful = true;
+                        }
+                    }
+                }
+                
+                // Add to destination (only if removal succeeded)
+                if transfer_successful {
+                    if let Some(to_market) = markets.get_market_mut(to_id) {
+                        to_market.inventory
+                            .entry(resource_type)
+                            .and_modify(|g| g.quantity += amount)
+                            .or_insert_with(|| world_sim_societal::MarketGood {
+                                resource_type,
+                                quantity: amount,
+                                base_price: match resource_type {
+                                    world_sim_core::ResourceType::Wood => 5.0,
+                                    world_sim_core::ResourceType::Stone => 3.0,
+                                    world_sim_core::ResourceType::Iron => 15.0,
+                                    world_sim_core::ResourceType::Food => 10.0,
+                                    _ => 5.0,
+                                },
+                                current_price: match resource_type {
+                                    world_sim_core::ResourceType::Wood => 5.0,
+                                    world_sim_core::ResourceType::Stone => 3.0,
+                                    world_sim_core::ResourceType::Iron => 15.0,
+                                    world_sim_core::ResourceType::Food => 10.0,
+                                    _ => 5.0,
+                                },
+                                sellers: Vec::new(), // No specific seller for inter-market transfers
+                            });
+                        
+                        info!("🚚 Merchants transferred {} {:?} from {} to {} (balancing markets)", 
+                              amount, resource_type, from_name, to_name);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Collect taxes from population to fund public works
+fn collect_taxes(world: &World) {
+    let mut agents = world.lifecycle.get_agents_mut();
+    
+    // Tax rate: 5% of wallet for non-nobles
+    let tax_rate = world.config.economy.tax_rate;
+    let mut total_collected = 0.0;
+    let mut taxpayers = 0;
+    
+    // Collect from peasants, burghers, merchants, clerics
+    let taxable_classes = [
+        world_sim_agents::SocialClass::Peasant,
+        world_sim_agents::SocialClass::Burgher,
+        world_sim_agents::SocialClass::Merchant,
+        world_sim_agents::SocialClass::Cleric,
+        world_sim_agents::SocialClass::Soldier,
+    ];
+    
+    for agent in agents.iter_mut() {
+        if taxable_classes.contains(&agent.social_class) && agent.wallet > 50.0 {
+            let tax_amount = agent.wallet * tax_rate;
+            agent.wallet -= tax_amount;
+            total_collected += tax_amount;
+            taxpayers += 1;
+        }
+    }
+    
+    if total_collected > 0.0 {
+        // Distribute tax revenue to nobles and kings for public works
+        let mut nobles: Vec<world_sim_core::AgentId> = Vec::new();
+        
+        for agent in agents.iter() {
+            if matches!(agent.social_class, world_sim_agents::SocialClass::King | world_sim_agents::SocialClass::Noble) {
+                nobles.push(agent.id);
+            }
+        }
+        
+        if !nobles.is_empty() {
+            let per_noble = total_collected / nobles.len() as f64;
+            
+            for agent in agents.iter_mut() {
+                if nobles.contains(&agent.id) {
+                    agent.wallet += per_noble;
+                }
+            }
+            
+            info!("💰 Tax collection: {:.1} gold from {} taxpayers → {} nobles ({:.1} each)", 
+                  total_collected, taxpayers, nobles.len(), per_noble);
+        } else {
+            // No one to hand it to - the gold leaves circulation
+            world.currency.write().burn_currency(total_collected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inflation_watch_reports_rises_from_the_floor() {
+        let mut watch = InflationWatch::default();
+        assert_eq!(watch.observe(0.05, 0.02), None);
+        assert_eq!(watch.observe(0.03, 0.02), None);
+        // 0.045 is only 0.015 above the 0.03 floor
+        assert_eq!(watch.observe(0.045, 0.02), None);
+        assert_eq!(watch.observe(0.06, 0.02), Some(0.03));
+        // The spike becomes the new floor, so a plateau doesn't repeat it
+        assert_eq!(watch.observe(0.07, 0.02), None);
+        assert_eq!(watch.observe(0.08, 0.02), Some(0.06));
+    }
+}
@@ -0,0 +1,174 @@
+//! Dungeon master events, ecology and demographics
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use tracing::info;
+use uuid::Uuid;
+use world_sim_core::{AgentId, BlockType};
+use world_sim_world::{EcologyLayer, WaterSimulation};
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+pub struct DungeonMasterSystem;
+
+#[async_trait]
+impl System for DungeonMasterSystem {
+    fn name(&self) -> &'static str {
+        "dungeon_master"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::DungeonMaster])
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        world.dungeon_master.tick(tick.delta_seconds as f32).await;
+        
+        Ok(())
+    }
+}
+
+/// Seasons and weather (owns the ecology layer)
+pub struct EcologySystem {
+    ecology: EcologyLayer,
+}
+
+impl EcologySystem {
+    pub fn new(ecology: EcologyLayer) -> Self {
+        Self { ecology }
+    }
+}
+
+#[async_trait]
+impl System for EcologySystem {
+    fn name(&self) -> &'static str {
+        "ecology"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::Grid, Resource::Weather])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        self.ecology.tick(&world.event_bus, &world.grid).await;
+        *world.weather.write() = self.ecology.weather.current_weather();
+        *world.season.write() = self.ecology.seasons.current_season();
+        
+        Ok(())
+    }
+}
+
+/// Flowing water, rain and evaporation, and flood damage to buildings
+pub struct WaterSystem {
+    water: WaterSimulation,
+}
+
+impl WaterSystem {
+    pub fn new(water: WaterSimulation) -> Self {
+        Self { water }
+    }
+}
+
+#[async_trait]
+impl System for WaterSystem {
+    fn name(&self) -> &'static str {
+        "water"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Weather], &[Resource::Grid, Resource::Buildings, Resource::Currency])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        let weather = *world.weather.read();
+        self.water.step(&world.grid, weather);
+
+        // Buildings standing in water wear down; a destroyed building's unspent fund is lost with it
+        let damage = self.water.config().flood_damage;
+        let mut lost_funds = 0.0;
+        {
+            let mut buildings = world.buildings.write();
+            let flooded: Vec<Uuid> = buildings
+                .get_all_buildings()
+                .iter()
+                .filter(|b| world.grid.get_block(b.position.to_grid_coord()) == BlockType::Water)
+                .map(|b| b.id)
+                .collect();
+            for id in flooded {
+                let Some(building) = buildings.get_building_mut(id) else { continue };
+                building.damage(damage);
+                if building.is_destroyed() {
+                    if let Some(building) = buildings.remove_building(id) {
+                        info!("🌊 {} was destroyed by flooding", building.name);
+                        lost_funds += building.construction_fund;
+                    }
+                }
+            }
+        }
+        if lost_funds > 0.0 {
+            world.currency.write().burn_currency(lost_funds);
+        }
+
+        Ok(())
+    }
+}
+
+/// Births and natural deaths
+pub struct DemographicsSystem;
+
+#[async_trait]
+impl System for DemographicsSystem {
+    fn name(&self) -> &'static str {
+        "demographics"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Grid], &[Resource::Agents, Resource::Currency])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        let before: Vec<(AgentId, f64)> = world.lifecycle.agents().iter().map(|a| (a.id, a.wallet)).collect();
+        let known: HashSet<AgentId> = before.iter().map(|(id, _)| *id).collect();
+        world.lifecycle.tick(&world.grid).await;
+        
+        // Newborns arrive with a purse, and the estates of archived agents leave circulation
+        let (mut born, mut archived) = (0.0, 0.0);
+        {
+            let agents = world.lifecycle.agents();
+            for agent in agents.iter().filter(|a| !known.contains(&a.id)) {
+                born += agent.wallet;
+            }
+            for (id, wallet) in &before {
+                if !agents.contains(*id) {
+                    archived += wallet;
+                }
+            }
+        }
+        let mut currency = world.currency.write();
+        if born > 0.0 {
+            currency.mint_currency(born);
+        }
+        if archived > 0.0 {
+            currency.burn_currency(archived);
+        }
+        
+        Ok(())
+    }
+}
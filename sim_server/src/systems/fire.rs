@@ -0,0 +1,352 @@
+//! Fire: lightning, wildfires and arson, burning buildings, and bucket lines
+//!
+//! The fire system owns the [`FireSimulation`] and publishes a [`FireFront`] for agents: where
+//! the flames are, so everyone keeps clear of them, and where each member of a bucket line should
+//! stand. A line runs from the nearest water to the fire with its members close enough to pass
+//! buckets hand to hand; once every member is at their post, it puts out the fires at its head.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+use world_sim_agents::{AgentStore, SimAgent, SocialClass};
+use world_sim_core::{sim_rng, AgentId, BlockType, GridCoord, Position, SpatialIndex, Trait};
+use world_sim_event_bus::{EventBus, EventEnvelope, EventSubscriber, WildfireStartedEvent};
+use world_sim_world::{BuildingOwner, FireSimulation, GridLayer};
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// Furthest a bucket is passed between neighbours on a line
+const PASS_DISTANCE: f32 = 4.0;
+/// How far from a line's head it reaches to douse fires
+const HEAD_REACH: f32 = 4.0;
+/// Fires a fully manned line puts out per step
+const DOUSED_PER_LINE: usize = 2;
+/// Most bucket lines formed at once
+const MAX_LINES: usize = 4;
+/// Closest two lines' fires may be to each other
+const LINE_SPACING: f32 = 8.0;
+
+/// Burning blocks and bucket-line posts, as agents see them
+#[derive(Debug, Clone)]
+pub struct FireFront {
+    burning: SpatialIndex<GridCoord>,
+    /// Where each bucket-line member should stand
+    pub posts: BTreeMap<AgentId, Position>,
+}
+
+impl Default for FireFront {
+    fn default() -> Self {
+        Self {
+            burning: SpatialIndex::new(8.0),
+            posts: BTreeMap::new(),
+        }
+    }
+}
+
+impl FireFront {
+    /// Nearest burning block and its distance
+    pub fn nearest(&self, position: &Position) -> Option<(Position, f32)> {
+        self.burning.nearest(position, |_| true).map(|(coord, distance)| (coord.to_position(), distance))
+    }
+}
+
+/// A bucket line and the fire it is fighting
+#[derive(Debug, Clone)]
+struct BucketLine {
+    /// Members and their posts, from the water to the fire
+    crew: Vec<(AgentId, Position)>,
+    /// Where the last member stands, by the fire
+    head: Position,
+}
+
+impl BucketLine {
+    /// Whether every member is alive and at their post
+    fn manned(&self, agents: &AgentStore) -> bool {
+        self.crew.iter().all(|(id, post)| {
+            agents
+                .get(*id)
+                .is_some_and(|agent| agent.is_alive() && horizontal_distance(&agent.position, post) <= 1.5)
+        })
+    }
+}
+
+/// Wildfires announced on the event bus, waiting for the next fire step
+#[derive(Default)]
+struct Wildfires {
+    pending: Mutex<Vec<WildfireStartedEvent>>,
+}
+
+#[async_trait]
+impl EventSubscriber for Wildfires {
+    async fn on_event(&self, event: &EventEnvelope) {
+        if let Ok(wildfire) = serde_json::from_value::<WildfireStartedEvent>(event.payload.clone()) {
+            self.pending.lock().push(wildfire);
+        }
+    }
+}
+
+/// Ignition, spreading fire, burning buildings and bucket lines (owns the fire simulation)
+pub struct FireSystem {
+    fire: FireSimulation,
+    wildfires: Arc<Wildfires>,
+    lines: Vec<BucketLine>,
+}
+
+impl FireSystem {
+    pub fn new(fire: FireSimulation, event_bus: &EventBus) -> Self {
+        let wildfires = Arc::new(Wildfires::default());
+        event_bus.subscribe("WildfireStarted", wildfires.clone());
+        Self { fire, wildfires, lines: Vec::new() }
+    }
+
+    /// Set alight every wooden block topping a column within the wildfire's radius
+    fn start_wildfire(&mut self, grid: &GridLayer, wildfire: &WildfireStartedEvent) -> usize {
+        let center = wildfire.center.to_grid_coord();
+        let r = wildfire.radius.max(0.0) as i32;
+        let mut lit = 0;
+        for z in center.z - r..=center.z + r {
+            for x in center.x - r..=center.x + r {
+                let (dx, dz) = ((x - center.x) as f32, (z - center.z) as f32);
+                if dx * dx + dz * dz > wildfire.radius * wildfire.radius {
+                    continue;
+                }
+                let Some(y) = grid.surface_height(x, z) else { continue };
+                let top = GridCoord::new(x, y, z);
+                if grid.get_block(top).is_flammable() && self.fire.ignite(grid, top) {
+                    lit += 1;
+                }
+            }
+        }
+        // Nothing to catch - the fire still breaks out where it started
+        if lit == 0 {
+            if let Some(y) = grid.surface_height(center.x, center.z) {
+                lit += self.fire.ignite(grid, GridCoord::new(center.x, y + 1, center.z)) as usize;
+            }
+        }
+        lit
+    }
+
+    /// Rebellious peasants beside a building that isn't theirs may set it alight
+    fn arson(&mut self, world: &World) {
+        let chance = self.fire.config().arson_chance;
+        if chance <= 0.0 {
+            return;
+        }
+        let suspects: Vec<(AgentId, String, Position)> = world
+            .lifecycle
+            .agents()
+            .living()
+            .filter(|a| a.social_class == SocialClass::Peasant && a.has_trait(Trait::Rebellious))
+            .map(|a| (a.id, a.name.clone(), a.position))
+            .collect();
+        if suspects.is_empty() {
+            return;
+        }
+
+        let mut rng = sim_rng();
+        let mut targets: Vec<(String, String, GridCoord)> = Vec::new();
+        {
+            let buildings = world.buildings.read();
+            for (id, name, position) in &suspects {
+                let target = buildings
+                    .buildings_within(position, 4.0)
+                    .into_iter()
+                    .find(|b| b.owner != BuildingOwner::Agent(*id));
+                if let Some(building) = target {
+                    if rng.gen::<f32>() < chance {
+                        targets.push((name.clone(), building.name.clone(), building.position.to_grid_coord()));
+                    }
+                }
+            }
+        }
+        for (arsonist, building, coord) in targets {
+            if self.fire.ignite(&world.grid, coord) {
+                info!("🔥 {} set fire to {}", arsonist, building);
+            }
+        }
+    }
+
+    /// Buildings lose health for every burning block within their footprint
+    fn burn_buildings(&self, world: &World, burning: &[GridCoord]) {
+        let damage = self.fire.config().building_damage;
+        if burning.is_empty() || damage <= 0.0 {
+            return;
+        }
+
+        // A destroyed building's unspent fund is lost with it
+        let mut lost_funds = 0.0;
+        {
+            let mut buildings = world.buildings.write();
+            let burned: Vec<(Uuid, usize)> = buildings
+                .get_all_buildings()
+                .iter()
+                .map(|b| (b.id, burning.iter().filter(|coord| b.occupies(**coord)).count()))
+                .filter(|(_, fires)| *fires > 0)
+                .collect();
+            for (id, fires) in burned {
+                let Some(building) = buildings.get_building_mut(id) else { continue };
+                building.damage(damage * fires as f32);
+                if building.is_destroyed() {
+                    if let Some(building) = buildings.remove_building(id) {
+                        info!("🔥 {} burned down", building.name);
+                        lost_funds += building.construction_fund;
+                    }
+                }
+            }
+        }
+        if lost_funds > 0.0 {
+            world.currency.write().burn_currency(lost_funds);
+        }
+    }
+
+    /// Lines manned since the last step put out the fires at their heads
+    fn douse(&mut self, world: &World) {
+        let manned: Vec<Position> = {
+            let agents = world.lifecycle.agents();
+            self.lines.iter().filter(|line| line.manned(&agents)).map(|line| line.head).collect()
+        };
+        for head in manned {
+            let mut near: Vec<(f32, GridCoord)> = self
+                .fire
+                .burning()
+                .map(|coord| (horizontal_distance(&head, &coord.to_position()), coord))
+                .filter(|(distance, _)| *distance <= HEAD_REACH)
+                .collect();
+            near.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            for (_, coord) in near.into_iter().take(DOUSED_PER_LINE) {
+                self.fire.douse(&world.grid, coord);
+            }
+        }
+    }
+
+    /// Form lines from the nearest water to spread-out fires, with the closest free hands
+    fn plan_lines(&self, world: &World, burning: &[GridCoord]) -> Vec<BucketLine> {
+        let config = self.fire.config();
+        let mut targets: Vec<Position> = Vec::new();
+        for coord in burning {
+            let position = coord.to_position();
+            if targets.len() < MAX_LINES && targets.iter().all(|t| horizontal_distance(t, &position) > LINE_SPACING) {
+                targets.push(position);
+            }
+        }
+
+        let agents = world.lifecycle.agents();
+        let mut taken: HashSet<AgentId> = HashSet::new();
+        let mut lines = Vec::new();
+        for fire in targets {
+            let Some(water) = world.grid.nearest_block(fire.to_grid_coord(), BlockType::Water, config.alarm_radius as i32) else {
+                continue;
+            };
+            let water = water.to_position();
+
+            // The head stands a couple of blocks short of the fire, on the water's side
+            let (dx, dz) = (water.x - fire.x, water.z - fire.z);
+            let length = (dx * dx + dz * dz).sqrt();
+            let head = if length > 2.0 {
+                Position::new(fire.x + dx / length * 2.0, water.y, fire.z + dz / length * 2.0)
+            } else {
+                water
+            };
+            let span = horizontal_distance(&water, &head);
+            let needed = (span / PASS_DISTANCE).ceil() as usize + 1;
+            if needed > config.bucket_line_crew {
+                continue;
+            }
+
+            let mut crew: Vec<(&SimAgent, f32)> = agents
+                .within_radius(&fire, config.alarm_radius)
+                .into_iter()
+                .filter(|a| a.is_alive() && can_fight_fire(a) && !taken.contains(&a.id))
+                .map(|a| (a, a.position.distance_to(&fire)))
+                .collect();
+            if crew.len() < needed {
+                continue;
+            }
+            crew.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.id.cmp(&b.0.id)));
+            crew.truncate(needed);
+            // Whoever is nearest the water takes that end, so posts stay put from step to step
+            crew.sort_by(|a, b| {
+                horizontal_distance(&a.0.position, &water).total_cmp(&horizontal_distance(&b.0.position, &water)).then(a.0.id.cmp(&b.0.id))
+            });
+
+            let posts = crew
+                .iter()
+                .enumerate()
+                .map(|(i, (agent, _))| {
+                    let t = if needed > 1 { i as f32 / (needed - 1) as f32 } else { 1.0 };
+                    let post = Position::new(water.x + (head.x - water.x) * t, agent.position.y, water.z + (head.z - water.z) * t);
+                    taken.insert(agent.id);
+                    (agent.id, post)
+                })
+                .collect();
+            lines.push(BucketLine { crew: posts, head });
+        }
+        lines
+    }
+}
+
+#[async_trait]
+impl System for FireSystem {
+    fn name(&self) -> &'static str {
+        "fire"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(
+            &[Resource::Weather, Resource::Agents],
+            &[Resource::Grid, Resource::Buildings, Resource::Currency, Resource::Fires],
+        )
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["water"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        let wildfires = std::mem::take(&mut *self.wildfires.pending.lock());
+        for wildfire in wildfires {
+            let lit = self.start_wildfire(&world.grid, &wildfire);
+            info!("🔥 Wildfire near ({:.0}, {:.0}): {} blocks alight", wildfire.center.x, wildfire.center.z, lit);
+        }
+        self.arson(world);
+        self.douse(world);
+
+        let weather = *world.weather.read();
+        let season = *world.season.read();
+        let step = self.fire.step(&world.grid, weather, season);
+        if let Some(strike) = step.lightning {
+            info!("⚡ Lightning set ({}, {}, {}) alight", strike.x, strike.y, strike.z);
+        }
+
+        let burning: Vec<GridCoord> = self.fire.burning().collect();
+        self.burn_buildings(world, &burning);
+        self.lines = self.plan_lines(world, &burning);
+
+        let mut front = FireFront::default();
+        for coord in &burning {
+            front.burning.insert(*coord, coord.to_position());
+        }
+        front.posts = self.lines.iter().flat_map(|line| line.crew.iter().copied()).collect();
+        *world.fires.write() = front;
+
+        Ok(())
+    }
+}
+
+/// Commoners turn out for bucket lines; soldiers, clergy and the nobility don't
+fn can_fight_fire(agent: &SimAgent) -> bool {
+    matches!(agent.social_class, SocialClass::Peasant | SocialClass::Burgher | SocialClass::Merchant) && agent.leader_id.is_none()
+}
+
+fn horizontal_distance(a: &Position, b: &Position) -> f32 {
+    ((a.x - b.x).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
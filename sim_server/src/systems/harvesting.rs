@@ -0,0 +1,319 @@
+//! Resource regeneration, harvesting and harvester deposits at markets
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+use uuid::Uuid;
+use world_sim_agents::{AgentState, Job};
+use world_sim_core::{AgentId, Position, ResourceType};
+use world_sim_societal::{Market, MarketGood};
+use world_sim_world::{Mine, Mining, ResourceNodeType, Tool};
+
+use super::view::WorldView;
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+pub struct RegenerationSystem;
+
+#[async_trait]
+impl System for RegenerationSystem {
+    fn name(&self) -> &'static str {
+        "resource_regeneration"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::Nodes])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        world.resources.regenerate();
+        
+        Ok(())
+    }
+}
+
+/// Working harvesters gather from nearby nodes and sell full loads at the nearest market;
+/// miners dig their yields out of the ground around rock and ore nodes
+#[derive(Default)]
+pub struct HarvestingSystem {
+    mining: Mining,
+}
+
+#[async_trait]
+impl System for HarvestingSystem {
+    fn name(&self) -> &'static str {
+        "harvesting"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(
+            &[Resource::Lod],
+            &[Resource::Agents, Resource::Nodes, Resource::Markets, Resource::Currency, Resource::Grid],
+        )
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["needs", "resource_regeneration"]
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        // ECONOMIC SYSTEM: Resource harvesting stores in agent inventory
+        // Miners need room for everything a block can drop
+        let block_load = world.content.all_blocks().iter().map(|b| b.drops.iter().map(|(_, q)| q).sum()).max().unwrap_or(0);
+        let view = WorldView::capture(world, tick);
+        let gathers = view.plan(|agent| {
+            // Only harvest if agent is Working near a resource node
+            if !matches!(agent.state, AgentState::Working { .. }) {
+                return None;
+            }
+            
+            let harvest_types = harvest_types(agent.job)?;
+            
+            // Find nearest resource of the right types (miners can harvest multiple types)
+            let node = view.nearest_node_of(agent.position, harvest_types)?;
+            
+            // Only harvest if close enough (< 3.0 units)
+            if node.position.distance_to(&agent.position) >= 3.0 {
+                return None;
+            }
+            
+            // Check carrying capacity
+            match node.resource_type.ore() {
+                Some(ore) if agent.can_carry_more(block_load) => {
+                    Some(Gather::Dig { node: node.id, position: node.position, ore, tools: tools(agent.job) })
+                }
+                None if agent.can_carry_more(HARVEST_AMOUNT) => {
+                    Some(Gather::Harvest { node: node.id, resource_type: node_yield(node.resource_type) })
+                }
+                _ => Some(Gather::InventoryFull),
+            }
+        });
+        drop(view);
+        
+        // Take from the nodes and the ground in agent order, then store the yields in agent inventories
+        let seconds = tick.delta_seconds as f32;
+        let yields: Vec<(AgentId, Option<Vec<(ResourceType, u32)>>)> = gathers
+            .into_iter()
+            .filter_map(|(id, gather)| match gather {
+                Gather::Harvest { node, resource_type } => world.resources
+                    .harvest(node, HARVEST_AMOUNT)
+                    .map(|harvested| (id, Some(vec![(resource_type, harvested)]))),
+                Gather::Dig { node, position, ore, tools } => self
+                    .dig(world, node, &position, ore, tools, seconds)
+                    .map(|drops| (id, Some(drops))),
+                Gather::InventoryFull => Some((id, None)),
+            })
+            .collect();
+        world.lifecycle.apply_updates(yields, |agent, harvest| match harvest {
+            Some(drops) => {
+                for (resource_type, harvested) in drops {
+                    *agent.inventory.entry(resource_type).or_insert(0) += harvested;
+                    
+                    // Set just_harvested for visualization (with current sim time)
+                    agent.just_harvested = Some((resource_type, harvested, tick.sim_time));
+                }
+            }
+            None => {
+                // Inventory full - transition to Trading state to sell at market
+                agent.state = AgentState::Trading { with: agent.id }; // Placeholder - trading with self means "going to market"
+                info!("📦 {} inventory full ({} total items), heading to market to sell", 
+                      agent.name, agent.current_inventory_weight());
+            }
+        });
+        
+        // ECONOMIC SYSTEM: Harvester deposits to market (direct transfer)
+        let view = WorldView::capture(world, tick);
+        let deposits = view.plan(|agent| {
+            // Only harvesters at markets (in Trading state)
+            if !matches!(agent.state, AgentState::Trading { .. })
+                || !matches!(agent.job, Job::Woodcutter | Job::Miner | Job::Farmer)
+            {
+                return None;
+            }
+            
+            // Too far - still traveling (movement handles it)
+            let market = view.nearest_market(&agent.position)?;
+            if agent.position.distance_to(&market.position) >= 6.0 {
+                return None;
+            }
+            
+            Some(Deposit {
+                market: market.id,
+                name: agent.name.clone(),
+                goods: agent.inventory.iter()
+                    .filter(|(_, quantity)| **quantity > 0)
+                    .map(|(resource_type, quantity)| (*resource_type, *quantity))
+                    .collect(),
+            })
+        });
+        drop(view);
+        
+        // SELL all resources from inventory to market (CURRENCY EXCHANGE!)
+        let mut markets_lock = world.markets.write();
+        let sales: Vec<(AgentId, (u32, f64))> = deposits
+            .into_iter()
+            .filter_map(|(id, deposit)| {
+                let market = markets_lock.get_market_mut(deposit.market)?;
+                let mut deposited_total = 0;
+                let mut total_earned = 0.0;
+                
+                for (resource_type, quantity) in deposit.goods {
+                    let earnings = sell_to_market(market, resource_type, quantity);
+                    
+                    deposited_total += quantity;
+                    total_earned += earnings;
+                    
+                    let market_quantity = market.inventory.get(&resource_type).map(|g| g.quantity).unwrap_or(0);
+                    info!("💰 {} SOLD {} {:?} to {} for {:.1} gold (market now has: {})", 
+                          deposit.name, quantity, resource_type, market.name, earnings, market_quantity);
+                }
+                
+                Some((id, (deposited_total, total_earned)))
+            })
+            .collect();
+        drop(markets_lock);
+        
+        let transactions: Vec<f64> = sales.iter().map(|(_, (_, earned))| *earned).filter(|earned| *earned > 0.0).collect();
+        world.lifecycle.apply_updates(sales, |agent, (deposited_total, total_earned)| {
+            // PAY the agent for their goods
+            if total_earned > 0.0 {
+                agent.wallet += total_earned;
+                // Set just_transacted for visualization (gold earned)
+                agent.just_transacted = Some((world_sim_agents::TransactionType::Sold, total_earned, tick.sim_time));
+                info!("💵 {} earned {:.1} gold total, wallet now: {:.1}", 
+                      agent.name, total_earned, agent.wallet);
+            }
+            
+            if deposited_total > 0 {
+                // Clear agent's inventory after deposit
+                agent.inventory.clear();
+                info!("✅ {} deposited {} items total, returning to work", agent.name, deposited_total);
+            }
+            
+            // Return to Working state to harvest more
+            agent.state = AgentState::Working { task: "gathering".to_string() };
+        });
+        
+        // Record transactions in currency system (markets hold no gold, so sales create it)
+        let mut currency = world.currency.write();
+        for total_earned in transactions {
+            currency.record_transaction(total_earned);
+            currency.mint_currency(total_earned);
+        }
+        
+        Ok(())
+    }
+}
+
+impl HarvestingSystem {
+    /// A miner's stroke at the next block in the way of the ore; returns the block's drops once
+    /// it comes out (none for plain ground) and recounts what the mine has left
+    fn dig(
+        &mut self,
+        world: &World,
+        node: Uuid,
+        position: &Position,
+        ore: ResourceType,
+        tools: &[Tool],
+        seconds: f32,
+    ) -> Option<Vec<(ResourceType, u32)>> {
+        // Ground paged out by streaming reads as air: wait for it to come back in
+        let mine = Mine::around(&world.grid, position).filter(|mine| mine.is_loaded(&world.grid))?;
+        let Some(face) = mine.face(&world.grid, &world.content, ore, tools) else {
+            world.resources.set_quantity(node, 0);
+            info!("⛏️ The {:?} mine at ({:.0}, {:.0}) is worked out", ore, position.x, position.z);
+            return None;
+        };
+        let block = self.mining.dig(&world.grid, &world.content, face, tools, seconds)?;
+        world.resources.set_quantity(node, mine.reserve(&world.grid, &world.content, ore));
+        let drops = world.content.get_block(block).map(|b| b.drops.clone()).unwrap_or_default();
+        (!drops.is_empty()).then_some(drops)
+    }
+}
+
+/// Units taken per harvest (per slow tick)
+pub(super) const HARVEST_AMOUNT: u32 = 5;
+
+/// Tools a job's workers carry
+pub(super) fn tools(job: Job) -> &'static [Tool] {
+    match job {
+        Job::Miner => &[Tool::Pickaxe, Tool::Shovel],
+        Job::Woodcutter => &[Tool::Axe],
+        Job::Farmer | Job::Builder => &[Tool::Shovel],
+        _ => &[],
+    }
+}
+
+/// Node types a job harvests (miners work rock, iron and gold), or `None` for non-harvesters
+pub(super) fn harvest_types(job: Job) -> Option<&'static [ResourceNodeType]> {
+    match job {
+        Job::Woodcutter => Some(&[ResourceNodeType::Tree]),
+        Job::Miner => Some(&[ResourceNodeType::Rock, ResourceNodeType::IronDeposit, ResourceNodeType::GoldDeposit]),
+        Job::Farmer => Some(&[ResourceNodeType::Farm]),
+        _ => None,
+    }
+}
+
+/// What harvesting a node produces
+pub(super) fn node_yield(node_type: ResourceNodeType) -> ResourceType {
+    match node_type {
+        ResourceNodeType::Tree => ResourceType::Wood,
+        ResourceNodeType::Rock => ResourceType::Stone,
+        ResourceNodeType::Farm => ResourceType::Food,
+        ResourceNodeType::IronDeposit => ResourceType::Iron,
+        ResourceNodeType::GoldDeposit => ResourceType::Gold,
+    }
+}
+
+/// Price a market first lists a good at
+pub(super) fn base_price(resource_type: ResourceType) -> f64 {
+    match resource_type {
+        ResourceType::Food => 10.0,
+        ResourceType::Wood => 5.0,
+        ResourceType::Stone => 3.0,
+        ResourceType::Iron => 15.0,
+        ResourceType::Gold => 40.0,
+        _ => 5.0,
+    }
+}
+
+/// Stock harvested goods at a market and return what the seller earns
+/// (90% of the base price - the market takes a 10% fee)
+pub(super) fn sell_to_market(market: &mut Market, resource_type: ResourceType, quantity: u32) -> f64 {
+    let base_price = base_price(resource_type);
+
+    market.inventory.entry(resource_type)
+        .and_modify(|good| good.quantity += quantity)
+        .or_insert_with(|| MarketGood {
+            resource_type,
+            quantity,
+            base_price,
+            current_price: base_price,
+            sellers: vec![],
+        });
+
+    base_price * 0.9 * quantity as f64
+}
+
+/// What a working harvester does this tick
+enum Gather {
+    Harvest { node: Uuid, resource_type: ResourceType },
+    /// Work at the mine around a rock or iron node
+    Dig { node: Uuid, position: Position, ore: ResourceType, tools: &'static [Tool] },
+    /// Too full to carry more - head to market
+    InventoryFull,
+}
+
+/// A harvester's load to sell at the market it is standing at
+struct Deposit {
+    market: Uuid,
+    name: String,
+    goods: Vec<(ResourceType, u32)>,
+}
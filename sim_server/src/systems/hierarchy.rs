@@ -0,0 +1,521 @@
+//! Hierarchical AI: kings set goals, nobles order buildings, peasants build for themselves
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+use world_sim_agents::{AgentState, AgentStore, Job, SocialClass};
+use world_sim_core::{sim_rng, AgentId, Position};
+use world_sim_world::ResourceNodeType;
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// How far from the chosen spot a new building may move to find flat open ground
+const SITE_SEARCH_RADIUS: i32 = 8;
+
+pub struct KingSystem;
+
+#[async_trait]
+impl System for KingSystem {
+    fn name(&self) -> &'static str {
+        "king_ai"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Agents, Resource::Buildings], &[Resource::Kingdoms])
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        process_king_decisions(world, tick).await;
+        
+        Ok(())
+    }
+}
+
+pub struct NobleSystem;
+
+#[async_trait]
+impl System for NobleSystem {
+    fn name(&self) -> &'static str {
+        "noble_ai"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Agents, Resource::Markets, Resource::Grid], &[Resource::Kingdoms, Resource::Buildings, Resource::Currency])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["king_ai"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        process_noble_orders(world).await;
+        
+        Ok(())
+    }
+}
+
+pub struct PeasantBuildingSystem;
+
+#[async_trait]
+impl System for PeasantBuildingSystem {
+    fn name(&self) -> &'static str {
+        "peasant_building"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Markets, Resource::Grid], &[Resource::Agents, Resource::Buildings, Resource::Currency])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["noble_ai"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        process_peasant_building(world).await;
+        
+        Ok(())
+    }
+}
+
+/// HIERARCHICAL AI: King decision-making (sets kingdom goals)
+async fn process_king_decisions(world: &World, tick: &Tick) {
+    let (agent_count, at_war, kings) = {
+        let agents = world.lifecycle.agents();
+        let at_war = agents.iter().any(|a| matches!(a.state, AgentState::Fighting { .. }));
+        (agents.len(), at_war, members(&agents, SocialClass::King))
+    };
+    let resource_nodes = world.resources.get_nodes();
+    
+    // Calculate economic metrics for decision-making
+    let total_food: u32 = resource_nodes.iter()
+        .filter(|r| matches!(r.resource_type, ResourceNodeType::Farm))
+        .map(|r| r.quantity).sum();
+    
+    let total_materials: u32 = resource_nodes.iter()
+        .filter(|r| matches!(r.resource_type, ResourceNodeType::Tree | ResourceNodeType::Rock | ResourceNodeType::IronDeposit))
+        .map(|r| r.quantity).sum();
+    
+    let food_per_capita = if agent_count > 0 { total_food as f32 / agent_count as f32 } else { 100.0 };
+    let materials_per_capita = if agent_count > 0 { total_materials as f32 / agent_count as f32 } else { 100.0 };
+    
+    // Check for threats
+    let factions = world.politics.get_all_factions();
+    let has_enemies = factions.len() >= 2;
+    
+    // Find all kings and make decisions
+    let mut kingdoms_lock = world.kingdoms.write();
+    
+    for agent in &kings {
+        // Ensure king has a kingdom
+        if kingdoms_lock.get_kingdom_by_king(agent.id).is_none() {
+            kingdoms_lock.create_kingdom(agent.id, agent.position);
+            info!("👑 Kingdom established by {}", agent.name);
+        }
+        
+        if let Some(kingdom) = kingdoms_lock.get_kingdom_by_king_mut(agent.id) {
+            // King AI: Analyze situation and set goal
+            let new_goal = if at_war || has_enemies {
+                use world_sim_societal::KingdomGoal;
+                (KingdomGoal::DefendTerritory, 1.0)
+            } else if food_per_capita < 15.0 {
+                use world_sim_societal::KingdomGoal;
+                (KingdomGoal::GrowPopulation, 0.9)
+            } else if materials_per_capita < 25.0 {
+                use world_sim_societal::KingdomGoal;
+                (KingdomGoal::ExpandResources, 0.8)
+            } else if agent_count > 50 {
+                use world_sim_societal::KingdomGoal;
+                (KingdomGoal::ImproveInfrastructure, 0.6)
+            } else {
+                use world_sim_societal::KingdomGoal;
+                (KingdomGoal::Consolidate, 0.3)
+            };
+            
+            if kingdom.current_goal != new_goal.0 {
+                kingdom.set_goal(new_goal.0, new_goal.1, tick.sim_time);
+                info!("👑 King {} sets new goal: {:?} (priority: {:.1})", 
+                      agent.name, new_goal.0, new_goal.1);
+            }
+        }
+    }
+    drop(kingdoms_lock); // Explicitly drop kingdoms write lock
+}
+
+/// HIERARCHICAL AI: Noble order execution (creates building orders)
+async fn process_noble_orders(world: &World) {
+    // CONSTRUCTION LIMIT: Check how many buildings are currently under construction
+    let buildings = world.buildings.read();
+    let under_construction = buildings.get_all_buildings().iter()
+        .filter(|b| b.construction_progress < 1.0)
+        .count();
+    
+    // Limit: Maximum 8 buildings under construction at once
+    const MAX_CONCURRENT_CONSTRUCTION: usize = 8;
+    
+    if under_construction >= MAX_CONCURRENT_CONSTRUCTION {
+        info!("🚧 Construction limit reached: {}/{} buildings under construction - no new orders", 
+              under_construction, MAX_CONCURRENT_CONSTRUCTION);
+        return;
+    }
+    
+    let available_slots = MAX_CONCURRENT_CONSTRUCTION - under_construction;
+    info!("🚧 Construction capacity: {}/{} buildings under construction, {} slots available", 
+          under_construction, MAX_CONCURRENT_CONSTRUCTION, available_slots);
+    drop(buildings);
+    
+    let (nobles, kings) = {
+        let agents = world.lifecycle.agents();
+        (members(&agents, SocialClass::Noble), members(&agents, SocialClass::King))
+    };
+    let mut kingdoms_write = world.kingdoms.write();
+    let mut buildings_created = 0;
+    
+    for agent in &nobles {
+        if buildings_created >= available_slots {
+            break; // Stop if we've used all available slots
+        }
+        
+        // Find king's kingdom to get current goal
+        let king_goal = kings.iter()
+            .filter_map(|king| kingdoms_write.get_kingdom_by_king(king.id))
+            .next()
+            .map(|k| k.current_goal);
+        
+        if let Some(goal) = king_goal {
+            // Noble AI: Execute king's goal by creating building orders
+            use world_sim_societal::{KingdomGoal, NobleOrder};
+            use world_sim_world::BuildingType;
+            use rand::Rng;
+            let mut rng = sim_rng();
+            
+            // Only create new orders occasionally (5% chance per minute, reduced from 10%)
+            if rng.gen::<f32>() < 0.05 {
+                let (building_type, priority) = match goal {
+                    KingdomGoal::DefendTerritory => {
+                        if rng.gen::<bool>() {
+                            (BuildingType::Barracks, 0.9)
+                        } else {
+                            (BuildingType::Walls, 1.0)
+                        }
+                    },
+                    KingdomGoal::ExpandResources => {
+                        if rng.gen::<bool>() {
+                            (BuildingType::Farm, 0.8)
+                        } else {
+                            (BuildingType::Mine, 0.9)
+                        }
+                    },
+                    KingdomGoal::PrepareForWar => {
+                        (BuildingType::Barracks, 1.0)
+                    },
+                    KingdomGoal::GrowPopulation => {
+                        (BuildingType::Farm, 0.9)
+                    },
+                    KingdomGoal::ImproveInfrastructure => {
+                        let choice = rng.gen_range(0..3);
+                        match choice {
+                            0 => (BuildingType::Workshop, 0.7),
+                            1 => (BuildingType::Tavern, 0.5),
+                            _ => (BuildingType::Market, 0.8),
+                        }
+                    },
+                    KingdomGoal::Consolidate => {
+                        // No new orders during consolidation
+                        continue;
+                    }
+                };
+                
+                // Choose location near noble's position, on flat open ground
+                let offset_x = rng.gen_range(-20.0..20.0);
+                let offset_z = rng.gen_range(-20.0..20.0);
+                let location = Position::new(
+                    agent.position.x + offset_x,
+                    1.0,
+                    agent.position.z + offset_z
+                );
+                let Some(location) = world.buildings.read().find_site(&world.grid, building_type, &location, SITE_SEARCH_RADIUS) else {
+                    continue; // Nowhere to build it here
+                };
+                
+                let order = NobleOrder::new(agent.id, building_type, location, priority);
+                kingdoms_write.add_noble_order(order.clone());
+                
+                let requirements = world.buildings.read().required_resources(building_type);
+                let req_summary = format!("{}W, {}S, {}I", 
+                    requirements.get(&world_sim_core::ResourceType::Wood).unwrap_or(&0),
+                    requirements.get(&world_sim_core::ResourceType::Stone).unwrap_or(&0),
+                    requirements.get(&world_sim_core::ResourceType::Iron).unwrap_or(&0));
+                info!("🏛️ Noble {} orders {:?} at ({:.1}, {:.1}) [Needs: {}]", 
+                      agent.name, building_type, location.x, location.z, req_summary);
+                
+                // Create the actual building
+                let mut buildings = world.buildings.write();
+                let mut new_building = buildings.new_building(
+                    building_type,
+                    location,
+                    format!("{:?} (Noble Order)", building_type),
+                    world_sim_world::BuildingOwner::Public,
+                );
+                
+                // FUNDING: Noble allocates construction funds from their own wallet
+                // Calculate total cost using REAL CURRENT MARKET PRICES
+                let total_cost = new_building.required_resources.iter()
+                    .map(|(resource_type, qty)| {
+                        let market_price = world.market_price(*resource_type);
+                        market_price * (*qty as f64)
+                    })
+                    .sum::<f64>();
+                
+                // Noble funds the building (allocates 300% for price volatility + market inefficiency)
+                let allocated_funds = total_cost * 3.0;
+                new_building.construction_fund = allocated_funds;
+                
+                info!("💰 Noble {} allocated {:.1} gold for {:?} construction (estimated cost: {:.1})", 
+                      agent.name, allocated_funds, building_type, total_cost);
+                
+                let building_id = new_building.id;
+                buildings.add_building(new_building);
+                // The noble's wallet is not charged, so the fund is new gold
+                world.currency.write().mint_currency(allocated_funds);
+                
+                // Update order with building ID
+                if let Some(order_mut) = kingdoms_write.get_order_mut(order.id) {
+                    order_mut.building_id = Some(building_id);
+                    order_mut.status = world_sim_societal::OrderStatus::InProgress;
+                }
+                
+                buildings_created += 1; // Track how many buildings we've created
+                drop(buildings); // CRITICAL: Drop buildings write lock immediately
+            }
+        }
+    }
+    drop(kingdoms_write); // Explicitly drop kingdoms write lock
+}
+
+/// HIERARCHICAL AI: Peasant self-building (personal needs)
+async fn process_peasant_building(world: &World) {
+    use rand::Rng;
+    let mut rng = sim_rng();
+    
+    // CONSTRUCTION LIMIT: Check how many buildings are currently under construction
+    let buildings = world.buildings.read();
+    let under_construction = buildings.get_all_buildings().iter()
+        .filter(|b| b.construction_progress < 1.0)
+        .count();
+    
+    // Use same limit as nobles
+    const MAX_CONCURRENT_CONSTRUCTION: usize = 8;
+    
+    if under_construction >= MAX_CONCURRENT_CONSTRUCTION {
+        info!("🚧 Construction limit reached - peasants cannot start new buildings");
+        return;
+    }
+    
+    let available_slots = MAX_CONCURRENT_CONSTRUCTION - under_construction;
+    drop(buildings);
+    
+    let peasants = members(&world.lifecycle.agents(), SocialClass::Peasant);
+    let mut buildings_created = 0;
+    
+    for agent in &peasants {
+        if buildings_created >= available_slots {
+            break; // Stop if we've used all available slots
+        }
+        
+        // Peasants occasionally decide to build for themselves (10% chance per minute for more construction)
+        if rng.gen::<f32>() < 0.10 {
+            // Check if they have a home nearby
+            let buildings = world.buildings.read();
+            let has_nearby_house = buildings.buildings_within(&agent.position, 30.0).iter()
+                .any(|b| matches!(b.building_type, world_sim_world::BuildingType::PeasantHouse));
+            
+            drop(buildings);
+            
+            if !has_nearby_house {
+                // Calculate cost using REAL CURRENT MARKET PRICES
+                let wood_price = world.market_price(world_sim_core::ResourceType::Wood);
+                let stone_price = world.market_price(world_sim_core::ResourceType::Stone);
+                
+                let house_wood_cost = 30.0 * wood_price;  // 30 wood @ market price
+                let house_stone_cost = 10.0 * stone_price; // 10 stone @ market price
+                let total_house_cost = (house_wood_cost + house_stone_cost) * 3.0; // 300% buffer for volatility
+                
+                // Check if peasant can afford it
+                if agent.wallet >= total_house_cost {
+                    // Build a house for themselves
+                    use world_sim_world::BuildingType;
+                    
+                    let offset_x = rng.gen_range(-10.0..10.0);
+                    let offset_z = rng.gen_range(-10.0..10.0);
+                    let location = Position::new(
+                        agent.position.x + offset_x,
+                        1.0,
+                        agent.position.z + offset_z
+                    );
+                    
+                    let mut buildings = world.buildings.write();
+                    let Some(location) = buildings.find_site(&world.grid, BuildingType::PeasantHouse, &location, SITE_SEARCH_RADIUS) else {
+                        continue; // No flat open ground nearby
+                    };
+                    
+                    let mut house = buildings.new_building(
+                        BuildingType::PeasantHouse,
+                        location,
+                        format!("{}'s House", agent.name),
+                        world_sim_world::BuildingOwner::Agent(agent.id),
+                    );
+                    
+                    // FUNDING: Peasant allocates their own money for construction
+                    house.construction_fund = total_house_cost;
+                    // NOTE: We DON'T deduct from wallet yet - it's deducted when builders buy materials
+                    
+                    buildings.add_building(house);
+                    drop(buildings); // CRITICAL: Drop buildings write lock immediately
+                    world.currency.write().mint_currency(total_house_cost); // Fund is new gold until the wallet is charged
+                    
+                    buildings_created += 1; // Track buildings created
+                    
+                    info!("🏠 Peasant {} starts building a house at ({:.1}, {:.1}) [Needs: 30 wood, 10 stone] [Fund: {:.1} gold]", 
+                          agent.name, location.x, location.z, total_house_cost);
+                } else {
+                    // Removed spam log - peasants silently save money
+                }
+            } else if agent.job == Job::Farmer {
+                // Farmers build sheds
+                let has_nearby_shed = {
+                    let buildings = world.buildings.read();
+                    buildings.buildings_within(&agent.position, 20.0).iter()
+                        .any(|b| matches!(b.building_type, world_sim_world::BuildingType::FarmingShed))
+                };
+                
+                if !has_nearby_shed {
+                    // Calculate cost using REAL CURRENT MARKET PRICES
+                    let wood_price = world.market_price(world_sim_core::ResourceType::Wood);
+                    let stone_price = world.market_price(world_sim_core::ResourceType::Stone);
+                    
+                    let shed_wood_cost = 20.0 * wood_price;  // 20 wood @ market price
+                    let shed_stone_cost = 5.0 * stone_price;  // 5 stone @ market price
+                    let total_shed_cost = (shed_wood_cost + shed_stone_cost) * 3.0; // 300% buffer
+                    
+                    // Check if farmer can afford it
+                    if agent.wallet >= total_shed_cost {
+                        use world_sim_world::BuildingType;
+                        
+                        let offset_x = rng.gen_range(-8.0..8.0);
+                        let offset_z = rng.gen_range(-8.0..8.0);
+                        let location = Position::new(
+                            agent.position.x + offset_x,
+                            1.0,
+                            agent.position.z + offset_z
+                        );
+                        
+                        let mut buildings = world.buildings.write();
+                        let Some(location) = buildings.find_site(&world.grid, BuildingType::FarmingShed, &location, SITE_SEARCH_RADIUS) else {
+                            continue; // No flat open ground nearby
+                        };
+                        
+                        let mut shed = buildings.new_building(
+                            BuildingType::FarmingShed,
+                            location,
+                            format!("{}'s Shed", agent.name),
+                            world_sim_world::BuildingOwner::Agent(agent.id),
+                        );
+                        
+                        // FUNDING: Farmer allocates their own money for construction
+                        shed.construction_fund = total_shed_cost;
+                        
+                        buildings.add_building(shed);
+                        drop(buildings); // CRITICAL: Drop buildings write lock immediately
+                        world.currency.write().mint_currency(total_shed_cost); // Fund is new gold (wallet not charged)
+                        
+                        buildings_created += 1; // Track buildings created
+                        
+                        info!("🌾 Farmer {} starts building a shed at ({:.1}, {:.1}) [Needs: 20 wood, 5 stone] [Fund: {:.1} gold]", 
+                              agent.name, location.x, location.z, total_shed_cost);
+                    } else {
+                        // Removed spam log - farmers silently save money
+                    }
+                }
+            }
+        }
+    }
+    
+    // DIAGNOSTICS: Why no buildings?
+    if buildings_created == 0 {
+        let peasant_count = peasants.len();
+        let rich_peasants = peasants.iter().filter(|a| a.wallet >= 400.0).count();
+        info!("🏠 Peasant building check: {} peasants, {} can afford houses (>400g), {} buildings created this cycle", 
+              peasant_count, rich_peasants, buildings_created);
+    }
+}
+
+/// What the hierarchy passes need of an agent
+struct Member {
+    id: AgentId,
+    name: String,
+    position: Position,
+    wallet: f64,
+    job: Job,
+}
+
+/// Agents of a social class, in store order, copied out of the read guard so no other world
+/// lock is taken while it is held
+fn members(agents: &AgentStore, class: SocialClass) -> Vec<Member> {
+    agents
+        .iter()
+        .filter(|a| a.social_class == class)
+        .map(|a| Member { id: a.id, name: a.name.clone(), position: a.position, wallet: a.wallet, job: a.job })
+        .collect()
+}
+
+/// Check if an agent can order construction of a building type (building permissions)
+#[allow(dead_code)]
+fn can_order_building(social_class: world_sim_agents::SocialClass, building_type: world_sim_world::BuildingType) -> bool {
+    use world_sim_agents::SocialClass;
+    use world_sim_world::BuildingType;
+    
+    match (social_class, building_type) {
+        // Kings can build anything
+        (SocialClass::King, _) => true,
+        
+        // Nobles can build military and infrastructure
+        (SocialClass::Noble, BuildingType::Barracks) => true,
+        (SocialClass::Noble, BuildingType::Walls) => true,
+        (SocialClass::Noble, BuildingType::Market) => true,
+        (SocialClass::Noble, BuildingType::Workshop) => true,
+        (SocialClass::Noble, BuildingType::NobleEstate) => true, // For themselves
+        (SocialClass::Noble, BuildingType::Farm) => true,
+        (SocialClass::Noble, BuildingType::Mine) => true,
+        
+        // Merchants can build commercial buildings
+        (SocialClass::Merchant, BuildingType::Workshop) => true,
+        (SocialClass::Merchant, BuildingType::Market) => true,
+        (SocialClass::Merchant, BuildingType::Tavern) => true,
+        
+        // Burghers (craftsmen) can build workshops
+        (SocialClass::Burgher, BuildingType::Workshop) => true,
+        (SocialClass::Burgher, BuildingType::Tavern) => true,
+        
+        // Clerics can build churches
+        (SocialClass::Cleric, BuildingType::Church) => true,
+        
+        // Peasants can only build basic personal structures
+        (SocialClass::Peasant, BuildingType::PeasantHouse) => true,
+        (SocialClass::Peasant, BuildingType::FarmingShed) => true,
+        
+        // Everything else is not permitted
+        _ => false,
+    }
+}
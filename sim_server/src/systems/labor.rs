@@ -0,0 +1,228 @@
+//! Labor rebalancing toward market demand
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+use world_sim_agents::Job;
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+pub struct LaborSystem;
+
+#[async_trait]
+impl System for LaborSystem {
+    fn name(&self) -> &'static str {
+        "labor"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::VerySlow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Markets], &[Resource::Agents])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        info!("🕐 About to call rebalance_labor...");
+        rebalance_labor(world);
+        info!("🕐 rebalance_labor completed");
+        
+        Ok(())
+    }
+}
+
+/// Forces a rebalance every 10 slow ticks if harvesters are critically low
+#[derive(Default)]
+pub struct LaborWatchdogSystem {
+    ticks: u32,
+}
+
+#[async_trait]
+impl System for LaborWatchdogSystem {
+    fn name(&self) -> &'static str {
+        "labor_watchdog"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Markets], &[Resource::Agents])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        self.ticks += 1;
+        if self.ticks.is_multiple_of(10) { // Every 10 seconds
+            let agents = world.lifecycle.agents();
+            let woodcutters = agents.iter().filter(|a| matches!(a.job, Job::Woodcutter)).count();
+            let miners = agents.iter().filter(|a| matches!(a.job, Job::Miner)).count();
+            let farmers = agents.iter().filter(|a| matches!(a.job, Job::Farmer)).count();
+            info!("🚨 EMERGENCY JOB CHECK: Woodcutters:{} Miners:{} Farmers:{} Total:{}", 
+                  woodcutters, miners, farmers, woodcutters + miners + farmers);
+            
+            // If harvesters are critically low, rebalance immediately
+            if woodcutters + miners + farmers < 20 {
+                info!("🚨 CRITICAL: Only {} harvesters! Forcing immediate rebalance!", woodcutters + miners + farmers);
+                drop(agents);
+                rebalance_labor(world);
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// Rebalance labor force to ensure minimum harvesters
+pub fn rebalance_labor(world: &World) {
+    // PRICE-BASED LABOR ALLOCATION: Calculate demand based on market prices & inventory
+    let markets = world.markets.read();
+    let all_markets = markets.get_all_markets();
+    
+    // Aggregate market data
+    let mut total_wood_inventory = 0u32;
+    let mut total_stone_inventory = 0u32;
+    let mut total_iron_inventory = 0u32;
+    let mut total_food_inventory = 0u32;
+    let mut wood_price = 5.0;
+    let mut stone_price = 3.0;
+    let mut iron_price = 15.0;
+    let mut food_price = 10.0;
+    
+    for market in all_markets {
+        if let Some(wood_good) = market.inventory.get(&world_sim_core::ResourceType::Wood) {
+            total_wood_inventory += wood_good.quantity;
+            wood_price = wood_good.current_price; // Use last market's price
+        }
+        if let Some(stone_good) = market.inventory.get(&world_sim_core::ResourceType::Stone) {
+            total_stone_inventory += stone_good.quantity;
+            stone_price = stone_good.current_price;
+        }
+        if let Some(iron_good) = market.inventory.get(&world_sim_core::ResourceType::Iron) {
+            total_iron_inventory += iron_good.quantity;
+            iron_price = iron_good.current_price;
+        }
+        if let Some(food_good) = market.inventory.get(&world_sim_core::ResourceType::Food) {
+            total_food_inventory += food_good.quantity;
+            food_price = food_good.current_price;
+        }
+    }
+    
+    drop(markets); // Release markets before taking the agents lock
+    
+    let mut agents = world.lifecycle.get_agents_mut();
+    let total = agents.len();
+    
+    if total == 0 {
+        info!("⚖️ Labor rebalance: No agents to rebalance");
+        return;
+    }
+    
+    // Count current job distribution
+    let woodcutters = agents.iter().filter(|a| matches!(a.job, Job::Woodcutter)).count();
+    let miners = agents.iter().filter(|a| matches!(a.job, Job::Miner)).count();
+    let farmers = agents.iter().filter(|a| matches!(a.job, Job::Farmer)).count();
+    
+    let harvesters = woodcutters + miners + farmers;
+    
+    // Calculate "demand scores" (higher = more valuable = more workers needed)
+    // Demand score = price / (inventory + 10)  [scarce + expensive = high score]
+    let wood_demand = wood_price / (total_wood_inventory as f64 + 10.0);
+    let stone_demand = stone_price / (total_stone_inventory as f64 + 10.0);
+    let iron_demand = iron_price / (total_iron_inventory as f64 + 10.0);
+    let food_demand = food_price / (total_food_inventory as f64 + 10.0);
+    
+    let total_demand = wood_demand + stone_demand + iron_demand + food_demand;
+    
+    // Calculate target worker distribution based on demand scores
+    let target_harvesters = (total as f32 * 0.40) as usize;
+    
+    // Miners harvest BOTH stone and iron, so their target is based on combined demand
+    let target_woodcutters = ((wood_demand / total_demand) * target_harvesters as f64).max(1.0) as usize;
+    let target_miners = (((stone_demand + iron_demand) / total_demand) * target_harvesters as f64).max(1.0) as usize;
+    let target_farmers = ((food_demand / total_demand) * target_harvesters as f64).max(1.0) as usize;
+    
+    // Adjust if targets exceed available slots
+    let target_sum = target_woodcutters + target_miners + target_farmers;
+    let (final_woodcutters, final_miners, final_farmers) = if target_sum > target_harvesters {
+        // Scale down proportionally
+        let scale = target_harvesters as f32 / target_sum as f32;
+        (
+            (target_woodcutters as f32 * scale).max(1.0) as usize,
+            (target_miners as f32 * scale).max(1.0) as usize,
+            (target_farmers as f32 * scale).max(1.0) as usize,
+        )
+    } else {
+        (target_woodcutters, target_miners, target_farmers)
+    };
+    
+    // ALWAYS log for diagnostics
+    info!("💹 Market demand: Wood:{:.3} Stone:{:.3} Iron:{:.3} Food:{:.3}", 
+          wood_demand, stone_demand, iron_demand, food_demand);
+    info!("⚖️ Target jobs: W:{} M:{} F:{} (current: W:{} M:{} F:{})", 
+          final_woodcutters, final_miners, final_farmers, woodcutters, miners, farmers);
+    info!("📦 Market inventory: 🌲{} 🪨{} ⚙️{} 🌾{}", 
+          total_wood_inventory, total_stone_inventory, total_iron_inventory, total_food_inventory);
+    
+    if harvesters < target_harvesters {
+        // Need more harvesters
+        let needed = target_harvesters - harvesters;
+        let mut converted = 0;
+        let mut current_wood = woodcutters;
+        let mut current_miners = miners;
+        let mut current_farmers = farmers;
+        
+        for agent in agents.iter_mut() {
+            if converted >= needed {
+                break;
+            }
+            
+            // Only convert Builders and Unemployed (not upper classes)
+            if matches!(agent.job, Job::Builder | Job::Unemployed) {
+                if matches!(agent.social_class, 
+                           world_sim_agents::SocialClass::King | 
+                           world_sim_agents::SocialClass::Noble | 
+                           world_sim_agents::SocialClass::Knight |
+                           world_sim_agents::SocialClass::Soldier) {
+                    continue;
+                }
+                
+                // Assign based on market demand targets
+                let new_job = if current_wood < final_woodcutters {
+                    current_wood += 1;
+                    Job::Woodcutter
+                } else if current_miners < final_miners {
+                    current_miners += 1;
+                    Job::Miner
+                } else if current_farmers < final_farmers {
+                    current_farmers += 1;
+                    Job::Farmer
+                } else {
+                    // If all targets met, assign to highest demand
+                    if wood_demand >= stone_demand + iron_demand && wood_demand >= food_demand {
+                        current_wood += 1;
+                        Job::Woodcutter
+                    } else if stone_demand + iron_demand >= food_demand {
+                        current_miners += 1;
+                        Job::Miner
+                    } else {
+                        current_farmers += 1;
+                        Job::Farmer
+                    }
+                };
+                
+                info!("🔄 Converting {} from {:?} to {:?} (market demand)", agent.name, agent.job, new_job);
+                agent.job = new_job;
+                converted += 1;
+            }
+        }
+        
+        if converted > 0 {
+            info!("✅ Converted {} agents based on market demand (W:+{} M:+{} F:+{})", 
+                  converted, current_wood - woodcutters, current_miners - miners, current_farmers - farmers);
+        } else {
+            info!("⚠️ Could not convert any agents! All eligible agents may already be harvesters or protected");
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! Agent level of detail for large populations
+//!
+//! Commoners near a focus point (configured points, observers, markets) are simulated in
+//! full. Further out they are planned only every few ticks, and beyond the reduced radius
+//! they are aggregated: they stop moving and deciding, and [`AggregateSystem`] settles their
+//! harvesting and meals against nodes and markets statistically. Births and deaths are
+//! already statistical (`LifecycleLayer::tick`) and apply to every level alike.
+//!
+//! Entering aggregation sells whatever the agent carries; coming back into focus restarts
+//! it from a clean state at the position it was aggregated at, so wallets, node stock and
+//! market stock stay consistent across the switch.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+use world_sim_agents::{AgentState, Job, SimAgent, SocialClass};
+use world_sim_core::{sim_rng, AgentId, Position, ResourceType};
+
+use super::harvesting::{harvest_types, node_yield, sell_to_market, HARVEST_AMOUNT};
+use super::{Access, Resource, System, Tick, TickRate, World};
+use crate::config::LodConfig;
+
+/// How closely an agent is simulated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Lod {
+    Full,
+    /// Planned every `lod.reduced_every` ticks
+    Reduced,
+    /// Not planned at all; settled by [`AggregateSystem`]
+    Aggregate,
+}
+
+/// Current level of every agent, and where the observers are
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LodTable {
+    /// Agents missing here (e.g. newborns) are at full detail
+    levels: HashMap<AgentId, Lod>,
+    observers: Vec<Position>,
+}
+
+impl LodTable {
+    pub fn level(&self, id: AgentId) -> Lod {
+        self.levels.get(&id).copied().unwrap_or(Lod::Full)
+    }
+
+    pub fn set_observers(&mut self, observers: Vec<Position>) {
+        self.observers = observers;
+    }
+
+    pub fn observers(&self) -> &[Position] {
+        &self.observers
+    }
+
+    /// Agents that sit out the agent phases of tick `number` (aggregated ones, and reduced
+    /// ones whose turn it isn't - turns are staggered by id so the load stays even)
+    pub fn skipped(&self, number: u64, reduced_every: u32) -> HashSet<AgentId> {
+        self.levels
+            .iter()
+            .filter(|(id, level)| match level {
+                Lod::Full => false,
+                Lod::Reduced => (id.0.as_u64_pair().1.wrapping_add(number)) % reduced_every as u64 != 0,
+                Lod::Aggregate => true,
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Number of agents at each level (full, reduced, aggregate)
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let count = |lod| self.levels.values().filter(|level| **level == lod).count();
+        (count(Lod::Full), count(Lod::Reduced), count(Lod::Aggregate))
+    }
+}
+
+/// Whether an agent may leave full detail - anyone a player would follow (rulers, the armed,
+/// followers, faction members, builders on a site) is always simulated in full
+fn can_reduce(agent: &SimAgent) -> bool {
+    matches!(
+        agent.social_class,
+        SocialClass::Peasant | SocialClass::Burgher | SocialClass::Merchant | SocialClass::Cleric
+    ) && agent.job != Job::Builder
+        && agent.leader_id.is_none()
+        && agent.personality.beliefs.faction_loyalty.is_none()
+}
+
+/// Level for an agent given the focus points
+fn level_for(agent: &SimAgent, foci: &[Position], config: &LodConfig) -> Lod {
+    if !can_reduce(agent) {
+        return Lod::Full;
+    }
+    let distance = foci
+        .iter()
+        .map(|focus| {
+            let dx = focus.x - agent.position.x;
+            let dz = focus.z - agent.position.z;
+            (dx * dx + dz * dz).sqrt()
+        })
+        .fold(f32::INFINITY, f32::min);
+    if distance <= config.full_radius {
+        Lod::Full
+    } else if distance <= config.reduced_radius {
+        Lod::Reduced
+    } else {
+        Lod::Aggregate
+    }
+}
+
+/// Reassigns levels by distance to the focus points and switches agents in and out of aggregation
+pub struct LodSystem;
+
+#[async_trait]
+impl System for LodSystem {
+    fn name(&self) -> &'static str {
+        "lod"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::Agents, Resource::Markets, Resource::Currency, Resource::Lod])
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        let config = &world.config.lod;
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let mut foci: Vec<Position> = config.focus.iter().map(|[x, z]| Position::new(*x, 0.0, *z)).collect();
+        foci.extend(world.lod.read().observers.iter().copied());
+        if config.focus_markets {
+            foci.extend(world.markets.read().get_all_markets().iter().map(|m| m.position));
+        }
+
+        // Assign levels, noting agents that enter aggregation (with their load) or leave it
+        let mut lod = world.lod.write();
+        let mut levels = HashMap::new();
+        let mut switches: Vec<(AgentId, Option<(Position, Vec<(ResourceType, u32)>)>)> = Vec::new();
+        for agent in world.lifecycle.agents().living() {
+            let level = level_for(agent, &foci, config);
+            match (lod.level(agent.id) == Lod::Aggregate, level == Lod::Aggregate) {
+                (false, true) => {
+                    let load = agent.inventory.iter().filter(|(_, q)| **q > 0).map(|(r, q)| (*r, *q)).collect();
+                    switches.push((agent.id, Some((agent.position, load))));
+                }
+                (true, false) => switches.push((agent.id, None)),
+                _ => {}
+            }
+            levels.insert(agent.id, level);
+        }
+        if levels != lod.levels {
+            lod.levels = levels;
+            let (full, reduced, aggregate) = lod.counts();
+            info!("🔭 LOD: {} full, {} reduced, {} aggregated", full, reduced, aggregate);
+        }
+        drop(lod);
+
+        // Cash in loads at the nearest market - aggregated agents don't walk there
+        let mut markets = world.markets.write();
+        let switches: Vec<(AgentId, f64)> = switches
+            .into_iter()
+            .map(|(id, entering)| {
+                let earned = entering
+                    .and_then(|(position, load)| {
+                        let market = markets.find_nearest_market(&position, None)?.id;
+                        let market = markets.get_market_mut(market)?;
+                        Some(load.into_iter().map(|(r, q)| sell_to_market(market, r, q)).sum())
+                    })
+                    .unwrap_or(0.0);
+                (id, earned)
+            })
+            .collect();
+        drop(markets);
+
+        let sales: Vec<f64> = switches.iter().map(|(_, earned)| *earned).filter(|earned| *earned > 0.0).collect();
+        world.lifecycle.apply_updates(switches, |agent, earned| {
+            if earned > 0.0 {
+                agent.inventory.clear();
+                agent.wallet += earned;
+            }
+            // Entering or leaving, the agent (re)starts from a clean slate where it stands
+            agent.state = if harvest_types(agent.job).is_some() {
+                AgentState::Working { task: "gathering".to_string() }
+            } else {
+                AgentState::Idle
+            };
+            agent.just_harvested = None;
+            agent.just_transacted = None;
+        });
+
+        let mut currency = world.currency.write();
+        for earned in sales {
+            currency.record_transaction(earned);
+            currency.mint_currency(earned);
+        }
+
+        Ok(())
+    }
+}
+
+/// Settles aggregated agents' work and meals statistically
+pub struct AggregateSystem;
+
+#[async_trait]
+impl System for AggregateSystem {
+    fn name(&self) -> &'static str {
+        "aggregate"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Lod], &[Resource::Agents, Resource::Nodes, Resource::Markets, Resource::Currency])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["lod", "harvesting"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        let config = &world.config.lod;
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let aggregated: Vec<(AgentId, Position, Job, f64)> = {
+            let lod = world.lod.read();
+            world
+                .lifecycle
+                .agents()
+                .living()
+                .filter(|agent| lod.level(agent.id) == Lod::Aggregate)
+                .map(|agent| (agent.id, agent.position, agent.job, agent.wallet))
+                .collect()
+        };
+        if aggregated.is_empty() {
+            return Ok(());
+        }
+
+        // Expected yield per tick, rounded up or down at random so it averages out
+        let expected = HARVEST_AMOUNT as f64 * config.yield_factor;
+        let mut rng = sim_rng();
+        let mut markets = world.markets.write();
+        let mut payments: Vec<(AgentId, f64)> = Vec::new();
+        let mut transactions = Vec::new();
+        let (mut harvested_total, mut eaten) = (0, 0);
+
+        for (id, position, job, wallet) in aggregated {
+            let mut balance = 0.0;
+
+            if let Some(types) = harvest_types(job) {
+                let amount = expected.floor() as u32 + u32::from(rng.gen::<f64>() < expected.fract());
+                let node = world
+                    .resources
+                    .find_nearest_matching(position, |n| types.contains(&n.resource_type) && n.quantity > 0);
+                let market = markets.find_nearest_market(&position, None).map(|m| m.id);
+                if let (Some(node), Some(market)) = (node, market.and_then(|id| markets.get_market_mut(id))) {
+                    let harvested = world.resources.harvest(node.id, amount).unwrap_or(0);
+                    if harvested > 0 {
+                        let earned = sell_to_market(market, node_yield(node.resource_type), harvested);
+                        balance += earned;
+                        transactions.push(earned);
+                        harvested_total += harvested;
+                    }
+                }
+            }
+
+            if rng.gen::<f64>() < config.food_rate {
+                let market = markets.find_nearest_market(&position, None).map(|m| m.id);
+                if let Some(food) = market
+                    .and_then(|id| markets.get_market_mut(id))
+                    .and_then(|m| m.inventory.get_mut(&ResourceType::Food))
+                {
+                    if food.quantity > 0 && wallet + balance >= food.current_price {
+                        food.quantity -= 1;
+                        balance -= food.current_price;
+                        transactions.push(-food.current_price);
+                        eaten += 1;
+                    }
+                }
+            }
+
+            if balance != 0.0 {
+                payments.push((id, balance));
+            }
+        }
+        drop(markets);
+
+        world.lifecycle.apply_updates(payments, |agent, balance| agent.wallet += balance);
+        // Sales to markets create gold and purchases from them destroy it
+        let mut currency = world.currency.write();
+        for amount in transactions {
+            currency.record_transaction(amount.abs());
+            if amount > 0.0 {
+                currency.mint_currency(amount);
+            } else {
+                currency.burn_currency(-amount);
+            }
+        }
+
+        tracing::debug!("🌫️ Aggregated agents harvested {} units and ate {} food", harvested_total, eaten);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_by_distance_and_role() {
+        let config = LodConfig { enabled: true, ..LodConfig::default() };
+        let foci = [Position::new(0.0, 0.0, 0.0), Position::new(500.0, 0.0, 0.0)];
+        let at = |x: f32, class: SocialClass| {
+            let mut agent = SimAgent::new_with_class("Agent".to_string(), Position::new(x, 5.0, 0.0), class);
+            if agent.job == Job::Builder {
+                agent.job = Job::Farmer;
+            }
+            agent
+        };
+
+        assert_eq!(level_for(&at(30.0, SocialClass::Peasant), &foci, &config), Lod::Full);
+        assert_eq!(level_for(&at(-100.0, SocialClass::Peasant), &foci, &config), Lod::Reduced);
+        assert_eq!(level_for(&at(250.0, SocialClass::Merchant), &foci, &config), Lod::Aggregate);
+        assert_eq!(level_for(&at(450.0, SocialClass::Peasant), &foci, &config), Lod::Full);
+        assert_eq!(level_for(&at(250.0, SocialClass::Knight), &foci, &config), Lod::Full);
+        let mut builder = at(250.0, SocialClass::Peasant);
+        builder.job = Job::Builder;
+        assert_eq!(level_for(&builder, &foci, &config), Lod::Full);
+        assert_eq!(level_for(&at(250.0, SocialClass::Peasant), &[], &config), Lod::Aggregate);
+
+        // Every reduced agent gets exactly one turn per `reduced_every` ticks
+        let mut table = LodTable::default();
+        let ids: Vec<AgentId> = (0..50).map(|_| at(100.0, SocialClass::Peasant).id).collect();
+        table.levels = ids.iter().map(|id| (*id, Lod::Reduced)).collect();
+        let mut turns: HashMap<AgentId, usize> = HashMap::new();
+        for number in 0..5 {
+            let skipped = table.skipped(number, 5);
+            for id in ids.iter().filter(|id| !skipped.contains(id)) {
+                *turns.entry(*id).or_default() += 1;
+            }
+        }
+        assert!(ids.iter().all(|id| turns.get(id) == Some(&1)));
+    }
+}
@@ -0,0 +1,482 @@
+//! Simulation systems and the scheduler that runs them
+//!
+//! Each behavior (combat, harvesting, banking, ...) is a [`System`] with a tick rate, the
+//! world data it reads and writes, and ordering constraints. The [`Scheduler`] orders the
+//! registered systems for each rate and runs them against the shared [`World`].
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{info, warn};
+use world_sim_agents::LifecycleLayer;
+use world_sim_cognitive::StimulusSubsystem;
+use world_sim_core::ResourceType;
+use world_sim_event_bus::{EventBus, Season};
+use world_sim_meta::DungeonMaster;
+use world_sim_persistence::ChunkStore;
+use world_sim_societal::{CurrencySystem, EconomySubsystem, KingdomManager, MarketSystem, PoliticalLayer};
+use world_sim_world::{BuildingManager, ContentDefinitionLayer, EcologyLayer, FireSimulation, GridLayer, ObjectManager, ResourceManager, TerrainGenerator, WaterSimulation, WeatherState};
+
+use crate::config::{SimConfig, SystemsConfig};
+
+mod audit;
+mod banking;
+mod combat;
+mod construction;
+mod economy;
+mod environment;
+mod fire;
+mod harvesting;
+mod hierarchy;
+mod labor;
+mod lod;
+mod movement;
+mod needs;
+mod perception;
+mod streaming;
+mod vehicles;
+mod view;
+
+pub use audit::Auditor;
+pub use fire::FireFront;
+pub use labor::rebalance_labor;
+pub use lod::LodTable;
+pub use vehicles::launch_vehicles;
+
+/// Shared world state that systems operate on
+pub struct World {
+    pub event_bus: Arc<EventBus>,
+    pub grid: Arc<GridLayer>,
+    pub resources: Arc<ResourceManager>,
+    pub buildings: Arc<RwLock<BuildingManager>>,
+    pub lifecycle: Arc<LifecycleLayer>,
+    pub economy: Arc<EconomySubsystem>,
+    pub politics: Arc<PoliticalLayer>,
+    pub markets: Arc<RwLock<MarketSystem>>,
+    pub currency: Arc<RwLock<CurrencySystem>>,
+    pub kingdoms: Arc<RwLock<KingdomManager>>,
+    pub dungeon_master: Arc<DungeonMaster>,
+    /// Agent levels of detail and observer positions
+    pub lod: Arc<RwLock<LodTable>>,
+    pub weather: Arc<RwLock<WeatherState>>,
+    pub season: Arc<RwLock<Season>>,
+    /// Burning blocks and bucket-line posts
+    pub fires: Arc<RwLock<FireFront>>,
+    pub objects: Arc<RwLock<ObjectManager>>,
+    /// Sights and sounds broadcast since perception last ran
+    pub stimuli: Arc<StimulusSubsystem>,
+    /// Chunks paged out of the grid by streaming (part of the `Grid` resource)
+    pub chunk_store: Arc<ChunkStore>,
+    /// Generates chunk columns nobody has come near yet (part of the `Grid` resource)
+    pub generator: Arc<TerrainGenerator>,
+    /// Actions, items and block properties (read-only)
+    pub content: Arc<ContentDefinitionLayer>,
+    pub config: SimConfig,
+}
+
+impl World {
+    /// Current market price for a resource (averaged across all markets)
+    pub fn market_price(&self, resource_type: ResourceType) -> f64 {
+        let markets = self.markets.read();
+        let all_markets = markets.get_all_markets();
+
+        let mut total_price = 0.0;
+        let mut count = 0;
+
+        for market in all_markets {
+            if let Some(good) = market.inventory.get(&resource_type) {
+                total_price += good.current_price;
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            total_price / count as f64
+        } else {
+            // Fallback to defaults if no market data
+            match resource_type {
+                ResourceType::Wood => 5.0,
+                ResourceType::Stone => 3.0,
+                ResourceType::Iron => 15.0,
+                ResourceType::Food => 10.0,
+                _ => 5.0,
+            }
+        }
+    }
+}
+
+/// Timing passed to every system run
+#[derive(Debug, Clone, Copy)]
+pub struct Tick {
+    /// Seconds covered by this tick (the rate's interval)
+    pub delta_seconds: f64,
+    /// Simulated seconds since the world started
+    pub sim_time: f64,
+    /// Ticks already run at this rate
+    pub number: u64,
+}
+
+/// How often a system runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TickRate {
+    /// Real-time systems (movement, combat)
+    Fast,
+    /// Economy and utility AI
+    Slow,
+    /// Ecology, demographics and hierarchical AI
+    VerySlow,
+}
+
+/// A piece of world data, for declaring system access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Agents,
+    Buildings,
+    Markets,
+    Currency,
+    /// Harvestable resource nodes
+    Nodes,
+    Kingdoms,
+    Politics,
+    Economy,
+    DungeonMaster,
+    Grid,
+    /// Agent levels of detail
+    Lod,
+    /// Current weather and season (set by ecology)
+    Weather,
+    /// Burning blocks and bucket lines (set by fire)
+    Fires,
+    /// Carts, boats and siege engines
+    Objects,
+    /// Broadcast sights and sounds (drained by perception)
+    Stimuli,
+}
+
+/// World data a system reads and writes
+#[derive(Debug, Clone, Default)]
+pub struct Access {
+    pub reads: Vec<Resource>,
+    pub writes: Vec<Resource>,
+}
+
+impl Access {
+    pub fn new(reads: &[Resource], writes: &[Resource]) -> Self {
+        Self {
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+        }
+    }
+
+    /// Whether two systems touch the same data with at least one of them writing
+    pub fn conflicts_with(&self, other: &Access) -> bool {
+        self.writes.iter().any(|r| other.reads.contains(r) || other.writes.contains(r))
+            || other.writes.iter().any(|r| self.reads.contains(r))
+    }
+}
+
+/// A unit of simulation behavior run by the [`Scheduler`]
+#[async_trait]
+pub trait System: Send {
+    /// Unique name used in ordering constraints and `[systems]` config
+    fn name(&self) -> &'static str;
+
+    fn rate(&self) -> TickRate;
+
+    /// World data the system reads and writes (declared for the startup log, not enforced)
+    fn access(&self) -> Access;
+
+    /// Systems that must run before this one at the same rate (ignored if not registered)
+    fn after(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()>;
+}
+
+/// Every system in its default order
+pub fn default_systems(world: &World) -> Vec<Box<dyn System>> {
+    vec![
+        // Fast
+        Box::new(combat::CombatSystem::lethal()),
+        Box::new(movement::MovementSystem),
+        Box::new(vehicles::VehicleSystem),
+        Box::new(perception::PerceptionSystem::default()),
+        // Slow
+        Box::new(lod::LodSystem),
+        Box::new(streaming::StreamingSystem::default()),
+        Box::new(labor::LaborWatchdogSystem::default()),
+        Box::new(economy::PriceSystem::default()),
+        Box::new(environment::DungeonMasterSystem),
+        Box::new(needs::NeedsSystem),
+        Box::new(construction::BuilderAssignmentSystem),
+        Box::new(vehicles::HaulageSystem::default()),
+        Box::new(banking::BankingSystem),
+        Box::new(harvesting::RegenerationSystem),
+        Box::new(harvesting::HarvestingSystem::default()),
+        Box::new(lod::AggregateSystem),
+        Box::new(economy::TradingSystem),
+        Box::new(economy::WageSystem::default()),
+        Box::new(construction::ConstructionSystem),
+        Box::new(environment::WaterSystem::new(
+            WaterSimulation::new(world.config.water.clone()).with_extent(world.config.terrain.extent()),
+        )),
+        Box::new(fire::FireSystem::new(
+            FireSimulation::new(world.config.fire.clone()).with_extent(world.config.terrain.extent()),
+            &world.event_bus,
+        )),
+        Box::new(construction::StructureSystem::default()),
+        // Very slow
+        Box::new(labor::LaborSystem),
+        Box::new(economy::TaxSystem),
+        Box::new(construction::ConstructionFundingSystem),
+        Box::new(environment::EcologySystem::new(EcologyLayer::new(world.grid.clone()))),
+        Box::new(environment::DemographicsSystem),
+        Box::new(combat::WarSystem),
+        Box::new(hierarchy::KingSystem),
+        Box::new(hierarchy::NobleSystem),
+        Box::new(hierarchy::PeasantBuildingSystem),
+    ]
+}
+
+/// Alternative implementation selectable with `systems.replace.<name> = "<variant>"`
+fn variant(name: &str, variant: &str) -> Option<Box<dyn System>> {
+    match (name, variant) {
+        ("combat", "nonlethal") => Some(Box::new(combat::CombatSystem::nonlethal())),
+        _ => None,
+    }
+}
+
+/// Ordering or registration problems found while building a schedule
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("system `{0}` is registered twice")]
+    Duplicate(&'static str),
+    #[error("system `{system}` must run after unknown system `{dependency}`")]
+    UnknownDependency {
+        system: &'static str,
+        dependency: &'static str,
+    },
+    #[error("ordering constraints form a cycle through: {0:?}")]
+    Cycle(Vec<&'static str>),
+    #[error("system `{0}` has no variant `{1}`")]
+    UnknownVariant(String, String),
+    #[error("unknown system `{name}` (expected one of {known})")]
+    UnknownSystem { name: String, known: String },
+}
+
+/// Orders registered systems per tick rate and runs them
+pub struct Scheduler {
+    /// Systems in execution order (the relative order within each rate is what matters)
+    systems: Vec<Box<dyn System>>,
+    /// Checks invariants after every system when auditing is on
+    auditor: Option<Auditor>,
+}
+
+impl Scheduler {
+    /// Order `systems` by their `after` constraints, keeping registration order otherwise
+    ///
+    /// `after` may name a registered system or one of the `known` systems left out of this
+    /// schedule (such as disabled ones); anything else is an error.
+    pub fn new(systems: Vec<Box<dyn System>>, known: &HashSet<&'static str>) -> std::result::Result<Self, ScheduleError> {
+        let mut seen = HashSet::new();
+        for system in &systems {
+            if !seen.insert(system.name()) {
+                return Err(ScheduleError::Duplicate(system.name()));
+            }
+        }
+        for system in &systems {
+            for dependency in system.after() {
+                if !seen.contains(dependency) && !known.contains(dependency) {
+                    return Err(ScheduleError::UnknownDependency {
+                        system: system.name(),
+                        dependency,
+                    });
+                }
+            }
+        }
+
+        // Kahn's algorithm, always picking the earliest registered ready system
+        let mut pending = systems;
+        let mut ordered: Vec<Box<dyn System>> = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|system| {
+                system.after().iter().all(|dependency| {
+                    !pending.iter().any(|other| other.name() == *dependency && other.rate() == system.rate())
+                })
+            });
+            match ready {
+                Some(index) => ordered.push(pending.remove(index)),
+                None => return Err(ScheduleError::Cycle(pending.iter().map(|s| s.name()).collect())),
+            }
+        }
+
+        Ok(Self { systems: ordered, auditor: None })
+    }
+
+    /// The default systems with `[systems]` config applied (disabled and replaced systems)
+    pub fn from_config(world: &World, config: &SystemsConfig) -> std::result::Result<Self, ScheduleError> {
+        let defaults = default_systems(world);
+        let known: HashSet<&'static str> = defaults.iter().map(|system| system.name()).collect();
+        for name in config.disabled.iter().chain(config.replace.keys()) {
+            if !known.contains(name.as_str()) {
+                let known = defaults.iter().map(|system| system.name()).collect::<Vec<_>>().join(", ");
+                return Err(ScheduleError::UnknownSystem { name: name.clone(), known });
+            }
+        }
+
+        let mut systems = Vec::new();
+        for system in defaults {
+            if config.disabled.iter().any(|name| name == system.name()) {
+                info!("⏭️ System disabled: {}", system.name());
+                continue;
+            }
+            match config.replace.get(system.name()) {
+                Some(name) => {
+                    let replacement = variant(system.name(), name)
+                        .ok_or_else(|| ScheduleError::UnknownVariant(system.name().to_string(), name.clone()))?;
+                    info!("🔁 System replaced: {} → {}", system.name(), name);
+                    systems.push(replacement);
+                }
+                None => systems.push(system),
+            }
+        }
+        Self::new(systems, &known)
+    }
+
+    /// Audit the world after every system
+    pub fn with_auditor(mut self, auditor: Auditor) -> Self {
+        self.auditor = Some(auditor);
+        self
+    }
+
+    pub fn auditor(&self) -> Option<&Auditor> {
+        self.auditor.as_ref()
+    }
+
+    /// Run every system registered at `rate`, in order
+    pub async fn run(&mut self, rate: TickRate, world: &World, tick: &Tick) -> Result<()> {
+        if let Some(auditor) = &mut self.auditor {
+            auditor.rebaseline(world);
+        }
+        for system in self.systems.iter_mut().filter(|s| s.rate() == rate) {
+            system.run(world, tick).await?;
+
+            if let Some(auditor) = &mut self.auditor {
+                for violation in auditor.check(system.name(), world, tick.sim_time) {
+                    if auditor.fails_on_violation() {
+                        bail!("Invariant violated: {}", violation);
+                    }
+                    warn!("🧾 Invariant violated: {}", violation);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Systems at `rate` grouped into stages whose members don't conflict on their declared
+    /// access, for the startup log. Systems always run one at a time, and declared access is not
+    /// checked against what a system actually touches.
+    pub fn stages(&self, rate: TickRate) -> Vec<Vec<&'static str>> {
+        let mut stages: Vec<Vec<&dyn System>> = Vec::new();
+        for system in self.systems.iter().filter(|s| s.rate() == rate) {
+            let system = system.as_ref();
+            let fits = stages.last().is_some_and(|stage| {
+                stage.iter().all(|other| {
+                    !system.access().conflicts_with(&other.access()) && !system.after().contains(&other.name())
+                })
+            });
+            match stages.last_mut() {
+                Some(stage) if fits => stage.push(system),
+                _ => stages.push(vec![system]),
+            }
+        }
+        stages
+            .into_iter()
+            .map(|stage| stage.into_iter().map(|s| s.name()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Probe {
+        name: &'static str,
+        after: &'static [&'static str],
+        access: Access,
+    }
+
+    #[async_trait]
+    impl System for Probe {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn rate(&self) -> TickRate {
+            TickRate::Slow
+        }
+
+        fn access(&self) -> Access {
+            self.access.clone()
+        }
+
+        fn after(&self) -> &'static [&'static str] {
+            self.after
+        }
+
+        async fn run(&mut self, _world: &World, _tick: &Tick) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn probe(name: &'static str, after: &'static [&'static str], reads: &[Resource], writes: &[Resource]) -> Box<dyn System> {
+        Box::new(Probe {
+            name,
+            after,
+            access: Access::new(reads, writes),
+        })
+    }
+
+    #[test]
+    fn test_ordering_and_stages() {
+        let scheduler = Scheduler::new(vec![
+            probe("trading", &["harvesting"], &[], &[Resource::Markets]),
+            probe("prices", &[], &[], &[Resource::Economy]),
+            probe("harvesting", &[], &[Resource::Nodes], &[Resource::Agents]),
+            probe("war", &[], &[Resource::Agents], &[Resource::Politics]),
+        ], &HashSet::new())
+        .unwrap();
+        assert_eq!(
+            scheduler.stages(TickRate::Slow),
+            vec![vec!["prices", "harvesting"], vec!["trading", "war"]]
+        );
+
+        assert!(matches!(
+            Scheduler::new(vec![probe("needs", &["no_such_system"], &[], &[])], &HashSet::new()),
+            Err(ScheduleError::UnknownDependency { .. })
+        ));
+        assert!(matches!(
+            Scheduler::new(vec![probe("needs", &["banking"], &[], &[]), probe("banking", &["needs"], &[], &[])], &HashSet::new()),
+            Err(ScheduleError::Cycle(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_config_names_registered_systems() {
+        let mut config = SimConfig::default();
+        config.terrain.generator = world_sim_world::TerrainKind::Flat;
+
+        config.systems.disabled = vec!["combat".to_string(), "wars".to_string()];
+        let error = crate::simulation::Simulation::new(config.clone(), Arc::new(EventBus::new())).err().unwrap();
+        assert!(error.to_string().contains("wars"), "{}", error);
+
+        // `trading` runs after `harvesting`, which may still be disabled
+        config.systems.disabled = vec!["harvesting".to_string()];
+        config.systems.replace.insert("combat".to_string(), "nonlethal".to_string());
+        assert!(crate::simulation::Simulation::new(config, Arc::new(EventBus::new())).is_ok());
+    }
+}
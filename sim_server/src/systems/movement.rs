@@ -0,0 +1,525 @@
+//! Job-based movement and social attraction/repulsion
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::Rng;
+use world_sim_agents::{AgentState, Job, SimAgent};
+use world_sim_core::{AgentId, GridCoord, Position};
+use world_sim_world::ResourceNodeType;
+
+use super::view::WorldView;
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// How close to a fire agents not fighting it will stay
+const FIRE_FLEE_RADIUS: f32 = 6.0;
+
+pub struct MovementSystem;
+
+#[async_trait]
+impl System for MovementSystem {
+    fn name(&self) -> &'static str {
+        "movement"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Fast
+    }
+
+    fn access(&self) -> Access {
+        Access::new(
+            &[Resource::Markets, Resource::Buildings, Resource::Nodes, Resource::Lod, Resource::Fires, Resource::Objects],
+            &[Resource::Agents],
+        )
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["combat"]
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        // Phase 2: Job-based movement + Phase 3: Social attraction/repulsion
+        // Agents aboard a cart or boat go where it takes them (see `VehicleSystem`)
+        let riders: HashSet<AgentId> = world.objects.read().get_all().iter().flat_map(|o| o.crew.iter().copied()).collect();
+        let view = WorldView::capture(world, tick);
+        let moves = view.plan_with_rng(|agent, rng| {
+            if riders.contains(&agent.id) {
+                return None;
+            }
+            let mut next = Motion {
+                position: agent.position,
+                state: agent.state.clone(),
+            };
+            steer(agent, &mut next, &view, rng);
+            Some(next)
+        });
+        drop(view);
+        
+        world.lifecycle.apply_updates(moves, |agent, next| {
+            let (dx, dz) = (next.position.x - agent.position.x, next.position.z - agent.position.z);
+            if dx != 0.0 || dz != 0.0 {
+                agent.heading = Some(Position::new(dx, 0.0, dz));
+            }
+            agent.position = next.position;
+            agent.state = next.state;
+        });
+        
+        Ok(())
+    }
+}
+
+/// Where an agent ends up this tick and what it is doing
+struct Motion {
+    position: Position,
+    state: AgentState,
+}
+
+/// Decide one agent's movement against the tick's view of the world
+fn steer(agent: &SimAgent, next: &mut Motion, view: &WorldView<'_>, rng: &mut StdRng) {
+    // Fire comes first: bucket-line crews take their posts, everyone else keeps clear of the flames
+    if let Some(post) = view.fires.posts.get(&agent.id) {
+        let dx = post.x - next.position.x;
+        let dz = post.z - next.position.z;
+        let dist = (dx * dx + dz * dz).sqrt();
+        if dist > 0.1 {
+            let stride = dist.min(0.8);
+            next.position.x += (dx / dist) * stride;
+            next.position.z += (dz / dist) * stride;
+        }
+        next.state = AgentState::Working { task: "bucket_line".to_string() };
+        return;
+    }
+    if let Some((fire, dist)) = view.fires.nearest(&next.position) {
+        if dist < FIRE_FLEE_RADIUS {
+            let dx = next.position.x - fire.x;
+            let dz = next.position.z - fire.z;
+            let dist = (dx * dx + dz * dz).sqrt().max(0.1);
+            next.position.x = (next.position.x + (dx / dist) * 0.8).clamp(-95.0, 95.0);
+            next.position.z = (next.position.z + (dz / dist) * 0.8).clamp(-95.0, 95.0);
+            return;
+        }
+    }
+
+    // Phase 0: Special class behaviors (highest priority)
+    
+    // Knights follow their leader (king)
+    if let Some(leader_id) = agent.leader_id {
+        // Find the leader
+        if let Some(leader) = view.agents.get(leader_id).filter(|a| a.is_alive()) {
+            let dist = next.position.distance_to(&leader.position);
+            
+            // If too far from leader, move closer
+            if dist > 5.0 {
+                let dx = leader.position.x - next.position.x;
+                let dz = leader.position.z - next.position.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                if dist > 0.1 {
+                    // Follow at good speed
+                    next.position.x += (dx / dist) * 0.8;
+                    next.position.z += (dz / dist) * 0.8;
+                    next.state = AgentState::Following { leader: leader_id };
+                }
+                return; // Skip other behaviors
+            } else if dist < 2.0 {
+                // Too close, back off slightly
+                let dx = next.position.x - leader.position.x;
+                let dz = next.position.z - leader.position.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                if dist > 0.1 {
+                    next.position.x += (dx / dist) * 0.2;
+                    next.position.z += (dz / dist) * 0.2;
+                }
+                next.state = AgentState::Following { leader: leader_id };
+                return;
+            }
+            // If at perfect distance (2-5 units), just maintain position
+            next.state = AgentState::Following { leader: leader_id };
+        }
+    }
+    
+    // HARVESTERS in Trading state: Move to nearest market to deposit resources
+    if matches!(next.state, AgentState::Trading { .. }) && matches!(agent.job, Job::Woodcutter | Job::Miner | Job::Farmer) {
+        if let Some(market) = view.nearest_market(&next.position) {
+            let dist = next.position.distance_to(&market.position);
+            
+            if dist > 6.0 {
+                // Travel to market
+                let dx = market.position.x - next.position.x;
+                let dz = market.position.z - next.position.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                if dist > 0.1 {
+                    next.position.x += (dx / dist) * 0.8; // Fast movement when full
+                    next.position.z += (dz / dist) * 0.8;
+                }
+            }
+            // Note: Deposit happens in tick_slow, not here
+        }
+        return; // Skip other movement when going to market
+    }
+    
+    // BUILDERS: Move to market to get resources or to construction site to deliver
+    if matches!(agent.job, Job::Builder) {
+        if let Some(carrying) = &agent.carrying_resources {
+            // Check if builder has ACTUAL resources (not just a target building ID)
+            let has_resources = carrying.wood > 0 || carrying.stone > 0 || carrying.iron > 0;
+            
+            if has_resources {
+                // Carrying ACTUAL resources - move to construction site to deliver
+                if let Some(building) = view.building(carrying.target_building_id) {
+                let dist = next.position.distance_to(&building.position);
+                
+                if dist > 5.0 {
+                    // Move towards construction site
+                    let dx = building.position.x - next.position.x;
+                    let dz = building.position.z - next.position.z;
+                    let dist = (dx * dx + dz * dz).sqrt();
+                    if dist > 0.1 {
+                        next.position.x += (dx / dist) * 0.7; // Fast delivery
+                        next.position.z += (dz / dist) * 0.7;
+                    }
+                    next.state = AgentState::Building { 
+                        building_type: format!("{:?}", building.building_type)
+                    };
+                }
+                // Note: Delivery happens in tick_slow when dist < 5.0
+            }
+                return; // Skip other movement when delivering
+            } else {
+                // Has target but NO resources (wood=0, stone=0, iron=0) - go to market!
+                if let Some(market) = view.nearest_market(&next.position) {
+                    let dist = next.position.distance_to(&market.position);
+                    
+                    if dist > 6.0 {
+                        // Travel to market to buy resources
+                        let dx = market.position.x - next.position.x;
+                        let dz = market.position.z - next.position.z;
+                        let dist = (dx * dx + dz * dz).sqrt();
+                        if dist > 0.1 {
+                            next.position.x += (dx / dist) * 0.7; // Move fast
+                            next.position.z += (dz / dist) * 0.7;
+                        }
+                    }
+                    // Switch to Moving state so we're recognized as going to market
+                    next.state = AgentState::Moving { destination: world_sim_core::GridCoord { x: 0, y: 0, z: 0 } };
+                }
+                return; // Skip other movement when going to market
+            }
+        } else if matches!(next.state, AgentState::Moving { .. }) {
+            // In Moving state - heading to market to get resources
+            if let Some(market) = view.nearest_market(&next.position) {
+                let dist = next.position.distance_to(&market.position);
+                
+                if dist > 6.0 {
+                    // Travel to market
+                    let dx = market.position.x - next.position.x;
+                    let dz = market.position.z - next.position.z;
+                    let dist = (dx * dx + dz * dz).sqrt();
+                    if dist > 0.1 {
+                        next.position.x += (dx / dist) * 0.7; // Builders move fast to get materials
+                        next.position.z += (dz / dist) * 0.7;
+                    }
+                }
+                // Note: Resource pickup happens in tick_slow, not here
+            }
+            return; // Skip other movement when going to market
+        }
+    }
+    
+    // Burghers and Merchants move to markets to facilitate trade
+    if matches!(agent.social_class, world_sim_agents::SocialClass::Burgher | world_sim_agents::SocialClass::Merchant) {
+        // Find nearest market
+        if let Some(market) = view.nearest_market(&next.position) {
+            let dist = next.position.distance_to(&market.position);
+            
+            if dist > 5.0 {
+                // Travel to market
+                let dx = market.position.x - next.position.x;
+                let dz = market.position.z - next.position.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                if dist > 0.1 {
+                    next.position.x += (dx / dist) * 0.6; // Faster than peasants
+                    next.position.z += (dz / dist) * 0.6;
+                }
+                next.state = AgentState::Moving { 
+                    destination: GridCoord::new(market.position.x as i32, 0, market.position.z as i32)
+                };
+            } else {
+                // At market, set trading state
+                next.state = AgentState::Trading { with: agent.id }; // Placeholder - trading with self
+            }
+            return; // Skip other movement
+        }
+    }
+    
+    // Soldiers patrol the community (central area by default, or faction territory if assigned)
+    if matches!(agent.social_class, world_sim_agents::SocialClass::Soldier) {
+        // Define patrol routes based on faction (if they have one) or central area
+        let patrol_routes = if let Some(faction) = agent.personality.beliefs.faction_loyalty {
+            // Patrol faction territory
+            let base_x = if format!("{:?}", faction).contains("2cf7e1d2") { -40.0 } else { 40.0 };
+            let base_z = if format!("{:?}", faction).contains("2cf7e1d2") { -40.0 } else { 40.0 };
+            
+            vec![
+                Position::new(base_x + 30.0, 1.0, base_z + 30.0),
+                Position::new(base_x + 30.0, 1.0, base_z - 30.0),
+                Position::new(base_x - 30.0, 1.0, base_z - 30.0),
+                Position::new(base_x - 30.0, 1.0, base_z + 30.0),
+            ]
+        } else {
+            // No faction - patrol central community area
+            vec![
+                Position::new(50.0, 1.0, 50.0),
+                Position::new(50.0, 1.0, -50.0),
+                Position::new(-50.0, 1.0, -50.0),
+                Position::new(-50.0, 1.0, 50.0),
+            ]
+        };
+        
+        // Get current waypoint index
+        let route_index = if let AgentState::Patrolling { route_index } = next.state {
+            route_index
+        } else {
+            0
+        };
+        
+        let waypoint = &patrol_routes[route_index % patrol_routes.len()];
+        let dist = next.position.distance_to(waypoint);
+        
+        if dist > 2.0 {
+            // Move toward waypoint
+            let dx = waypoint.x - next.position.x;
+            let dz = waypoint.z - next.position.z;
+            let dist = (dx * dx + dz * dz).sqrt();
+            if dist > 0.1 {
+                next.position.x += (dx / dist) * 0.5;
+                next.position.z += (dz / dist) * 0.5;
+            }
+            next.state = AgentState::Patrolling { route_index };
+        } else {
+            // Reached waypoint, move to next
+            next.state = AgentState::Patrolling { 
+                route_index: (route_index + 1) % patrol_routes.len()
+            };
+        }
+        return; // Skip other movement
+    }
+    
+    // Phase 1: Conversation behavior (before work/combat)
+    // Non-soldiers have a chance to talk to nearby agents
+    if !matches!(agent.social_class, world_sim_agents::SocialClass::Soldier) {
+        // Look for nearby agents to talk to
+        for other in view.agents.within_radius(&next.position, 3.0) {
+            if other.id == agent.id || !other.is_alive() {
+                continue;
+            }
+            
+            // Check if same faction (allies) - if no factions, everyone is friendly
+            let are_friendly = match (agent.personality.beliefs.faction_loyalty, other.personality.beliefs.faction_loyalty) {
+                (Some(f1), Some(f2)) => f1 == f2, // Same faction
+                (None, None) => true,              // Both have no faction = friendly
+                _ => false,                        // One has faction, one doesn't = neutral/avoid
+            };
+            
+            if are_friendly {
+                let dist = next.position.distance_to(&other.position);
+                
+                // If very close (within 3 units) and not already talking, start conversation
+                if dist < 3.0 && dist > 0.5 {
+                    // Random chance to initiate conversation (10% per tick)
+                    if rng.gen::<f32>() < 0.1 {
+                        // Check if both agents are idle or already talking
+                        let can_talk = matches!(next.state, AgentState::Idle) 
+                            || matches!(next.state, AgentState::Talking { .. });
+                            
+                        if can_talk {
+                            next.state = AgentState::Talking { with: other.id };
+                            return; // Skip other behaviors while talking
+                        }
+                    }
+                }
+            }
+        }
+        
+        // If already in talking state but the conversation partner is gone or far away, go back to idle
+        if let AgentState::Talking { with } = next.state {
+            let partner_found = view.agents.get(with).is_some_and(|other| {
+                other.is_alive() && next.position.distance_to(&other.position) < 5.0
+            });
+            
+            if !partner_found {
+                next.state = AgentState::Idle;
+            } else {
+                // Stay in place while talking
+                return;
+            }
+        }
+    }
+    
+    // Phase 3: Social behavior
+    // Find nearest ally (same faction) and enemy (other faction) - only faction members have either
+    let mut nearest_ally_pos: Option<Position> = None;
+    let mut nearest_enemy_pos: Option<Position> = None;
+    let mut nearest_ally_dist = f32::MAX;
+    let mut nearest_enemy_dist = f32::MAX;
+    
+    if let Some(my_f) = agent.personality.beliefs.faction_loyalty {
+        let nearest = |allied: bool| view.agents.nearest(&next.position, |other| {
+            other.id != agent.id
+                && other.is_alive()
+                && other.personality.beliefs.faction_loyalty.is_some_and(|other_f| (other_f == my_f) == allied)
+        });
+        if let Some((ally, dist)) = nearest(true) {
+            nearest_ally_pos = Some(ally.position);
+            nearest_ally_dist = dist;
+        }
+        if let Some((enemy, dist)) = nearest(false) {
+            nearest_enemy_pos = Some(enemy.position);
+            nearest_enemy_dist = dist;
+        }
+    }
+    
+    // Movement priorities: 1) Flee from close enemies, 2) Move to job, 3) Stay near allies
+    if let Some(enemy_pos) = nearest_enemy_pos {
+        if nearest_enemy_dist < 25.0 {
+            // Enemy nearby! Most agents are aggressive (increased from 10.0 for more combat)
+            if agent.has_trait(world_sim_core::Trait::Brave) 
+               || matches!(next.state, AgentState::Fighting { .. })
+               || rng.gen::<f32>() < 0.7 {  // 70% of agents are aggressive
+                // Move TOWARD enemy (brave, fighting, or randomly aggressive)
+                let dx = enemy_pos.x - next.position.x;
+                let dz = enemy_pos.z - next.position.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                if dist > 0.1 {
+                    next.position.x += (dx / dist) * 1.0; // Increased from 0.4 for faster pursuit
+                    next.position.z += (dz / dist) * 1.0;
+                }
+                return; // Skip other movement
+            } else {
+                // Flee! (only 30% of agents flee)
+                let dx = next.position.x - enemy_pos.x;
+                let dz = next.position.z - enemy_pos.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                if dist > 0.1 {
+                    next.position.x = (next.position.x + (dx / dist) * 0.6).clamp(-95.0, 95.0);
+                    next.position.z = (next.position.z + (dz / dist) * 0.6).clamp(-95.0, 95.0);
+                }
+                return; // Skip other movement
+            }
+        }
+    }
+    
+    // Normal job-based movement
+    match agent.job {
+        Job::Woodcutter => {
+            if let Some(tree) = view.nearest_node(next.position, ResourceNodeType::Tree) {
+                let dx = tree.position.x - next.position.x;
+                let dz = tree.position.z - next.position.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                
+                if dist > 2.0 {
+                    next.position.x += (dx / dist) * 0.3;
+                    next.position.z += (dz / dist) * 0.3;
+                } else {
+                    next.state = AgentState::Working { task: "chopping".to_string() };
+                }
+            }
+        },
+        Job::Miner => {
+            // Miners harvest from rocks (stone), iron deposits (iron) and gold deposits (gold)
+            // Prioritize the ores if they're not much further, otherwise rocks
+            let iron_deposit = view.nearest_node(next.position, ResourceNodeType::IronDeposit);
+            let gold_deposit = view.nearest_node(next.position, ResourceNodeType::GoldDeposit);
+            let rock = view.nearest_node(next.position, ResourceNodeType::Rock);
+            
+            let target = match (iron_deposit, rock) {
+                (Some(iron), Some(rock_node)) => {
+                    let iron_dist = next.position.distance_to(&iron.position);
+                    let rock_dist = next.position.distance_to(&rock_node.position);
+                    // Choose iron if it's within reasonable distance, otherwise rock
+                    if iron_dist < rock_dist * 1.5 { // Prefer iron if comparable distance
+                        Some(iron)
+                    } else {
+                        Some(rock_node)
+                    }
+                },
+                (Some(iron), None) => Some(iron),
+                (None, Some(rock_node)) => Some(rock_node),
+                (None, None) => None,
+            };
+            // Gold is worth a longer walk
+            let target = match (gold_deposit, target) {
+                (Some(gold), Some(other)) => {
+                    if next.position.distance_to(&gold.position) < next.position.distance_to(&other.position) * 2.0 {
+                        gold
+                    } else {
+                        other
+                    }
+                }
+                (Some(gold), None) => gold,
+                (None, Some(other)) => other,
+                (None, None) => {
+                    next.state = AgentState::Idle;
+                    return; // No resources available
+                }
+            };
+            
+            let dx = target.position.x - next.position.x;
+            let dz = target.position.z - next.position.z;
+            let dist = (dx * dx + dz * dz).sqrt();
+            
+            if dist > 2.0 {
+                next.position.x += (dx / dist) * 0.3;
+                next.position.z += (dz / dist) * 0.3;
+            } else {
+                next.state = AgentState::Working { task: "mining".to_string() };
+            }
+        },
+        Job::Farmer => {
+            if let Some(farm) = view.nearest_node(next.position, ResourceNodeType::Farm) {
+                let dx = farm.position.x - next.position.x;
+                let dz = farm.position.z - next.position.z;
+                let dist = (dx * dx + dz * dz).sqrt();
+                
+                if dist > 2.0 {
+                    next.position.x += (dx / dist) * 0.3;
+                    next.position.z += (dz / dist) * 0.3;
+                } else {
+                    next.state = AgentState::Working { task: "farming".to_string() };
+                }
+            }
+        },
+        Job::Builder => {
+            // Builders handled by dedicated logic above (lines 476-522)
+            // Skip default movement to avoid conflicts
+        },
+        Job::Unemployed => {
+            // Social grouping - move toward allies
+            if let Some(ally_pos) = nearest_ally_pos {
+                if nearest_ally_dist > 15.0 {
+                    // Too far from allies, move toward them
+                    let dx = ally_pos.x - next.position.x;
+                    let dz = ally_pos.z - next.position.z;
+                    let dist = (dx * dx + dz * dz).sqrt();
+                    if dist > 0.1 {
+                        next.position.x += (dx / dist) * 0.2;
+                        next.position.z += (dz / dist) * 0.2;
+                    }
+                } else {
+                    // Near allies, random wander
+                    let dx = rng.gen::<f32>() * 2.0 - 1.0;
+                    let dz = rng.gen::<f32>() * 2.0 - 1.0;
+                    next.position.x = (next.position.x + dx * 0.2).clamp(-95.0, 95.0);
+                    next.position.z = (next.position.z + dz * 0.2).clamp(-95.0, 95.0);
+                }
+            } else {
+                // No allies found, random wander
+                let dx = rng.gen::<f32>() * 2.0 - 1.0;
+                let dz = rng.gen::<f32>() * 2.0 - 1.0;
+                next.position.x = (next.position.x + dx * 0.3).clamp(-95.0, 95.0);
+                next.position.z = (next.position.z + dz * 0.3).clamp(-95.0, 95.0);
+            }
+        },
+    }
+}
@@ -0,0 +1,126 @@
+//! Basic needs cycle (eat, sleep, work)
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use world_sim_agents::{AgentState, Job};
+
+use super::view::WorldView;
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+pub struct NeedsSystem;
+
+#[async_trait]
+impl System for NeedsSystem {
+    fn name(&self) -> &'static str {
+        "needs"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Lod], &[Resource::Agents])
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        // Quick Win: Basic needs cycle for agents (runs every second)
+        let view = WorldView::capture(world, tick);
+        let states = view.plan_with_rng(|agent, rng| {
+            // Simple state machine: Idle → Eating → Sleeping → Working → Idle
+            // BUT: Harvesters (Woodcutter/Miner/Farmer) should be Working 80% of the time!
+            let new_state = match &agent.state {
+                AgentState::Idle => {
+                    // HARVESTERS: Prioritize Working state
+                    if matches!(agent.job, Job::Woodcutter | Job::Miner | Job::Farmer) {
+                        let roll = rng.gen::<f32>();
+                        if roll < 0.85 {
+                            // 85% chance to start working
+                            AgentState::Working { task: "gathering".to_string() }
+                        } else if roll < 0.92 {
+                            // 7% chance to eat
+                            AgentState::Eating
+                        } else {
+                            // 8% chance to sleep
+                            AgentState::Sleeping
+                        }
+                    } else if matches!(agent.job, Job::Builder) {
+                        // BUILDERS: Don't randomly wander - they'll be assigned to buildings below
+                        AgentState::Idle
+                    } else {
+                        // OTHER NON-HARVESTERS: Original state machine
+                        let roll = rng.gen::<f32>();
+                        if roll < 0.3 {
+                            AgentState::Eating
+                        } else if roll < 0.5 {
+                            AgentState::Sleeping
+                        } else if roll < 0.7 {
+                            AgentState::Working { task: "gathering".to_string() }
+                        } else {
+                            AgentState::Idle
+                        }
+                    }
+                },
+                AgentState::Eating => {
+                    // HARVESTERS: Quickly return to work after eating
+                    if matches!(agent.job, Job::Woodcutter | Job::Miner | Job::Farmer) {
+                        // 90% chance to go straight back to working
+                        if rng.gen::<f32>() < 0.9 {
+                            AgentState::Working { task: "gathering".to_string() }
+                        } else {
+                            AgentState::Sleeping
+                        }
+                    } else if matches!(agent.job, Job::Builder) {
+                        // BUILDERS: Always return to Idle after eating (ready for assignment)
+                        AgentState::Idle
+                    } else {
+                        // Non-harvesters: original logic
+                        if rng.gen::<f32>() < 0.5 {
+                            AgentState::Sleeping
+                        } else {
+                            AgentState::Idle
+                        }
+                    }
+                },
+                AgentState::Sleeping => {
+                    // HARVESTERS: Immediately return to work after sleeping
+                    if matches!(agent.job, Job::Woodcutter | Job::Miner | Job::Farmer) {
+                        AgentState::Working { task: "gathering".to_string() }
+                    } else if matches!(agent.job, Job::Builder) {
+                        // BUILDERS: Always return to Idle after sleeping (ready for assignment)
+                        AgentState::Idle
+                    } else {
+                        // Non-harvesters: go to idle
+                        AgentState::Idle
+                    }
+                },
+                AgentState::Working { .. } => {
+                    // HARVESTERS: Stay in working longer (only 15% chance to stop)
+                    if matches!(agent.job, Job::Woodcutter | Job::Miner | Job::Farmer) {
+                        if rng.gen::<f32>() < 0.15 {
+                            AgentState::Idle
+                        } else {
+                            // Stay working!
+                            AgentState::Working { task: "gathering".to_string() }
+                        }
+                    } else if matches!(agent.social_class, world_sim_agents::SocialClass::Burgher | world_sim_agents::SocialClass::Merchant) {
+                        // BURGHERS/MERCHANTS: Stay in Trading state (they live at markets)
+                        AgentState::Trading { with: agent.id } // Placeholder ID
+                    } else {
+                        // Other non-harvesters: cycle back to idle
+                        AgentState::Idle
+                    }
+                },
+                _ => agent.state.clone(),
+            };
+            
+            Some(new_state)
+        });
+        drop(view);
+        
+        world.lifecycle.apply_updates(states, |agent, state| agent.state = state);
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! What agents see and hear
+//!
+//! Every agent keeps an [`AgentPerception`], looking the way it last stepped. Once every
+//! [`PERCEIVE_EVERY`] ticks (turns staggered by id) it learns of the agents in sight (within
+//! its sight radius and cone, not behind solid blocks) and of the sounds broadcast on
+//! [`World::stimuli`] since its last turn. Agents skipped by level of detail miss the turn.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use world_sim_agents::{AgentStore, SimAgent};
+use world_sim_cognitive::{AgentPerception, Stimulus, VisualStimulus};
+use world_sim_core::AgentId;
+use world_sim_world::GridLayer;
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// Fast ticks between an agent's looks around (a raycast per agent in sight is too dear to do every tick)
+const PERCEIVE_EVERY: u64 = 10;
+
+#[derive(Default)]
+pub struct PerceptionSystem {
+    perceptions: HashMap<AgentId, AgentPerception>,
+    /// Stimuli broadcast in each of the last `PERCEIVE_EVERY` ticks, oldest first
+    recent: VecDeque<Vec<Stimulus>>,
+}
+
+#[async_trait]
+impl System for PerceptionSystem {
+    fn name(&self) -> &'static str {
+        "perception"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Fast
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Agents, Resource::Grid, Resource::Lod], &[Resource::Stimuli])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["movement"]
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        if self.recent.len() == PERCEIVE_EVERY as usize {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(world.stimuli.collect_and_clear());
+        let sounds: Vec<Stimulus> = self.recent.iter().flatten().cloned().collect();
+        let skipped = if world.config.lod.enabled {
+            world.lod.read().skipped(tick.number, world.config.lod.reduced_every)
+        } else {
+            HashSet::new()
+        };
+        let agents = world.lifecycle.agents();
+
+        // The dead forget; newcomers start out knowing nobody
+        self.perceptions.retain(|id, _| agents.get(*id).is_some_and(|a| a.is_alive()));
+        for agent in agents.living() {
+            self.perceptions.entry(agent.id).or_default();
+        }
+
+        let time = tick.sim_time as u64;
+        self.perceptions
+            .par_iter_mut()
+            .filter(|(id, _)| id.0.as_u64_pair().1.wrapping_add(tick.number) % PERCEIVE_EVERY == 0 && !skipped.contains(id))
+            .for_each(|(id, perception)| {
+                if let Some(agent) = agents.get(*id) {
+                    perceive(perception, agent, &agents, &sounds, &world.grid, time);
+                }
+            });
+
+        Ok(())
+    }
+}
+
+/// Update one agent's perception: it faces its heading and takes in the agents within sight
+/// radius along with the broadcast `sounds`
+fn perceive(
+    perception: &mut AgentPerception,
+    agent: &SimAgent,
+    agents: &AgentStore,
+    sounds: &[Stimulus],
+    grid: &GridLayer,
+    time: u64,
+) {
+    perception.facing = agent.heading;
+    let mut stimuli: Vec<Stimulus> = agents
+        .within_radius(&agent.position, perception.sight_radius)
+        .into_iter()
+        .filter(|other| other.id != agent.id && other.is_alive())
+        .map(|other| Stimulus::Visual {
+            source: other.position,
+            stimulus_type: VisualStimulus::Agent(other.id),
+        })
+        .collect();
+    stimuli.extend(sounds.iter().cloned());
+    perception.process_stimuli(agent.position, &stimuli, grid, time);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world_sim_core::{BlockType, GridCoord, Position};
+
+    #[test]
+    fn test_agents_see_ahead_of_their_heading_and_not_through_walls() {
+        let grid = GridLayer::new();
+        grid.fill_box(GridCoord::new(5, 0, -2), GridCoord::new(5, 3, 2), BlockType::WallStone);
+        let at = |name: &str, x: f32, z: f32| SimAgent::new(name.to_string(), Position::new(x, 0.0, z));
+        let mut watcher = at("Watcher", 0.5, 0.5);
+        let (ahead, behind_wall, behind_back) = (at("Ahead", 10.0, 8.5), at("BehindWall", 10.0, 0.5), at("BehindBack", -10.0, 0.5));
+        let mut agents = AgentStore::new();
+        for agent in [&watcher, &ahead, &behind_wall, &behind_back] {
+            agents.insert(agent.clone());
+        }
+
+        // Someone who has not moved yet looks all around, walls still hide
+        let mut perception = AgentPerception::new();
+        perceive(&mut perception, &watcher, &agents, &[], &grid, 0);
+        assert!(perception.knows_agent(ahead.id) && perception.knows_agent(behind_back.id));
+        assert!(!perception.knows_agent(behind_wall.id) && !perception.knows_agent(watcher.id));
+
+        watcher.heading = Some(Position::new(1.0, 0.0, 0.0));
+        let mut perception = AgentPerception::new();
+        perceive(&mut perception, &watcher, &agents, &[], &grid, 1);
+        assert_eq!(perception.facing, watcher.heading);
+        assert!(perception.knows_agent(ahead.id));
+        assert!(!perception.knows_agent(behind_wall.id) && !perception.knows_agent(behind_back.id));
+    }
+}
@@ -0,0 +1,187 @@
+//! Chunk streaming: paging chunks in around activity and out where nothing happens, and
+//! generating the parts of the world nothing has come near before
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{info, warn};
+use world_sim_core::{ChunkCoord, Position};
+use world_sim_persistence::ChunkStore;
+use world_sim_world::{Chunk, GridLayer, TerrainGenerator, CHUNK_SIZE};
+
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// Keeps the chunk columns within `streaming.load_radius` of activity in the grid, loading them
+/// from the chunk store or generating those never seen before, and pages out columns nothing has
+/// come near for `streaming.unload_after` seconds - writing only chunks edited since they were
+/// loaded (or never stored)
+#[derive(Default)]
+pub struct StreamingSystem {
+    /// Sim time each chunk column last had activity within reach
+    last_active: BTreeMap<(i32, i32), f64>,
+}
+
+#[async_trait]
+impl System for StreamingSystem {
+    fn name(&self) -> &'static str {
+        "streaming"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(
+            &[Resource::Agents, Resource::Buildings, Resource::Markets, Resource::Objects, Resource::Lod],
+            &[Resource::Grid],
+        )
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["lod"]
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        let config = &world.config.streaming;
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let mut activity: Vec<Position> = world.lifecycle.agents().living().map(|a| a.position).collect();
+        activity.extend(world.buildings.read().get_all_buildings().iter().map(|b| b.position));
+        activity.extend(world.markets.read().get_all_markets().iter().map(|m| m.position));
+        activity.extend(world.objects.read().get_all().iter().map(|o| o.position));
+        activity.extend(world.lod.read().observers().iter().copied());
+
+        let radius = config.load_radius;
+        let active: BTreeSet<(i32, i32)> = activity
+            .iter()
+            .map(|p| p.to_grid_coord().to_chunk_coord(CHUNK_SIZE))
+            .flat_map(|c| (-radius..=radius).flat_map(move |dz| (-radius..=radius).map(move |dx| (c.x + dx, c.z + dz))))
+            .collect();
+        for column in &active {
+            self.last_active.insert(*column, tick.sim_time);
+        }
+
+        let paged_in = page_in(&world.grid, &world.chunk_store, &active)?;
+        let generated = generate_new(&world.grid, &world.chunk_store, &world.generator, &active);
+
+        // Page out what has been left alone long enough
+        let idle = |column: &(i32, i32)| {
+            self.last_active.get(column).is_none_or(|at| tick.sim_time - at >= config.unload_after)
+        };
+        let mut stale: Vec<ChunkCoord> = world.grid.get_loaded_chunks().into_iter().filter(|c| idle(&(c.x, c.z))).collect();
+        stale.sort_unstable();
+        let (written, unchanged) = page_out(&world.grid, &world.chunk_store, &stale)?;
+        self.last_active.retain(|column, at| tick.sim_time - *at < config.unload_after || active.contains(column));
+
+        if paged_in + generated + written + unchanged > 0 {
+            info!(
+                "🗺️ Streamed {} chunks in, generated {} and streamed {} out ({} written, {} unchanged); {} loaded, {} stored",
+                paged_in,
+                generated,
+                written + unchanged,
+                written,
+                unchanged,
+                world.grid.get_loaded_chunks().len(),
+                world.chunk_store.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Load the unloaded stored chunks of some chunk columns back into the grid, returning how many
+fn page_in(grid: &GridLayer, store: &ChunkStore, columns: &BTreeSet<(i32, i32)>) -> Result<usize> {
+    let mut paged_in = 0;
+    for (x, z) in columns {
+        for coord in store.column(*x, *z) {
+            if !grid.is_unloaded(coord) {
+                continue;
+            }
+            let Some(data) = store.get(coord)? else { continue };
+            match bincode::deserialize::<Chunk>(&data) {
+                Ok(chunk) => {
+                    grid.insert_chunk(chunk);
+                    paged_in += 1;
+                }
+                Err(e) => warn!("Stored chunk {:?} is unreadable, leaving it out: {}", coord, e),
+            }
+        }
+    }
+    Ok(paged_in)
+}
+
+/// Generate the chunk columns with no chunk in the grid or the store, returning how many chunks
+fn generate_new(grid: &GridLayer, store: &ChunkStore, generator: &TerrainGenerator, columns: &BTreeSet<(i32, i32)>) -> usize {
+    let known: BTreeSet<(i32, i32)> = grid.get_loaded_chunks().into_iter().map(|c| (c.x, c.z)).collect();
+    let mut generated = 0;
+    for (x, z) in columns {
+        if known.contains(&(*x, *z)) || !store.column(*x, *z).is_empty() {
+            continue;
+        }
+        for chunk in generator.column(*x, *z) {
+            grid.insert_chunk(chunk);
+            generated += 1;
+        }
+    }
+    generated
+}
+
+/// Unload chunks from the grid, storing those edited since they were loaded (or never stored);
+/// returns how many were written and how many were already stored as they are
+fn page_out(grid: &GridLayer, store: &ChunkStore, coords: &[ChunkCoord]) -> Result<(usize, usize)> {
+    let (mut written, mut unchanged) = (0, 0);
+    for coord in coords {
+        let Some((chunk, dirty)) = grid.unload_chunk(*coord) else { continue };
+        if dirty || !store.contains(*coord) {
+            store.put(*coord, &bincode::serialize(&chunk)?)?;
+            written += 1;
+        } else {
+            unchanged += 1;
+        }
+    }
+    Ok((written, unchanged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world_sim_core::{BlockType, GridCoord};
+
+    #[test]
+    fn test_chunks_page_out_and_back_in() {
+        let grid = GridLayer::new();
+        let store = ChunkStore::scratch().unwrap();
+        grid.generate_simple_terrain(GridCoord::new(0, 0, 0), GridCoord::new(40, 0, 8));
+        let coords = grid.get_loaded_chunks();
+        let (near, far) = ((0, 0), (1, 0));
+        let in_column = |column: (i32, i32)| -> Vec<ChunkCoord> {
+            let mut coords: Vec<ChunkCoord> = coords.iter().filter(|c| (c.x, c.z) == column).copied().collect();
+            coords.sort_unstable();
+            coords
+        };
+
+        // Generated chunks have never been stored, so all of them are written
+        assert_eq!(page_out(&grid, &store, &in_column(far)).unwrap(), (2, 0));
+        assert_eq!(grid.get_block(GridCoord::new(35, 0, 0)), BlockType::Air);
+        assert_eq!(grid.set_block(GridCoord::new(35, 1, 0), BlockType::Wood), None);
+        assert_eq!(page_in(&grid, &store, &BTreeSet::from([near])).unwrap(), 0);
+
+        assert_eq!(page_in(&grid, &store, &BTreeSet::from([near, far])).unwrap(), 2);
+        assert_eq!(grid.get_block(GridCoord::new(35, 0, 0)), BlockType::Grass);
+        assert_eq!(grid.get_block(GridCoord::new(35, -3, 0)), BlockType::Dirt);
+        assert_eq!(grid.set_block(GridCoord::new(35, 1, 0), BlockType::Wood), Some(BlockType::Air));
+
+        // Only the edited chunk is written again; both come back as they were left
+        assert_eq!(page_out(&grid, &store, &in_column(far)).unwrap(), (1, 1));
+        assert_eq!(page_in(&grid, &store, &BTreeSet::from([far])).unwrap(), 2);
+        assert_eq!(grid.get_block(GridCoord::new(35, 1, 0)), BlockType::Wood);
+        assert_eq!(grid.get_block(GridCoord::new(35, -3, 0)), BlockType::Dirt);
+        assert_eq!(page_out(&grid, &store, &in_column(far)).unwrap(), (0, 2));
+        assert_eq!(store.len(), 2);
+    }
+}
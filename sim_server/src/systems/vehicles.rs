@@ -0,0 +1,362 @@
+//! Carts and boats: parking them at markets and docks, moving them with their crews, and
+//! putting them to work hauling building materials and shipping goods between markets
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+use uuid::Uuid;
+use world_sim_agents::{Job, SocialClass};
+use world_sim_core::{AgentId, BlockType, GridCoord, Position};
+use world_sim_world::{stand_on, BuildingOwner, DynamicObject, GridLayer, ObjectKind};
+
+use super::harvesting::base_price;
+use super::{Access, Resource, System, Tick, TickRate, World};
+
+/// Builders step onto a free cart this close to them
+const BOARD_DISTANCE: f32 = 7.0;
+
+/// Columns from a market at which a cart stops to take on materials
+const MARKET_REACH: i32 = 3;
+
+/// Columns around a market searched for open ground to park carts on
+const PARKING_RADIUS: i32 = 4;
+
+/// Merchants this close to a dock's market crew its boats
+const CREW_RADIUS: f32 = 10.0;
+
+/// A boat this close to a dock is moored there
+const MOORING_DISTANCE: f32 = 2.0;
+
+/// Where a market's boats tie up: open water near it
+#[derive(Debug)]
+struct Dock {
+    market: Uuid,
+    /// Surface water block boats moor on
+    water: GridCoord,
+}
+
+/// Park public carts at every market and boats at every dock that water joins to another
+pub fn launch_vehicles(world: &World) {
+    let config = &world.config.vehicles;
+    let markets: Vec<(Uuid, Position)> = world.markets.read().get_all_markets().iter().map(|m| (m.id, m.position)).collect();
+    let docks = find_docks(world);
+    let mut objects = world.objects.write();
+
+    let mut carts = 0;
+    for (_, position) in &markets {
+        let Some(spot) = parking_spot(&world.grid, position) else { continue };
+        for _ in 0..config.carts_per_market {
+            objects.add(DynamicObject::new(ObjectKind::Cart, stand_on(spot), BuildingOwner::Public));
+            carts += 1;
+        }
+    }
+
+    let mut boats = 0;
+    for dock in &docks {
+        let mut probe = DynamicObject::new(ObjectKind::Boat, stand_on(dock.water), BuildingOwner::Public);
+        let connected = docks
+            .iter()
+            .filter(|other| other.market != dock.market)
+            .any(|other| probe.plan_route(&world.grid, other.water, 1));
+        if !connected {
+            continue;
+        }
+        for _ in 0..config.boats_per_dock {
+            objects.add(DynamicObject::new(ObjectKind::Boat, stand_on(dock.water), BuildingOwner::Public));
+            boats += 1;
+        }
+    }
+
+    info!("🛒 {} carts parked at {} markets", carts, markets.len());
+    info!("⛵ {} boats at {} docks", boats, docks.len());
+}
+
+/// Docks of the markets within `vehicles.dock_reach` of surface water, in market order
+fn find_docks(world: &World) -> Vec<Dock> {
+    let markets: Vec<(Uuid, Position)> = world.markets.read().get_all_markets().iter().map(|m| (m.id, m.position)).collect();
+    markets
+        .into_iter()
+        .filter_map(|(market, position)| {
+            let water = world.grid.nearest_block(position.to_grid_coord(), BlockType::Water, world.config.vehicles.dock_reach)?;
+            let (top, block) = surface_top(&world.grid, water.x, water.z)?;
+            (block == BlockType::Water).then_some(Dock { market, water: top })
+        })
+        .collect()
+}
+
+/// Top block of a column and what it is
+fn surface_top(grid: &GridLayer, x: i32, z: i32) -> Option<(GridCoord, BlockType)> {
+    let coord = GridCoord::new(x, grid.surface_height(x, z)?, z);
+    Some((coord, grid.get_block(coord)))
+}
+
+/// Closest surface block to `position` a cart can stand on
+fn parking_spot(grid: &GridLayer, position: &Position) -> Option<GridCoord> {
+    let center = position.to_grid_coord();
+    (0..=PARKING_RADIUS).find_map(|ring| {
+        (-ring..=ring)
+            .flat_map(|dz| (-ring..=ring).map(move |dx| (dx, dz)))
+            .filter(|(dx, dz)| dx.abs().max(dz.abs()) == ring)
+            .filter_map(|(dx, dz)| surface_top(grid, center.x + dx, center.z + dz))
+            .find(|(_, block)| matches!(block, BlockType::Grass | BlockType::Dirt | BlockType::Stone))
+            .map(|(coord, _)| coord)
+    })
+}
+
+/// Moves every cart, boat and siege engine along its route and takes its crew with it
+pub struct VehicleSystem;
+
+#[async_trait]
+impl System for VehicleSystem {
+    fn name(&self) -> &'static str {
+        "vehicles"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Fast
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[], &[Resource::Objects, Resource::Agents])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["movement"]
+    }
+
+    async fn run(&mut self, world: &World, tick: &Tick) -> Result<()> {
+        let riders: Vec<(AgentId, Position)> = {
+            let mut objects = world.objects.write();
+            for object in objects.get_all_mut() {
+                object.step(tick.delta_seconds as f32);
+            }
+            objects
+                .get_all()
+                .iter()
+                .flat_map(|o| o.crew.iter().map(|id| (*id, o.position)))
+                .collect()
+        };
+
+        world.lifecycle.apply_updates(riders, |agent, at| {
+            agent.position.x = at.x;
+            agent.position.z = at.z;
+        });
+
+        Ok(())
+    }
+}
+
+/// Puts carts and boats to work.
+///
+/// Builders take a cart parked near them while they have a site to supply: it adds its capacity
+/// to what they can haul, and they drive it to the market to load and then to the site to unload.
+/// Boats wait at docks until merchants crew them, then carry goods from their market to the
+/// dock whose market pays more for them.
+#[derive(Default)]
+pub struct HaulageSystem {
+    /// Builders whose cart found no way to where they were going, with the site they were supplying
+    /// (they go on foot until they are sent to another)
+    stranded: BTreeMap<AgentId, Uuid>,
+    /// Found on the first run
+    docks: Option<Vec<Dock>>,
+}
+
+/// A builder with a site to supply
+struct Haul {
+    id: AgentId,
+    position: Position,
+    site: Uuid,
+    /// Carrying materials for the site
+    laden: bool,
+}
+
+#[async_trait]
+impl System for HaulageSystem {
+    fn name(&self) -> &'static str {
+        "haulage"
+    }
+
+    fn rate(&self) -> TickRate {
+        TickRate::Slow
+    }
+
+    fn access(&self) -> Access {
+        Access::new(&[Resource::Grid, Resource::Buildings, Resource::Agents], &[Resource::Objects, Resource::Markets])
+    }
+
+    fn after(&self) -> &'static [&'static str] {
+        &["builder_assignment"]
+    }
+
+    async fn run(&mut self, world: &World, _tick: &Tick) -> Result<()> {
+        self.drive_carts(world);
+        self.sail_boats(world);
+        Ok(())
+    }
+}
+
+impl HaulageSystem {
+    fn drive_carts(&mut self, world: &World) {
+        let hauls: Vec<Haul> = world
+            .lifecycle
+            .agents()
+            .iter()
+            .filter(|a| a.is_alive() && matches!(a.job, Job::Builder))
+            .filter_map(|a| {
+                let carrying = a.carrying_resources.as_ref()?;
+                Some(Haul {
+                    id: a.id,
+                    position: a.position,
+                    site: carrying.target_building_id,
+                    laden: carrying.wood + carrying.stone + carrying.iron > 0,
+                })
+            })
+            .collect();
+        let sites: HashMap<Uuid, (Position, i32)> = world
+            .buildings
+            .read()
+            .get_all_buildings()
+            .iter()
+            .map(|b| (b.id, (b.position, b.building_type.approach() + 1)))
+            .collect();
+
+        self.stranded.retain(|id, site| hauls.iter().any(|h| h.id == *id && h.site == *site));
+        let hauling: HashMap<AgentId, &Haul> = hauls.iter().map(|h| (h.id, h)).collect();
+
+        let mut objects = world.objects.write();
+
+        // Drivers who delivered, died or were stood down leave their cart where it stands
+        for cart in objects.get_all_mut().filter(|o| o.kind == ObjectKind::Cart) {
+            if cart.crew.first().is_some_and(|driver| !hauling.contains_key(driver)) {
+                cart.crew.clear();
+                cart.route.clear();
+            }
+        }
+
+        for haul in &hauls {
+            if self.stranded.contains_key(&haul.id) || objects.aboard(haul.id).is_some() {
+                continue;
+            }
+            if let Some(cart) = objects.nearest_free(&haul.position, ObjectKind::Cart, BOARD_DISTANCE).map(|o| o.id) {
+                if let Some(cart) = objects.get_mut(cart) {
+                    cart.crew.push(haul.id);
+                }
+            }
+        }
+
+        // Empty carts head to the nearest market to load, laden ones to the site
+        let markets = world.markets.read();
+        for cart in objects.get_all_mut().filter(|o| o.kind == ObjectKind::Cart && o.has_arrived()) {
+            let Some(haul) = cart.crew.first().and_then(|driver| hauling.get(driver)) else { continue };
+            let (goal, reach) = if haul.laden || cart.load() > 0 {
+                match sites.get(&haul.site) {
+                    Some(site) => *site,
+                    None => continue,
+                }
+            } else {
+                match markets.find_nearest_market(&cart.position, None) {
+                    Some(market) => (market.position, MARKET_REACH),
+                    None => continue,
+                }
+            };
+
+            let (here, goal) = (cart.position.to_grid_coord(), goal.to_grid_coord());
+            if (here.x - goal.x).abs() + (here.z - goal.z).abs() <= reach {
+                continue;
+            }
+            if !cart.plan_route(&world.grid, goal, reach) {
+                info!("🛒 No road for a cart to ({}, {}); its driver goes on foot", goal.x, goal.z);
+                cart.crew.clear();
+                self.stranded.insert(haul.id, haul.site);
+            }
+        }
+    }
+
+    fn sail_boats(&mut self, world: &World) {
+        let docks = &*self.docks.get_or_insert_with(|| find_docks(world));
+        if docks.len() < 2 {
+            return;
+        }
+
+        let sailors: Vec<(AgentId, Position)> = world
+            .lifecycle
+            .agents()
+            .iter()
+            .filter(|a| a.is_alive() && matches!(a.social_class, SocialClass::Merchant | SocialClass::Burgher))
+            .map(|a| (a.id, a.position))
+            .collect();
+
+        let mut objects = world.objects.write();
+        let mut aboard: HashSet<AgentId> = objects.get_all().iter().flat_map(|o| o.crew.iter().copied()).collect();
+        let mut markets = world.markets.write();
+
+        for boat in objects.get_all_mut().filter(|o| o.kind == ObjectKind::Boat && o.has_arrived()) {
+            let Some(dock) = docks
+                .iter()
+                .find(|d| stand_on(d.water).horizontal_distance_to(&boat.position) <= MOORING_DISTANCE)
+            else {
+                continue;
+            };
+
+            // Journey's end: goods ashore and the crew off
+            if !boat.crew.is_empty() || boat.load() > 0 {
+                if let Some(market) = markets.get_market_mut(dock.market) {
+                    for (resource, quantity) in std::mem::take(&mut boat.cargo) {
+                        market.add_inventory(resource, quantity, base_price(resource));
+                        info!("⛵ Boat landed {} {:?} at {}", quantity, resource, market.name);
+                    }
+                }
+                for sailor in boat.crew.drain(..) {
+                    aboard.remove(&sailor);
+                }
+                continue;
+            }
+
+            let Some(home) = markets.get_market(dock.market) else { continue };
+            let crew: Vec<AgentId> = sailors
+                .iter()
+                .filter(|(id, position)| !aboard.contains(id) && position.horizontal_distance_to(&home.position) <= CREW_RADIUS)
+                .map(|(id, _)| *id)
+                .take(ObjectKind::Boat.crew())
+                .collect();
+            if crew.len() < ObjectKind::Boat.crew() {
+                continue;
+            }
+
+            // Cargoes worth shipping, most profitable first
+            let mut cargoes = Vec::new();
+            for (to, destination) in docks.iter().enumerate().filter(|(_, d)| d.market != dock.market) {
+                let Some(destination) = markets.get_market(destination.market) else { continue };
+                let mut goods: Vec<_> = home.inventory.values().collect();
+                goods.sort_by_key(|g| g.resource_type);
+                for good in goods {
+                    let Some(wanted) = destination.inventory.get(&good.resource_type) else { continue };
+                    let quantity = (good.quantity / 2).min(boat.free_capacity());
+                    let profit = (wanted.current_price - good.current_price) * quantity as f64;
+                    if profit > 0.0 {
+                        cargoes.push((profit, to, good.resource_type, quantity));
+                    }
+                }
+            }
+            cargoes.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+            for (_, to, resource, quantity) in cargoes {
+                if !boat.plan_route(&world.grid, docks[to].water, 1) {
+                    continue;
+                }
+                if let Some(market) = markets.get_market_mut(dock.market) {
+                    if market.remove_inventory(resource, quantity) {
+                        boat.load_cargo(resource, quantity);
+                        aboard.extend(crew.iter().copied());
+                        boat.crew = crew;
+                        info!("⛵ Boat sails from {} with {} {:?}", market.name, quantity, resource);
+                        break;
+                    }
+                }
+                boat.route.clear();
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Immutable per-tick world view for agent phases
+//!
+//! Agent phases never mutate agents while holding another world lock. They capture a
+//! [`WorldView`] (each lock taken and released on its own, the agents read lock last and
+//! held by the view), decide one intent per agent in parallel against it, drop it, then
+//! commit the intents in agent order with `LifecycleLayer::apply_updates` - touching one
+//! lock at a time.
+
+use parking_lot::RwLockReadGuard;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+use world_sim_agents::{AgentStore, SimAgent};
+use world_sim_core::{sim_rng, stream_rng, AgentId, Position, SpatialIndex};
+use world_sim_world::{BuildingType, ResourceNode, ResourceNodeType};
+
+use super::{FireFront, Tick, World};
+
+/// Where a market is
+#[derive(Debug, Clone)]
+pub struct MarketSite {
+    pub id: Uuid,
+    pub position: Position,
+}
+
+/// Where a building is and what it is
+#[derive(Debug, Clone)]
+pub struct BuildingSite {
+    pub position: Position,
+    pub building_type: BuildingType,
+}
+
+/// Snapshot of the world as agents see it at the start of a phase
+///
+/// Agents are borrowed rather than copied: the view holds the agents read lock, so drop it
+/// before taking any other world lock.
+pub struct WorldView<'a> {
+    /// Every agent not yet archived, with its id and spatial indexes
+    pub agents: RwLockReadGuard<'a, AgentStore>,
+    pub nodes: Vec<ResourceNode>,
+    /// Node positions, keyed by place in `nodes`
+    node_index: SpatialIndex<usize>,
+    pub markets: HashMap<Uuid, MarketSite>,
+    market_index: SpatialIndex<Uuid>,
+    pub buildings: HashMap<Uuid, BuildingSite>,
+    pub fires: FireFront,
+    /// Agents left out of planning this tick (see `LodTable::skipped`)
+    skipped: HashSet<AgentId>,
+}
+
+impl<'a> WorldView<'a> {
+    pub fn capture(world: &'a World, tick: &Tick) -> Self {
+        let (nodes, node_index) = world.resources.get_nodes_indexed();
+        let (markets, market_index) = {
+            let markets = world.markets.read();
+            let sites = markets
+                .get_all_markets()
+                .into_iter()
+                .map(|m| (m.id, MarketSite { id: m.id, position: m.position }))
+                .collect();
+            (sites, markets.spatial_index().clone())
+        };
+        let buildings = world
+            .buildings
+            .read()
+            .get_all_buildings()
+            .into_iter()
+            .map(|b| (b.id, BuildingSite { position: b.position, building_type: b.building_type }))
+            .collect();
+
+        let fires = world.fires.read().clone();
+
+        let skipped = if world.config.lod.enabled {
+            world.lod.read().skipped(tick.number, world.config.lod.reduced_every)
+        } else {
+            HashSet::new()
+        };
+        let agents = world.lifecycle.agents();
+
+        Self { agents, nodes, node_index, markets, market_index, buildings, fires, skipped }
+    }
+
+    /// Nearest node of a type that still has something to harvest
+    pub fn nearest_node(&self, position: Position, resource_type: ResourceNodeType) -> Option<&ResourceNode> {
+        self.nearest_node_of(position, &[resource_type])
+    }
+
+    /// Nearest node of any of the given types that still has something to harvest
+    pub fn nearest_node_of(&self, position: Position, resource_types: &[ResourceNodeType]) -> Option<&ResourceNode> {
+        self.node_index
+            .nearest(&position, |i| {
+                let node = &self.nodes[i];
+                resource_types.contains(&node.resource_type) && node.quantity > 0
+            })
+            .map(|(i, _)| &self.nodes[i])
+    }
+
+    pub fn nearest_market(&self, position: &Position) -> Option<&MarketSite> {
+        self.market_index
+            .nearest(position, |_| true)
+            .map(|(id, _)| &self.markets[&id])
+    }
+
+    pub fn building(&self, id: Uuid) -> Option<&BuildingSite> {
+        self.buildings.get(&id)
+    }
+
+    /// Decide an intent for every living agent not skipped this tick, in parallel (results are in agent order)
+    pub fn plan<I, F>(&self, decide: F) -> Vec<(AgentId, I)>
+    where
+        I: Send,
+        F: Fn(&SimAgent) -> Option<I> + Sync,
+    {
+        self.agents
+            .as_slice()
+            .par_iter()
+            .filter(|agent| agent.is_alive() && !self.skipped.contains(&agent.id))
+            .filter_map(|agent| decide(agent).map(|intent| (agent.id, intent)))
+            .collect()
+    }
+
+    /// Like [`plan`](Self::plan), with a random stream per agent seeded from the simulation RNG
+    pub fn plan_with_rng<I, F>(&self, decide: F) -> Vec<(AgentId, I)>
+    where
+        I: Send,
+        F: Fn(&SimAgent, &mut StdRng) -> Option<I> + Sync,
+    {
+        // Drawn here, on the simulation thread - workers have their own (unseeded) RNGs
+        let seed: u64 = sim_rng().gen();
+        self.plan_with_seed(seed, decide)
+    }
+
+    fn plan_with_seed<I, F>(&self, seed: u64, decide: F) -> Vec<(AgentId, I)>
+    where
+        I: Send,
+        F: Fn(&SimAgent, &mut StdRng) -> Option<I> + Sync,
+    {
+        self.plan(|agent| {
+            let (high, low) = agent.id.0.as_u64_pair();
+            decide(agent, &mut stream_rng(seed, high ^ low))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::RwLock;
+    use world_sim_agents::SocialClass;
+
+    #[test]
+    fn test_plan_is_independent_of_threads() {
+        let agents: RwLock<AgentStore> = RwLock::new(
+            (0..200)
+                .map(|i| SimAgent::new_with_class(format!("Peasant_{}", i), Position::new(i as f32, 1.0, 0.0), SocialClass::Peasant))
+                .collect(),
+        );
+        let view = WorldView {
+            agents: agents.read(),
+            nodes: Vec::new(),
+            node_index: SpatialIndex::new(16.0),
+            markets: HashMap::new(),
+            market_index: SpatialIndex::new(32.0),
+            buildings: HashMap::new(),
+            fires: FireFront::default(),
+            skipped: HashSet::new(),
+        };
+        let roll = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            pool.install(|| view.plan_with_seed(9, |agent, rng| Some(agent.position.x + rng.gen::<f32>())))
+        };
+
+        let serial = roll(1);
+        assert_eq!(serial.len(), 200);
+        assert_eq!(serial, roll(4));
+        assert_eq!(serial[0].0, view.agents.as_slice()[0].id);
+    }
+}
@@ -0,0 +1,364 @@
+/// simctl - command-line control for a running simulation via the Admin API
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use comfy_table::{presets::UTF8_FULL_CONDENSED, Table};
+use serde::Serialize;
+use uuid::Uuid;
+use world_sim_admin_client::*;
+use world_sim_core::Position;
+
+#[derive(Parser)]
+#[command(name = "simctl", version, about = "Inspect and control a running World Sim server")]
+struct Cli {
+    /// Admin API base URL
+    #[arg(long, env = "SIMCTL_URL", default_value = "http://127.0.0.1:8080", global = true)]
+    url: String,
+
+    /// Print raw JSON instead of tables (one object per line for `events tail`)
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show simulation metrics
+    Metrics,
+    /// List or inspect agents
+    #[command(subcommand)]
+    Agents(AgentsCommand),
+    /// Follow the live event stream
+    #[command(subcommand)]
+    Events(EventsCommand),
+    /// Dungeon Master story events
+    #[command(subcommand)]
+    Dm(DmCommand),
+    /// Pause the simulation loop
+    Pause,
+    /// Resume the simulation loop
+    Resume,
+    /// Set observer positions for agent level of detail (none clears them)
+    Focus {
+        /// Points as `x,z`, e.g. `focus 0,0 120,-40`
+        #[arg(value_parser = parse_point, allow_negative_numbers = true)]
+        points: Vec<Position>,
+    },
+    /// Create, list and restore world snapshots
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+    /// Show market inventories and order books
+    Markets,
+}
+
+#[derive(Subcommand)]
+enum AgentsCommand {
+    /// List all agents
+    List {
+        /// Only show agents in this state (e.g. "Working")
+        #[arg(long)]
+        state: Option<String>,
+    },
+    /// Show a single agent
+    Show { id: String },
+}
+
+#[derive(Subcommand)]
+enum EventsCommand {
+    /// Print events as they are published (Ctrl+C to stop)
+    Tail {
+        /// Only show events of this type
+        #[arg(long = "type")]
+        event_type: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DmCommand {
+    /// List story events and their cooldowns
+    Events,
+    /// Trigger a story event immediately
+    Trigger {
+        id: String,
+        /// Event center (requires --y and --z as well)
+        #[arg(long, requires_all = ["y", "z"])]
+        x: Option<f32>,
+        #[arg(long, requires_all = ["x", "z"])]
+        y: Option<f32>,
+        #[arg(long, requires_all = ["x", "y"])]
+        z: Option<f32>,
+        #[arg(long)]
+        radius: Option<f32>,
+        #[arg(long)]
+        severity: Option<f32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommand {
+    /// Save the current world
+    Create {
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List saved snapshots
+    List,
+    /// Replace the current world with a saved snapshot
+    Restore { id: Uuid },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let client = AdminClient::new(&cli.url);
+
+    match cli.command {
+        Command::Metrics => {
+            let metrics = client.metrics().await.context("Failed to fetch metrics")?;
+            print(cli.json, &metrics, |m| {
+                let mut table = table(&["Metric", "Value"]);
+                table.add_row(vec!["Status".to_string(), if m.paused { "Paused" } else { "Running" }.to_string()]);
+                table.add_row(vec!["Uptime (s)".to_string(), m.uptime_seconds.to_string()]);
+                table.add_row(vec!["Agents".to_string(), m.agent_count.to_string()]);
+                table.add_row(vec!["Events processed".to_string(), m.events_processed.to_string()]);
+                println!("{table}");
+            })?;
+        }
+        Command::Agents(AgentsCommand::List { state }) => {
+            let mut response = client.agents().await.context("Failed to fetch agents")?;
+            if let Some(state) = state {
+                response.agents.retain(|a| a.state.eq_ignore_ascii_case(&state));
+            }
+            print(cli.json, &response, |r| {
+                let mut table = table(&["ID", "Name", "Class", "State", "Faction", "Wallet", "Position"]);
+                for agent in &r.agents {
+                    table.add_row(vec![
+                        agent.id.clone(),
+                        agent.name.clone(),
+                        agent.social_class.clone(),
+                        agent.state.clone(),
+                        agent.faction.clone().unwrap_or_else(|| "-".to_string()),
+                        format!("{:.1}", agent.wallet),
+                        format!("({:.0}, {:.0})", agent.x, agent.z),
+                    ]);
+                }
+                println!("{table}");
+                println!("{} agents", r.agents.len());
+            })?;
+        }
+        Command::Agents(AgentsCommand::Show { id }) => {
+            let agent = client.agent(&id).await.with_context(|| format!("Failed to fetch agent {}", id))?;
+            print(cli.json, &agent, |a| {
+                let mut table = table(&["Field", "Value"]);
+                let rows = [
+                    ("ID", a.id.clone()),
+                    ("Name", a.name.clone()),
+                    ("Class", a.social_class.clone()),
+                    ("State", a.state.clone()),
+                    ("Faction", a.faction.clone().unwrap_or_else(|| "-".to_string())),
+                    ("Leader", a.leader_id.clone().unwrap_or_else(|| "-".to_string())),
+                    ("Position", format!("({:.1}, {:.1}, {:.1})", a.x, a.y, a.z)),
+                    ("Wallet", format!("{:.2}", a.wallet)),
+                    (
+                        "Inventory",
+                        format!(
+                            "wood {} / stone {} / food {} / iron {}",
+                            a.inventory_wood, a.inventory_stone, a.inventory_food, a.inventory_iron
+                        ),
+                    ),
+                    (
+                        "Carrying",
+                        format!("wood {} / stone {} / iron {}", a.carrying_wood, a.carrying_stone, a.carrying_iron),
+                    ),
+                    ("Target building", a.target_building_id.clone().unwrap_or_else(|| "-".to_string())),
+                ];
+                for (field, value) in rows {
+                    table.add_row(vec![field.to_string(), value]);
+                }
+                println!("{table}");
+            })?;
+        }
+        Command::Events(EventsCommand::Tail { event_type }) => {
+            let mut stream = client
+                .stream_events(event_type.as_deref())
+                .await
+                .context("Failed to open event stream")?;
+            while let Some(event) = stream.next().await? {
+                if cli.json {
+                    println!("{}", serde_json::to_string(&event)?);
+                } else {
+                    println!(
+                        "{} [{}] {} {}",
+                        event.timestamp.format("%H:%M:%S%.3f"),
+                        event.source,
+                        event.event_type,
+                        event.payload
+                    );
+                }
+            }
+        }
+        Command::Dm(DmCommand::Events) => {
+            let response = client.dm_events().await.context("Failed to fetch story events")?;
+            print(cli.json, &response, |r| {
+                let mut table = table(&["ID", "Name", "Impact", "Cooldown (s)", "Ready in (s)"]);
+                for status in &r.events {
+                    table.add_row(vec![
+                        status.event.id.clone(),
+                        status.event.name.clone(),
+                        format!("{:?}", status.event.impact),
+                        format!("{:.0}", status.event.cooldown),
+                        format!("{:.0}", status.cooldown_remaining),
+                    ]);
+                }
+                println!("{table}");
+            })?;
+        }
+        Command::Dm(DmCommand::Trigger { id, x, y, z, radius, severity }) => {
+            let center = match (x, y, z) {
+                (Some(x), Some(y), Some(z)) => Some(Position::new(x, y, z)),
+                _ => None,
+            };
+            let request = TriggerDmEventRequest { center, radius, severity };
+            let response = client
+                .trigger_dm_event(&id, &request)
+                .await
+                .with_context(|| format!("Failed to trigger story event {}", id))?;
+            print(cli.json, &response, |r| {
+                println!("Triggered '{}' ({})", r.event.name, r.event.id);
+            })?;
+        }
+        Command::Pause => {
+            let response = client.pause().await.context("Failed to pause simulation")?;
+            print(cli.json, &response, |_| println!("Simulation paused"))?;
+        }
+        Command::Resume => {
+            let response = client.resume().await.context("Failed to resume simulation")?;
+            print(cli.json, &response, |_| println!("Simulation resumed"))?;
+        }
+        Command::Focus { points } => {
+            let request = SetFocusRequest { observers: points };
+            let response = client.set_focus(&request).await.context("Failed to set observers")?;
+            print(cli.json, &response, |r| println!("Observers set: {}", r.observers))?;
+        }
+        Command::Snapshot(SnapshotCommand::Create { name }) => {
+            let response = client
+                .create_snapshot(name.as_deref())
+                .await
+                .context("Failed to create snapshot")?;
+            print(cli.json, &response, |r| match r.snapshot_id {
+                Some(id) => println!("Snapshot created: {}", id),
+                None => println!("{}", r.message),
+            })?;
+        }
+        Command::Snapshot(SnapshotCommand::List) => {
+            let response = client.list_snapshots().await.context("Failed to list snapshots")?;
+            print(cli.json, &response, |r| {
+                let mut table = table(&["ID", "Name", "Created"]);
+                for (id, name, created_at) in &r.snapshots {
+                    table.add_row(vec![
+                        id.to_string(),
+                        name.clone(),
+                        created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    ]);
+                }
+                println!("{table}");
+            })?;
+        }
+        Command::Snapshot(SnapshotCommand::Restore { id }) => {
+            let response = client
+                .restore_snapshot(id)
+                .await
+                .with_context(|| format!("Failed to restore snapshot {}", id))?;
+            print(cli.json, &response, |r| println!("Snapshot restored: {}", r.snapshot_id))?;
+        }
+        Command::Markets => {
+            let response = client.markets().await.context("Failed to fetch markets")?;
+            print(cli.json, &response, print_markets)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse an `x,z` ground position
+fn parse_point(raw: &str) -> std::result::Result<Position, String> {
+    let (x, z) = raw.split_once(',').ok_or("expected x,z")?;
+    let x = x.trim().parse::<f32>().map_err(|e| e.to_string())?;
+    let z = z.trim().parse::<f32>().map_err(|e| e.to_string())?;
+    Ok(Position::new(x, 0.0, z))
+}
+
+/// Print `value` as pretty JSON, or with the given table renderer
+fn print<T: Serialize>(json: bool, value: &T, render: impl FnOnce(&T)) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        render(value);
+    }
+    Ok(())
+}
+
+fn table(header: &[&str]) -> Table {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED).set_header(header.to_vec());
+    table
+}
+
+/// Inventory plus buy/sell order book for each market
+fn print_markets(response: &MarketsResponse) {
+    for market in &response.markets {
+        println!(
+            "🏪 {} ({:?}) - {} transactions, reputation {:.2}",
+            market.name, market.market_type, market.transaction_count, market.reputation
+        );
+
+        let mut goods: Vec<_> = market.inventory.values().collect();
+        goods.sort_by_key(|good| good.resource_type.to_string());
+        let mut inventory = table(&["Resource", "Quantity", "Base price", "Price"]);
+        for good in goods {
+            inventory.add_row(vec![
+                good.resource_type.to_string(),
+                good.quantity.to_string(),
+                format!("{:.2}", good.base_price),
+                format!("{:.2}", good.current_price),
+            ]);
+        }
+        println!("{inventory}");
+
+        let mut book = table(&["Side", "Resource", "Quantity", "Price/unit", "Agent"]);
+        let mut bids: Vec<_> = market.buy_orders.iter().collect();
+        bids.sort_by(|a, b| b.price_per_unit.total_cmp(&a.price_per_unit));
+        let mut asks: Vec<_> = market.sell_orders.iter().collect();
+        asks.sort_by(|a, b| a.price_per_unit.total_cmp(&b.price_per_unit));
+        for (side, orders) in [("BUY", bids), ("SELL", asks)] {
+            for order in orders {
+                book.add_row(vec![
+                    side.to_string(),
+                    order.resource.to_string(),
+                    order.quantity.to_string(),
+                    format!("{:.2}", order.price_per_unit),
+                    order.agent_id.0.to_string(),
+                ]);
+            }
+        }
+        println!("{book}");
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["simctl", "--json", "dm", "trigger", "wood_blight", "--radius", "20"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(cli.command, Command::Dm(DmCommand::Trigger { radius: Some(r), .. }) if r == 20.0));
+        assert!(Cli::try_parse_from(["simctl", "dm", "trigger", "wood_blight", "--x", "1"]).is_err());
+    }
+}